@@ -0,0 +1,381 @@
+use crate::commands::process::{emit_progress, run_sudo, spawn_sudo, stream_child};
+use crate::commands::storage::PrivateDir;
+use crate::models::ArchiveVersion;
+use crate::parsers::{is_valid_package_name, parse_archive_listing, parse_package_filename};
+use std::io::Write;
+use std::path::Path;
+
+const DEFAULT_ARCHIVE_URL: &str = "https://archive.archlinux.org";
+const PACMAN_CACHE_DIR: &str = "/var/cache/pacman/pkg";
+const PROGRESS_EVENT: &str = "install-progress";
+
+// Base URL of the Arch Linux Archive, overridable with ARCHSTORE_ARCHIVE_URL (e.g. a local fixture server)
+fn archive_base_url() -> String {
+    std::env::var("ARCHSTORE_ARCHIVE_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| DEFAULT_ARCHIVE_URL.to_string())
+}
+
+// The archive groups packages by their first letter: /packages/f/firefox/
+fn package_dir_url(base_url: &str, package_name: &str) -> String {
+    let first = package_name
+        .chars()
+        .next()
+        .unwrap_or('_')
+        .to_ascii_lowercase();
+    format!("{}/packages/{}/{}/", base_url, first, package_name)
+}
+
+fn validate_package_name(package_name: &str) -> Result<(), String> {
+    if !is_valid_package_name(package_name) {
+        return Err(format!("Invalid package name: {}", package_name));
+    }
+    Ok(())
+}
+
+// List the historical versions of an official package available in the Arch Linux Archive
+#[tauri::command]
+pub async fn list_archive_versions(package_name: String) -> Result<Vec<ArchiveVersion>, String> {
+    validate_package_name(&package_name)?;
+    fetch_archive_versions(&archive_base_url(), &package_name).await
+}
+
+async fn fetch_archive_versions(
+    base_url: &str,
+    package_name: &str,
+) -> Result<Vec<ArchiveVersion>, String> {
+    let url = package_dir_url(base_url, package_name);
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Failed to reach the Arch Linux Archive: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "Arch Linux Archive returned {} for {}",
+            response.status(),
+            package_name
+        ));
+    }
+
+    let html = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read archive listing: {}", e))?;
+
+    let mut versions = parse_archive_listing(&html, package_name);
+    for version in &mut versions {
+        version.url = format!("{}{}", url, version.filename);
+        version.cached = Path::new(PACMAN_CACHE_DIR).join(&version.filename).exists();
+    }
+
+    Ok(versions)
+}
+
+// Downgrade (or reinstall) a package to a specific archived version
+#[tauri::command]
+pub async fn downgrade_package(
+    package_name: String,
+    filename: String,
+    password: String,
+    window: tauri::Window,
+) -> Result<(), String> {
+    validate_package_name(&package_name)?;
+
+    // Only accept plain package file names for this package, never paths
+    let matches_package = parse_package_filename(&filename)
+        .map(|(name, _, _)| name == package_name)
+        .unwrap_or(false);
+    if filename.contains('/') || filename.ends_with(".sig") || !matches_package {
+        return Err(format!(
+            "{} is not a package file for {}",
+            filename, package_name
+        ));
+    }
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        10,
+        format!("Preparing to install {}...", filename),
+        false,
+    );
+
+    let cached_path = Path::new(PACMAN_CACHE_DIR).join(&filename);
+    if cached_path.exists() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            60,
+            format!("Using {} from the package cache", filename),
+            false,
+        );
+    } else if let Err(e) = download_to_cache(&package_name, &filename, &password, &window).await {
+        emit_progress(&window, PROGRESS_EVENT, 0, e.clone(), true);
+        return Err(e);
+    }
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        70,
+        format!("Installing {}...", filename),
+        false,
+    );
+
+    let cached_path = cached_path.to_string_lossy().to_string();
+    let child = spawn_sudo(&password, &["pacman", "-U", "--noconfirm", &cached_path])?;
+    let output = stream_child(child, &window, PROGRESS_EVENT, 80)?;
+
+    if !output.status.success() {
+        let error = output.error_message();
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            0,
+            format!("Downgrade failed: {}", error),
+            true,
+        );
+        return Err(format!("Downgrade failed: {}", error));
+    }
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        100,
+        format!("{} installed successfully!", filename),
+        true,
+    );
+    Ok(())
+}
+
+// Download a package and its signature, verify it, then move both into the pacman cache
+async fn download_to_cache(
+    package_name: &str,
+    filename: &str,
+    password: &str,
+    window: &tauri::Window,
+) -> Result<(), String> {
+    // Removed with its contents when it goes out of scope
+    let staging_dir = PrivateDir::create("archive")?;
+
+    let package_path = staging_dir.path().join(filename);
+    let signature_path = staging_dir.path().join(format!("{}.sig", filename));
+    let base_url = package_dir_url(&archive_base_url(), package_name);
+
+    async {
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            20,
+            format!("Downloading {} from the Arch Linux Archive...", filename),
+            false,
+        );
+        download_file(&format!("{}{}", base_url, filename), &package_path, window).await?;
+
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            50,
+            "Downloading signature...".to_string(),
+            false,
+        );
+        download_file(
+            &format!("{}{}.sig", base_url, filename),
+            &signature_path,
+            window,
+        )
+        .await
+        .map_err(|e| format!("No signature available for {}: {}", filename, e))?;
+
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            55,
+            "Verifying package signature...".to_string(),
+            false,
+        );
+        let signature = signature_path.to_string_lossy().to_string();
+        let verify = run_sudo(password, &["pacman-key", "--verify", &signature])?;
+        if !verify.status.success() {
+            return Err(format!(
+                "Signature verification failed for {}: {}",
+                filename,
+                String::from_utf8_lossy(&verify.stderr).trim()
+            ));
+        }
+
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            60,
+            "Signature verified, copying to package cache...".to_string(),
+            false,
+        );
+        let package = package_path.to_string_lossy().to_string();
+        let copy = run_sudo(
+            password,
+            &[
+                "install",
+                "-m",
+                "644",
+                "-t",
+                PACMAN_CACHE_DIR,
+                &package,
+                &signature,
+            ],
+        )?;
+        if !copy.status.success() {
+            return Err(format!(
+                "Failed to copy {} into the package cache: {}",
+                filename,
+                String::from_utf8_lossy(&copy.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+    .await
+}
+
+// Download a URL to a file, reporting progress in 10% steps
+async fn download_file(url: &str, dest: &Path, window: &tauri::Window) -> Result<(), String> {
+    let mut response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+
+    let total = response.content_length().unwrap_or(0);
+    let mut file =
+        std::fs::File::create(dest).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut downloaded: u64 = 0;
+    let mut last_step = 0;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download interrupted: {}", e))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        downloaded += chunk.len() as u64;
+
+        // Unknown content length means no percentage, just the final result
+        let step = (downloaded * 10).checked_div(total).unwrap_or(0);
+        if step > last_step {
+            last_step = step;
+            emit_progress(
+                window,
+                PROGRESS_EVENT,
+                20 + (step as u32) * 3,
+                format!(
+                    "Downloaded {:.1} of {:.1} MiB",
+                    downloaded as f64 / 1024.0 / 1024.0,
+                    total as f64 / 1024.0 / 1024.0
+                ),
+                false,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    const LISTING: &str = r#"<html><head><title>Index of /packages/f/foo/</title></head><body>
+<h1>Index of /packages/f/foo/</h1><hr><pre><a href="../">../</a>
+<a href="foo-1.0-1-x86_64.pkg.tar.zst">foo-1.0-1-x86_64.pkg.tar.zst</a>                       10-Feb-2022 12:30             1048576
+<a href="foo-1.0-1-x86_64.pkg.tar.zst.sig">foo-1.0-1-x86_64.pkg.tar.zst.sig</a>                   10-Feb-2022 12:30                 566
+<a href="foo-1%3A0.9-2-x86_64.pkg.tar.zst">foo-1:0.9-2-x86_64.pkg.tar.zst</a>                     11-Mar-2023 08:00             2097152
+<a href="foo-bar-2.0-1-any.pkg.tar.zst">foo-bar-2.0-1-any.pkg.tar.zst</a>                     11-Mar-2023 08:00                1024
+</pre><hr></body></html>
+"#;
+
+    // Serve one canned response per connection, recording the requested paths
+    fn serve(
+        responses: Vec<(&'static str, &'static str)>,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut paths = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                    header.clear();
+                }
+                paths.push(
+                    request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            paths
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn lists_versions_from_the_archive() {
+        let (url, server) = serve(vec![("200 OK", LISTING), ("404 Not Found", "")]);
+
+        let versions = fetch_archive_versions(&url, "foo").await.unwrap();
+        let summary: Vec<(&str, &str, bool)> = versions
+            .iter()
+            .map(|version| {
+                (
+                    version.version.as_str(),
+                    version.url.as_str(),
+                    version.has_signature,
+                )
+            })
+            .collect();
+        let newest = format!("{}/packages/f/foo/foo-1:0.9-2-x86_64.pkg.tar.zst", url);
+        let oldest = format!("{}/packages/f/foo/foo-1.0-1-x86_64.pkg.tar.zst", url);
+        assert_eq!(
+            summary,
+            vec![
+                ("1:0.9-2", newest.as_str(), false),
+                ("1.0-1", oldest.as_str(), true),
+            ]
+        );
+        assert_eq!(versions[1].size, "1.00 MiB");
+
+        // Packages the archive does not know have no versions rather than an error
+        assert!(
+            fetch_archive_versions(&url, "missing")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["/packages/f/foo/", "/packages/m/missing/"]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::commands::risk::{metadata_findings, risk_report};
+use crate::models::{AurPackageInfo, Package};
+use crate::parsers::version::vercmp;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::process::Command;
+
+const DEFAULT_AUR_URL: &str = "https://aur.archlinux.org";
+// Keep info requests well below the URL length limit
+const INFO_BATCH_SIZE: usize = 150;
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    results: Vec<AurPackageInfo>,
+}
+
+// Base URL of the AUR, overridable with ARCHSTORE_AUR_URL
+pub(crate) fn aur_base_url() -> String {
+    std::env::var("ARCHSTORE_AUR_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| DEFAULT_AUR_URL.to_string())
+}
+
+async fn rpc(url: &str) -> Result<Vec<AurPackageInfo>, String> {
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("Failed to reach the AUR: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("AUR RPC returned {}", response.status()));
+    }
+
+    let body: RpcResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid AUR RPC response: {}", e))?;
+    if body.kind == "error" {
+        return Err(format!("AUR RPC error: {}", body.error.unwrap_or_default()));
+    }
+    Ok(body.results)
+}
+
+// RPC search on one field: "name", "name-desc", "provides", "depends", ...
+pub(crate) async fn aur_search(by: &str, arg: &str) -> Result<Vec<AurPackageInfo>, String> {
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/rpc/v5/search/{}", aur_base_url(), arg),
+        &[("by", by)],
+    )
+    .map_err(|e| format!("Invalid AUR URL: {}", e))?;
+    rpc(url.as_str()).await
+}
+
+// RPC multi-info for exact package names, batched
+pub(crate) async fn aur_info(names: &[String]) -> Result<Vec<AurPackageInfo>, String> {
+    let mut results = Vec::new();
+    for batch in names.chunks(INFO_BATCH_SIZE) {
+        let params: Vec<(&str, &str)> = batch.iter().map(|name| ("arg[]", name.as_str())).collect();
+        let url =
+            reqwest::Url::parse_with_params(&format!("{}/rpc/v5/info", aur_base_url()), &params)
+                .map_err(|e| format!("Invalid AUR URL: {}", e))?;
+        results.extend(rpc(url.as_str()).await?);
+    }
+    Ok(results)
+}
+
+// Git URL of a package base
+pub(crate) fn aur_git_url(pkgbase: &str) -> String {
+    format!("{}/{}.git", aur_base_url(), pkgbase)
+}
+
+// Turn RPC metadata into the Package used by search results
+pub(crate) fn aur_package(info: &AurPackageInfo, installed: bool) -> Package {
+    Package {
+        name: info.name.clone(),
+        version: info.version.clone(),
+        description: info.description.clone().unwrap_or_default(),
+        source: "aur".to_string(),
+        installed,
+        category: String::new(),
+        downloads: info.num_votes,
+        rating: info.popularity as f32,
+        maintainer: info.maintainer.clone().unwrap_or_default(),
+        size: String::new(),
+        last_updated: chrono::DateTime::from_timestamp(info.last_modified, 0)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        held: false,
+        install_reason: String::new(),
+        risk: Some(risk_report(metadata_findings(info), false)),
+    }
+}
+
+// Installed packages that are in no sync database (`pacman -Qm`), with their versions
+pub(crate) fn foreign_packages() -> Vec<(String, String)> {
+    Command::new("pacman")
+        .arg("-Qm")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_once(' '))
+                .map(|(name, version)| (name.to_string(), version.trim().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Foreign packages with a newer version in the AUR: (info, installed version)
+pub(crate) async fn aur_updates() -> Result<Vec<(AurPackageInfo, String)>, String> {
+    let installed: HashMap<String, String> = foreign_packages().into_iter().collect();
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let names: Vec<String> = installed.keys().cloned().collect();
+
+    let mut updates: Vec<(AurPackageInfo, String)> = aur_info(&names)
+        .await?
+        .into_iter()
+        .filter_map(|info| {
+            let current = installed.get(&info.name)?.clone();
+            (vercmp(&info.version, &current) == Ordering::Greater).then_some((info, current))
+        })
+        .collect();
+    updates.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+    Ok(updates)
+}
@@ -0,0 +1,331 @@
+use crate::commands::aur_resolve::resolve_build_plan;
+use crate::commands::localdb::read_installed_packages;
+use crate::commands::process::{new_job_id, run_sudo, spawn_sudo, stream_child};
+use crate::commands::review::{aur_clone_dir, record_build, verify_approved};
+use crate::models::{AurBuildPlan, AurProgress};
+use crate::parsers::parse_package_filename;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use tauri::Emitter;
+
+const PROGRESS_EVENT: &str = "aur-progress";
+
+// Structured progress for one pipeline run
+struct Progress<'a> {
+    window: &'a tauri::Window,
+    event: &'a str,
+    job_id: String,
+    total_steps: u32,
+}
+
+impl Progress<'_> {
+    fn emit(&self, stage: &str, package: Option<&str>, step: u32, message: String) {
+        let _ = self.window.emit(
+            self.event,
+            AurProgress {
+                job_id: self.job_id.clone(),
+                stage: stage.to_string(),
+                package: package.map(str::to_string),
+                step,
+                total_steps: self.total_steps,
+                percentage: (step * 100 / self.total_steps.max(1)).min(99),
+                message,
+                completed: false,
+            },
+        );
+    }
+}
+
+fn run_privileged(
+    password: &str,
+    args: &[&str],
+    progress: &Progress,
+    step: u32,
+) -> Result<(), String> {
+    let percentage = (step * 100 / progress.total_steps.max(1)).min(99);
+    let output = spawn_sudo(password, args)
+        .and_then(|child| stream_child(child, progress.window, progress.event, percentage))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            args.join(" "),
+            output.error_message()
+        ))
+    }
+}
+
+// Build one package base with makepkg as the current (unprivileged) user
+// Returns the built package files of the requested packages
+fn build_base(
+    pkgbase: &str,
+    packages: &[String],
+    progress: &Progress,
+    step: u32,
+) -> Result<Vec<String>, String> {
+    let dir = aur_clone_dir(pkgbase);
+    let percentage = (step * 100 / progress.total_steps.max(1)).min(99);
+
+    let child = Command::new("makepkg")
+        .args(["--force", "--cleanbuild", "--noconfirm"])
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute makepkg: {}", e))?;
+    let output = stream_child(child, progress.window, progress.event, percentage)?;
+    if !output.status.success() {
+        return Err(format!(
+            "makepkg failed for {}: {}",
+            pkgbase,
+            output.error_message()
+        ));
+    }
+
+    // --packagelist honours PKGDEST and lists every package of a split base
+    let list = Command::new("makepkg")
+        .arg("--packagelist")
+        .current_dir(&dir)
+        .output()
+        .map_err(|e| format!("Failed to execute makepkg: {}", e))?;
+    let files: Vec<String> = String::from_utf8_lossy(&list.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|path| {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            parse_package_filename(file_name).is_some_and(|(name, _, _)| packages.contains(&name))
+        })
+        .filter(|path| std::path::Path::new(path).exists())
+        .collect();
+
+    if files.is_empty() {
+        return Err(format!("makepkg did not produce packages for {}", pkgbase));
+    }
+    Ok(files)
+}
+
+// Native AUR pipeline: resolve, check reviews, install repo dependencies, then build and
+// install each package base in order with makepkg and `pacman -U`
+// Completion is left to the caller, which may run this as part of a larger job
+pub(crate) async fn run_aur_pipeline(
+    targets: &[String],
+    password: &str,
+    window: &tauri::Window,
+    event: &str,
+    job_id: &str,
+) -> Result<AurBuildPlan, String> {
+    let mut progress = Progress {
+        window,
+        event,
+        job_id: job_id.to_string(),
+        total_steps: 1,
+    };
+    progress.emit(
+        "resolve",
+        None,
+        0,
+        format!(":: Resolving dependencies of {}...", targets.join(", ")),
+    );
+    let plan = resolve_build_plan(targets).await?;
+
+    // Steps: review, repo dependencies, then build and install for every base
+    progress.total_steps = 2 + plan.steps.len() as u32 * 2;
+
+    progress.emit("review", None, 1, ":: Checking reviews...".to_string());
+    let unreviewed: Vec<String> = plan
+        .steps
+        .iter()
+        .filter_map(|step| verify_approved(&step.pkgbase).err())
+        .collect();
+    if !unreviewed.is_empty() {
+        return Err(format!(
+            "Review and approve the build files first: {}",
+            unreviewed.join("; ")
+        ));
+    }
+
+    if !plan.repo_dependencies.is_empty() {
+        progress.emit(
+            "repo_deps",
+            None,
+            2,
+            format!(
+                ":: Installing repository dependencies: {}",
+                plan.repo_dependencies.join(" ")
+            ),
+        );
+        let mut args = vec!["pacman", "-S", "--needed", "--asdeps", "--noconfirm"];
+        args.extend(plan.repo_dependencies.iter().map(|name| name.as_str()));
+        run_privileged(password, &args, &progress, 2)?;
+    }
+
+    for (index, step) in plan.steps.iter().enumerate() {
+        let build_step = 3 + index as u32 * 2;
+        progress.emit(
+            "build",
+            Some(&step.pkgbase),
+            build_step,
+            format!(":: Building {} {}...", step.pkgbase, step.version),
+        );
+        let files = build_base(&step.pkgbase, &step.packages, &progress, build_step)?;
+
+        progress.emit(
+            "install",
+            Some(&step.pkgbase),
+            build_step + 1,
+            format!(":: Installing {}...", step.packages.join(" ")),
+        );
+        let mut args = vec!["pacman", "-U", "--noconfirm"];
+        if step.as_dependency {
+            args.push("--asdeps");
+        }
+        args.extend(files.iter().map(|file| file.as_str()));
+        run_privileged(password, &args, &progress, build_step + 1)?;
+
+        let _ = record_build(&step.pkgbase, &files);
+    }
+
+    progress.emit(
+        "done",
+        None,
+        progress.total_steps,
+        format!("Built and installed {} package bases", plan.steps.len()),
+    );
+    Ok(plan)
+}
+
+// Split AUR targets into those whose whole build plan (AUR dependencies included) is
+// approved at the current AUR HEAD and the rest, each with the reason it was skipped
+// Update flows have no review step of their own, so they build what was reviewed and
+// report the others instead of failing outright
+pub(crate) async fn split_reviewed_targets(targets: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut verified: HashMap<String, Result<(), String>> = HashMap::new();
+    let mut reviewed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for target in targets {
+        let plan = match resolve_build_plan(std::slice::from_ref(target)).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                skipped.push(format!("{} ({})", target, e));
+                continue;
+            }
+        };
+        let problems: Vec<String> = plan
+            .steps
+            .iter()
+            .filter_map(|step| {
+                verified
+                    .entry(step.pkgbase.clone())
+                    .or_insert_with(|| verify_approved(&step.pkgbase))
+                    .clone()
+                    .err()
+            })
+            .collect();
+        if problems.is_empty() {
+            reviewed.push(target.clone());
+        } else {
+            skipped.push(format!("{} ({})", target, problems.join("; ")));
+        }
+    }
+    (reviewed, skipped)
+}
+
+// Last event of an AUR job, success or failure
+fn emit_finished(
+    window: &tauri::Window,
+    job_id: String,
+    result: &Result<AurBuildPlan, String>,
+    success: &str,
+) {
+    let (message, completed_ok) = match result {
+        Ok(_) => (success.to_string(), true),
+        Err(e) => (format!("AUR build failed: {}", e), false),
+    };
+    let _ = window.emit(
+        PROGRESS_EVENT,
+        AurProgress {
+            job_id,
+            stage: if completed_ok { "done" } else { "failed" }.to_string(),
+            package: None,
+            step: 0,
+            total_steps: 0,
+            percentage: if completed_ok { 100 } else { 0 },
+            message,
+            completed: true,
+        },
+    );
+}
+
+// Build and install AUR packages natively (progress via "aur-progress")
+#[tauri::command]
+pub async fn install_aur_packages(
+    packages: Vec<String>,
+    password: String,
+    window: tauri::Window,
+) -> Result<AurBuildPlan, String> {
+    if packages.is_empty() {
+        return Err("No packages selected".to_string());
+    }
+
+    let job_id = new_job_id();
+    let result = run_aur_pipeline(&packages, &password, &window, PROGRESS_EVENT, &job_id).await;
+    emit_finished(
+        &window,
+        job_id,
+        &result,
+        "AUR packages installed successfully!",
+    );
+    result
+}
+
+// Rebuild installed AUR packages against the current libraries (progress via "aur-progress")
+// pacman -U installs every target as explicit, so dependencies are marked as such again
+#[tauri::command]
+pub async fn rebuild_aur_packages(
+    packages: Vec<String>,
+    password: String,
+    window: tauri::Window,
+) -> Result<AurBuildPlan, String> {
+    if packages.is_empty() {
+        return Err("No packages selected".to_string());
+    }
+    let installed = read_installed_packages();
+    if let Some(name) = packages
+        .iter()
+        .find(|name| !installed.iter().any(|package| &package.name == *name))
+    {
+        return Err(format!("{} is not installed", name));
+    }
+    let dependencies: Vec<&str> = installed
+        .iter()
+        .filter(|package| !package.explicit && packages.contains(&package.name))
+        .map(|package| package.name.as_str())
+        .collect();
+
+    let job_id = new_job_id();
+    let mut result = run_aur_pipeline(&packages, &password, &window, PROGRESS_EVENT, &job_id).await;
+    if result.is_ok() && !dependencies.is_empty() {
+        let mut args = vec!["pacman", "-D", "--asdeps"];
+        args.extend(dependencies.iter().copied());
+        match run_sudo(&password, &args) {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => {
+                result = Err(format!(
+                    "Rebuilt, but restoring install reasons failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+            Err(e) => result = Err(e),
+        }
+    }
+    emit_finished(
+        &window,
+        job_id,
+        &result,
+        "AUR packages rebuilt successfully!",
+    );
+    result
+}
@@ -0,0 +1,358 @@
+use crate::commands::aur::{aur_info, aur_search};
+use crate::models::{AurBuildPlan, AurBuildStep, AurPackageInfo};
+use crate::parsers::{dependency_name, is_valid_package_name, satisfies_dependency};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+// Providers searched per missing dependency; search results carry no dependency lists, so
+// each candidate costs another multi-info lookup
+const PROVIDER_CANDIDATES: usize = 10;
+
+// Dependencies that installed packages do not satisfy, as reported by `pacman -T`
+// pacman -T understands version constraints and provides
+fn unsatisfied(deps: &[String]) -> Result<Vec<String>, String> {
+    if deps.is_empty() {
+        return Ok(Vec::new());
+    }
+    let output = Command::new("pacman")
+        .arg("-T")
+        .args(deps)
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+// Sync repository package that would satisfy a dependency, including providers
+// dbpath points pacman at another database directory, e.g. a private sync for checks
+pub(crate) fn repo_provider(dep: &str, dbpath: Option<&Path>) -> Option<String> {
+    let mut command = Command::new("pacman");
+    if let Some(dbpath) = dbpath {
+        command.arg("--dbpath").arg(dbpath);
+    }
+    command
+        .args(["-Sddp", "--print-format", "%n", dep])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .map(|line| line.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+}
+
+fn build_depends(info: &AurPackageInfo) -> impl Iterator<Item = &String> {
+    info.depends
+        .iter()
+        .chain(info.make_depends.iter())
+        .chain(info.check_depends.iter())
+}
+
+// Whether an AUR package satisfies a dependency by its own name or through provides
+fn provides_dependency(info: &AurPackageInfo, dep: &str) -> bool {
+    satisfies_dependency(dep, &info.name, &info.version)
+        || info.provides.iter().any(|provide| {
+            let version = provide.split_once('=').map_or("", |(_, version)| version);
+            satisfies_dependency(dep, dependency_name(provide), version)
+        })
+}
+
+// Depth-first over package bases, dependencies first; a base reached again while still on the
+// path closes a cycle, which makepkg cannot build
+fn visit(
+    base: &str,
+    edges: &HashMap<String, Vec<String>>,
+    path: &mut Vec<String>,
+    done: &mut HashSet<String>,
+    order: &mut Vec<String>,
+) -> Result<(), String> {
+    if done.contains(base) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|entry| entry == base) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(base.to_string());
+        return Err(format!(
+            "Dependency cycle between AUR packages: {}",
+            cycle.join(" -> ")
+        ));
+    }
+
+    path.push(base.to_string());
+    for dep in edges.get(base).into_iter().flatten() {
+        visit(dep, edges, path, done, order)?;
+    }
+    path.pop();
+
+    done.insert(base.to_string());
+    order.push(base.to_string());
+    Ok(())
+}
+
+// Resolve AUR targets into the repo packages to install first and the AUR package bases
+// to build, dependencies before the packages that need them
+pub(crate) async fn resolve_build_plan(targets: &[String]) -> Result<AurBuildPlan, String> {
+    if targets.is_empty() {
+        return Err("No packages to build".to_string());
+    }
+    if let Some(name) = targets.iter().find(|name| !is_valid_package_name(name)) {
+        return Err(format!("Invalid package name: {}", name));
+    }
+
+    let mut infos: HashMap<String, AurPackageInfo> = HashMap::new();
+    for info in aur_info(targets).await? {
+        infos.insert(info.name.clone(), info);
+    }
+    if let Some(missing) = targets.iter().find(|name| !infos.contains_key(*name)) {
+        return Err(format!("{} was not found in the AUR", missing));
+    }
+
+    // Packages in the plan in discovery order, and the planned packages each one needs
+    let mut planned: Vec<String> = Vec::new();
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    let mut repo_dependencies: BTreeSet<String> = BTreeSet::new();
+    // AUR packages providing a name, most popular first
+    let mut providers: HashMap<String, Vec<String>> = HashMap::new();
+
+    let mut expand: Vec<String> = targets.to_vec();
+    loop {
+        // (dependency, package that requires it)
+        let mut pending: Vec<(String, String)> = Vec::new();
+        for name in std::mem::take(&mut expand) {
+            if planned.contains(&name) {
+                continue;
+            }
+            planned.push(name.clone());
+            edges.entry(name.clone()).or_default();
+
+            let deps: Vec<String> = build_depends(&infos[&name]).cloned().collect();
+            for dep in unsatisfied(&deps)? {
+                pending.push((dep, name.clone()));
+            }
+        }
+        if pending.is_empty() {
+            break;
+        }
+
+        // A package already in the plan wins, e.g. a -git target providing what another
+        // target needs; then the sync repositories, then the AUR
+        let mut aur_deps: Vec<(String, String)> = Vec::new();
+        for (dep, required_by) in pending {
+            if let Some(provider) = planned
+                .iter()
+                .find(|name| provides_dependency(&infos[*name], &dep))
+            {
+                let needs = edges.entry(required_by).or_default();
+                if !needs.contains(provider) {
+                    needs.push(provider.clone());
+                }
+            } else if let Some(provider) = repo_provider(&dep, None) {
+                repo_dependencies.insert(provider);
+            } else {
+                aur_deps.push((dep, required_by));
+            }
+        }
+
+        let names: Vec<String> = aur_deps
+            .iter()
+            .map(|(dep, _)| dependency_name(dep).to_string())
+            .filter(|name| !infos.contains_key(name))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        for info in aur_info(&names).await? {
+            infos.insert(info.name.clone(), info);
+        }
+
+        // Names no AUR package carries may still be provided by one, e.g. "java-runtime"
+        let mut candidates: Vec<String> = Vec::new();
+        for name in names.iter().filter(|name| !infos.contains_key(*name)) {
+            let mut found = aur_search("provides", name).await.unwrap_or_default();
+            found.sort_by(|a, b| b.popularity.total_cmp(&a.popularity));
+            let found: Vec<String> = found
+                .into_iter()
+                .take(PROVIDER_CANDIDATES)
+                .map(|info| info.name)
+                .collect();
+            candidates.extend(
+                found
+                    .iter()
+                    .filter(|candidate| !infos.contains_key(*candidate))
+                    .cloned(),
+            );
+            providers.insert(name.clone(), found);
+        }
+        for info in aur_info(&candidates).await? {
+            infos.insert(info.name.clone(), info);
+        }
+
+        for (dep, required_by) in aur_deps {
+            let name = dependency_name(&dep);
+            let provider = std::iter::once(name.to_string())
+                .chain(providers.get(name).into_iter().flatten().cloned())
+                .find(|candidate| {
+                    infos
+                        .get(candidate)
+                        .is_some_and(|info| provides_dependency(info, &dep))
+                });
+            let Some(provider) = provider else {
+                return Err(match infos.get(name) {
+                    Some(info) => format!(
+                        "{} requires {}, but the AUR has {} {}",
+                        required_by, dep, info.name, info.version
+                    ),
+                    None => format!(
+                        "{} requires {}, which was not found in the repositories or the AUR",
+                        required_by, dep
+                    ),
+                });
+            };
+
+            let needs = edges.entry(required_by).or_default();
+            if !needs.contains(&provider) {
+                needs.push(provider.clone());
+            }
+            expand.push(provider);
+        }
+    }
+
+    // Split packages share a pkgbase and are built together, so ordering is per base;
+    // packages of one base may depend on each other freely
+    let base_of = |name: &str| infos[name].package_base.clone();
+    let mut base_edges: HashMap<String, Vec<String>> = HashMap::new();
+    for name in &planned {
+        let base = base_of(name);
+        let needs = base_edges.entry(base.clone()).or_default();
+        for dep in &edges[name] {
+            let dep_base = base_of(dep);
+            if dep_base != base && !needs.contains(&dep_base) {
+                needs.push(dep_base);
+            }
+        }
+    }
+
+    let mut done = HashSet::new();
+    let mut order = Vec::new();
+    for name in &planned {
+        visit(
+            &base_of(name),
+            &base_edges,
+            &mut Vec::new(),
+            &mut done,
+            &mut order,
+        )?;
+    }
+
+    let steps = order
+        .into_iter()
+        .map(|base| {
+            let packages: Vec<String> = planned
+                .iter()
+                .filter(|name| infos[*name].package_base == base)
+                .cloned()
+                .collect();
+            let required_by: Vec<String> = planned
+                .iter()
+                .filter(|name| {
+                    infos[*name].package_base != base
+                        && edges[*name].iter().any(|dep| packages.contains(dep))
+                })
+                .cloned()
+                .collect();
+            AurBuildStep {
+                version: infos[&packages[0]].version.clone(),
+                as_dependency: !packages.iter().any(|name| targets.contains(name)),
+                pkgbase: base,
+                packages,
+                required_by,
+            }
+        })
+        .collect();
+
+    Ok(AurBuildPlan {
+        targets: targets.to_vec(),
+        steps,
+        repo_dependencies: repo_dependencies.into_iter().collect(),
+    })
+}
+
+// Ordered build plan for AUR packages, shown before anything is reviewed or built
+#[tauri::command]
+pub async fn get_aur_build_plan(packages: Vec<String>) -> Result<AurBuildPlan, String> {
+    resolve_build_plan(&packages).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(base, deps)| {
+                (
+                    base.to_string(),
+                    deps.iter().map(|dep| dep.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn build_order(
+        targets: &[&str],
+        edges: &HashMap<String, Vec<String>>,
+    ) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        let mut done = HashSet::new();
+        let mut order = Vec::new();
+        for target in targets {
+            visit(target, edges, &mut path, &mut done, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    #[test]
+    fn orders_dependencies_first() {
+        let edges = edges(&[
+            ("app", &["lib-a", "lib-b"]),
+            ("lib-a", &["lib-c"]),
+            ("lib-b", &["lib-c"]),
+            ("lib-c", &[]),
+        ]);
+        assert_eq!(
+            build_order(&["app"], &edges).unwrap(),
+            vec!["lib-c", "lib-a", "lib-b", "app"]
+        );
+        // A shared dependency is built once, however many targets need it
+        assert_eq!(
+            build_order(&["lib-b", "app"], &edges).unwrap(),
+            vec!["lib-c", "lib-b", "lib-a", "app"]
+        );
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let cyclic = edges(&[
+            ("app", &["lib-a"]),
+            ("lib-a", &["lib-b"]),
+            ("lib-b", &["lib-a"]),
+        ]);
+        assert_eq!(
+            build_order(&["app"], &cyclic).unwrap_err(),
+            "Dependency cycle between AUR packages: lib-a -> lib-b -> lib-a"
+        );
+
+        let looping = edges(&[("self-dep", &["self-dep"])]);
+        assert_eq!(
+            build_order(&["self-dep"], &looping).unwrap_err(),
+            "Dependency cycle between AUR packages: self-dep -> self-dep"
+        );
+    }
+}
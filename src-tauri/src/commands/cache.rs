@@ -0,0 +1,300 @@
+use crate::commands::localdb::read_installed_packages;
+use crate::commands::pacman_conf::{option_list, read_pacman_conf};
+use crate::commands::process::{emit_progress, run_sudo};
+use crate::models::{CacheReport, CachedPackage, CachedPackageFile, HelperCache};
+use crate::parsers::version::vercmp;
+use crate::parsers::{format_bytes, parse_package_filename};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_CACHE_DIR: &str = "/var/cache/pacman/pkg";
+const DEFAULT_KEEP: usize = 3;
+
+// Preview what a cache cleanup would remove
+// keep: newest versions kept per installed package (paccache defaults to 3)
+// include_uninstalled: drop every cached version of packages that are no longer installed
+// include_helper_caches: also clear yay/paru build caches in ~/.cache
+#[tauri::command]
+pub async fn analyze_package_cache(
+    keep: Option<usize>,
+    include_uninstalled: Option<bool>,
+    include_helper_caches: Option<bool>,
+) -> Result<CacheReport, String> {
+    build_report(
+        keep.unwrap_or(DEFAULT_KEEP),
+        include_uninstalled.unwrap_or(true),
+        include_helper_caches.unwrap_or(false),
+    )
+}
+
+// Remove what analyze_package_cache previewed with the same options
+// Pacman cache files are removed through sudo, helper caches belong to the user
+#[tauri::command]
+pub async fn clean_package_cache(
+    keep: Option<usize>,
+    include_uninstalled: Option<bool>,
+    include_helper_caches: Option<bool>,
+    password: String,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let event = "cache-progress";
+    let report = build_report(
+        keep.unwrap_or(DEFAULT_KEEP),
+        include_uninstalled.unwrap_or(true),
+        include_helper_caches.unwrap_or(false),
+    )?;
+
+    let files: Vec<&CachedPackageFile> = report
+        .packages
+        .iter()
+        .flat_map(|package| package.files.iter())
+        .filter(|file| file.remove)
+        .collect();
+
+    emit_progress(
+        &window,
+        event,
+        10,
+        format!("Removing {} cached package files...", files.len()),
+        false,
+    );
+
+    // Remove in batches to keep the argument list reasonable
+    let paths: Vec<String> = files
+        .iter()
+        .flat_map(|file| {
+            let signature = format!("{}.sig", file.path);
+            let mut paths = vec![file.path.clone()];
+            if Path::new(&signature).exists() {
+                paths.push(signature);
+            }
+            paths
+        })
+        .collect();
+    let batches = paths.chunks(200).count();
+    for (index, batch) in paths.chunks(200).enumerate() {
+        let mut args = vec!["rm", "-f", "--"];
+        args.extend(batch.iter().map(|path| path.as_str()));
+        let output = run_sudo(&password, &args)?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            emit_progress(
+                &window,
+                event,
+                0,
+                format!("Cache cleanup failed: {}", error),
+                true,
+            );
+            return Err(format!("Cache cleanup failed: {}", error));
+        }
+        let percentage = 10 + (70 * (index + 1) / batches) as u32;
+        emit_progress(
+            &window,
+            event,
+            percentage,
+            format!(
+                "Removed {} of {} files",
+                (index * 200 + batch.len()),
+                paths.len()
+            ),
+            false,
+        );
+    }
+
+    for cache in &report.helper_caches {
+        emit_progress(
+            &window,
+            event,
+            85,
+            format!("Clearing {}...", cache.path),
+            false,
+        );
+        clear_directory(Path::new(&cache.path))
+            .map_err(|e| format!("Failed to clear {}: {}", cache.path, e))?;
+    }
+
+    let message = format!("Cache cleanup freed {}", report.reclaimable_size);
+    emit_progress(&window, event, 100, message.clone(), true);
+    Ok(message)
+}
+
+fn build_report(
+    keep: usize,
+    include_uninstalled: bool,
+    include_helper_caches: bool,
+) -> Result<CacheReport, String> {
+    let cache_dirs = cache_dirs();
+    // Keyed by (name, arch) so files of another architecture (e.g. a lib32 or ARM rebuild
+    // with the same name) neither count as the installed version nor push it out of `keep`
+    let installed: HashMap<(String, String), String> = read_installed_packages()
+        .into_iter()
+        .map(|package| ((package.name, package.arch), package.version))
+        .collect();
+
+    let mut grouped: HashMap<(String, String), Vec<CachedPackageFile>> = HashMap::new();
+    for dir in &cache_dirs {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            if filename.ends_with(".sig") || filename.ends_with(".part") {
+                continue;
+            }
+            let Some((name, version, arch)) = parse_package_filename(&filename) else {
+                continue;
+            };
+            let size_bytes = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            grouped
+                .entry((name, arch))
+                .or_default()
+                .push(CachedPackageFile {
+                    filename,
+                    path: entry.path().to_string_lossy().to_string(),
+                    version,
+                    size: format_bytes(size_bytes),
+                    size_bytes,
+                    remove: false,
+                });
+        }
+    }
+
+    let mut packages: Vec<CachedPackage> = grouped
+        .into_iter()
+        .map(|((name, arch), mut files)| {
+            // Newest first
+            files.sort_by(|a, b| vercmp(&b.version, &a.version));
+            let installed_version = installed.get(&(name.clone(), arch.clone())).cloned();
+
+            for (index, file) in files.iter_mut().enumerate() {
+                file.remove = match &installed_version {
+                    // The installed version always stays, so reinstalling it works offline
+                    Some(version) => index >= keep && file.version != *version,
+                    None => include_uninstalled,
+                };
+            }
+
+            let total_bytes = files.iter().map(|file| file.size_bytes).sum();
+            let reclaimable_bytes = files
+                .iter()
+                .filter(|file| file.remove)
+                .map(|file| file.size_bytes)
+                .sum();
+            CachedPackage {
+                name,
+                arch,
+                installed_version,
+                files,
+                total_bytes,
+                reclaimable_bytes,
+            }
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name).then(a.arch.cmp(&b.arch)));
+
+    let helper_caches = if include_helper_caches {
+        helper_caches()
+    } else {
+        Vec::new()
+    };
+
+    let total_bytes: u64 = packages.iter().map(|package| package.total_bytes).sum();
+    let reclaimable_bytes: u64 = packages
+        .iter()
+        .map(|package| package.reclaimable_bytes)
+        .chain(helper_caches.iter().map(|cache| cache.size_bytes))
+        .sum();
+
+    Ok(CacheReport {
+        cache_dirs: cache_dirs
+            .iter()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .collect(),
+        packages,
+        helper_caches,
+        total_size: format_bytes(total_bytes),
+        reclaimable_bytes,
+        reclaimable_size: format_bytes(reclaimable_bytes),
+    })
+}
+
+// CacheDir entries from pacman.conf, or pacman's default
+pub(crate) fn cache_dirs() -> Vec<PathBuf> {
+    let configured: Vec<PathBuf> = read_pacman_conf()
+        .map(|conf| option_list(&conf, "CacheDir"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+
+    if configured.is_empty() {
+        vec![PathBuf::from(DEFAULT_CACHE_DIR)]
+    } else {
+        configured
+    }
+}
+
+// Build caches of AUR helpers that exist for the current user
+fn helper_caches() -> Vec<HelperCache> {
+    let home = match std::env::var("HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(_) => return Vec::new(),
+    };
+    let cache_home = std::env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".cache"));
+
+    [
+        ("yay", cache_home.join("yay")),
+        ("paru", cache_home.join("paru")),
+    ]
+    .into_iter()
+    .filter(|(_, path)| path.is_dir())
+    .map(|(helper, path)| {
+        let size_bytes = directory_size(&path);
+        HelperCache {
+            helper: helper.to_string(),
+            path: path.to_string_lossy().to_string(),
+            size: format_bytes(size_bytes),
+            size_bytes,
+        }
+    })
+    .collect()
+}
+
+// Total size of a directory tree, without following symlinks
+fn directory_size(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| directory_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+// Remove the contents of a directory but keep the directory itself
+fn clear_directory(path: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+        if entry.file_type()?.is_dir() {
+            std::fs::remove_dir_all(&entry_path)?;
+        } else {
+            std::fs::remove_file(&entry_path)?;
+        }
+    }
+    Ok(())
+}
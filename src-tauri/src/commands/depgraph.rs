@@ -0,0 +1,444 @@
+use crate::commands::localdb::{InstalledPackage, provider_index, read_installed_packages};
+use crate::models::{
+    DependencyChain, DependencyEdge, DependencyGraph, DependencyNode, InstallChains,
+};
+use crate::parsers::{dependency_name, parse_info_blocks};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::Command;
+
+const DEFAULT_MAX_DEPTH: u32 = 3;
+
+// A package as seen by the graph, from the local database or a sync database
+pub(crate) struct GraphPackage {
+    pub version: String,
+    pub installed: bool,
+    pub explicit: bool,
+    pub depends: Vec<String>,    // dependency names, versions stripped
+    pub optdepends: Vec<String>, // dependency names, descriptions stripped
+}
+
+// A package that depends on another one
+#[derive(Clone)]
+pub(crate) struct Dependent {
+    pub name: String,
+    pub optional: bool,
+    pub via: String, // the dependency name it asked for, differs from the target for provides
+}
+
+// Dependency lookups over the local database, falling back to the sync databases
+pub(crate) struct DependencySource {
+    installed: HashMap<String, InstalledPackage>,
+    providers: HashMap<String, Vec<String>>,
+    reverse: HashMap<String, Vec<Dependent>>,
+    sync: HashMap<String, Option<HashMap<String, String>>>,
+}
+
+impl DependencySource {
+    pub fn load() -> DependencySource {
+        let packages = read_installed_packages();
+        let providers = provider_index(&packages);
+
+        // Reverse index: installed package -> installed packages depending on it
+        let mut reverse: HashMap<String, Vec<Dependent>> = HashMap::new();
+        for package in &packages {
+            let links = package
+                .depends
+                .iter()
+                .map(|dep| (dependency_name(dep), false))
+                .chain(package.optdepends.iter().map(|dep| (dep.as_str(), true)));
+            for (dep, optional) in links {
+                for target in providers.get(dep).into_iter().flatten() {
+                    if target != &package.name {
+                        reverse.entry(target.clone()).or_default().push(Dependent {
+                            name: package.name.clone(),
+                            optional,
+                            via: dep.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        DependencySource {
+            installed: packages
+                .into_iter()
+                .map(|package| (package.name.clone(), package))
+                .collect(),
+            providers,
+            reverse,
+            sync: HashMap::new(),
+        }
+    }
+
+    pub fn installed_package(&self, name: &str) -> Option<&InstalledPackage> {
+        self.installed.get(name)
+    }
+
+    // Installed packages that depend on an installed package, directly or through provides
+    pub fn installed_dependents(&self, name: &str) -> &[Dependent] {
+        self.reverse.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Look up several packages in the sync databases with one pacman call
+    fn prefetch(&mut self, names: &[String]) {
+        let missing: Vec<&String> = names
+            .iter()
+            .filter(|name| !self.installed.contains_key(*name) && !self.sync.contains_key(*name))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        // -Sii also reports Required By and Optional For
+        // Unknown names make pacman exit non-zero, the rest is still printed
+        let output = Command::new("pacman").arg("-Sii").args(&missing).output();
+        let mut found: HashMap<String, HashMap<String, String>> = output
+            .map(|output| parse_info_blocks(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|info| Some((info.get("Name")?.clone(), info)))
+            .collect();
+
+        for name in missing {
+            let info = found.remove(name.as_str());
+            self.sync.insert(name.clone(), info);
+        }
+    }
+
+    // Package satisfying a dependency name: installed packages first, then the sync databases
+    // The second value is true when the name is only provided by that package
+    pub fn resolve(&mut self, dep: &str) -> Option<(String, bool)> {
+        if let Some(providers) = self.providers.get(dep) {
+            let name = providers
+                .iter()
+                .find(|name| *name == dep)
+                .unwrap_or(&providers[0])
+                .clone();
+            let provided = name != dep;
+            return Some((name, provided));
+        }
+
+        self.prefetch(&[dep.to_string()]);
+        if self.sync.get(dep).is_some_and(|info| info.is_some()) {
+            return Some((dep.to_string(), false));
+        }
+
+        // Virtual dependency such as "sh": let pacman pick the provider it would install
+        let provider = Command::new("pacman")
+            .args(["-Sddp", "--print-format", "%n", dep])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .next()
+                    .map(|line| line.trim().to_string())
+            })
+            .filter(|name| !name.is_empty())?;
+        Some((provider, true))
+    }
+
+    pub fn package(&mut self, name: &str) -> Option<GraphPackage> {
+        if let Some(package) = self.installed.get(name) {
+            return Some(GraphPackage {
+                version: package.version.clone(),
+                installed: true,
+                explicit: package.explicit,
+                depends: package
+                    .depends
+                    .iter()
+                    .map(|dep| dependency_name(dep).to_string())
+                    .collect(),
+                optdepends: package.optdepends.clone(),
+            });
+        }
+
+        self.prefetch(&[name.to_string()]);
+        let info = self.sync.get(name)?.as_ref()?;
+        Some(GraphPackage {
+            version: info.get("Version").cloned().unwrap_or_default(),
+            installed: false,
+            explicit: false,
+            depends: info_list(info, "Depends On")
+                .iter()
+                .map(|dep| dependency_name(dep).to_string())
+                .collect(),
+            optdepends: info_optdepends(info),
+        })
+    }
+
+    // Packages depending on a package that is not installed, according to the sync databases
+    fn sync_dependents(&mut self, name: &str) -> Vec<Dependent> {
+        self.prefetch(&[name.to_string()]);
+        let Some(Some(info)) = self.sync.get(name) else {
+            return Vec::new();
+        };
+
+        let required = info_list(info, "Required By")
+            .into_iter()
+            .map(|dependent| (dependent, false));
+        let optional = info_list(info, "Optional For")
+            .into_iter()
+            .map(|dependent| (dependent, true));
+        required
+            .chain(optional)
+            .map(|(dependent, optional)| Dependent {
+                name: dependent,
+                optional,
+                via: name.to_string(),
+            })
+            .collect()
+    }
+}
+
+// Space-separated -Si field, "None" meaning empty
+fn info_list(info: &HashMap<String, String>, key: &str) -> Vec<String> {
+    info.get(key)
+        .map(|value| {
+            value
+                .split_whitespace()
+                .filter(|item| *item != "None")
+                .map(|item| item.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// "Optional Deps" entries are "name: description", wrapped lines joined with two spaces
+fn info_optdepends(info: &HashMap<String, String>) -> Vec<String> {
+    info.get("Optional Deps")
+        .map(|value| {
+            value
+                .split("  ")
+                .map(|entry| entry.split(':').next().unwrap_or(entry).trim())
+                .filter(|name| !name.is_empty() && *name != "None")
+                .map(|name| dependency_name(name).to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Build a dependency graph around a package, like pactree
+// direction: "forward" (depends), "reverse" (required by) or "both"
+// Edges always point from the dependent package to its dependency
+#[tauri::command]
+pub async fn get_dependency_graph(
+    package_name: String,
+    direction: Option<String>,
+    max_depth: Option<u32>,
+    include_optional: Option<bool>,
+) -> Result<DependencyGraph, String> {
+    let direction = direction.unwrap_or_else(|| "forward".to_string());
+    let (forward, reverse) = match direction.as_str() {
+        "forward" => (true, false),
+        "reverse" => (false, true),
+        "both" => (true, true),
+        _ => return Err(format!("Unknown direction: {}", direction)),
+    };
+    let max_depth = max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    let include_optional = include_optional.unwrap_or(true);
+
+    let mut source = DependencySource::load();
+    let (root, _) = source
+        .resolve(&package_name)
+        .ok_or_else(|| format!("Package {} not found", package_name))?;
+
+    let mut nodes: Vec<DependencyNode> = Vec::new();
+    let mut edges: Vec<DependencyEdge> = Vec::new();
+    let mut seen_edges: HashSet<(String, String)> = HashSet::new();
+    let mut listed: HashSet<String> = HashSet::new();
+    // A package reached by both walks is expanded once per direction, so "both" still shows
+    // the dependents of a package that also appears as a dependency (and vice versa)
+    let mut visited: HashSet<(String, bool)> = HashSet::new();
+    let mut truncated = false;
+    let mut queue: VecDeque<(String, u32, bool)> = VecDeque::new();
+
+    // Forward and reverse walks are kept apart so "both" does not wander into siblings
+    if forward {
+        queue.push_back((root.clone(), 0, true));
+    }
+    if reverse {
+        queue.push_back((root.clone(), 0, false));
+    }
+
+    while let Some((name, depth, walking_forward)) = queue.pop_front() {
+        if !visited.insert((name.clone(), walking_forward)) {
+            continue;
+        }
+        let package = source.package(&name);
+
+        if listed.insert(name.clone()) {
+            nodes.push(match &package {
+                Some(package) => DependencyNode {
+                    name: name.clone(),
+                    version: package.version.clone(),
+                    installed: package.installed,
+                    explicit: package.explicit,
+                    depth,
+                    missing: false,
+                },
+                None => DependencyNode {
+                    name: name.clone(),
+                    version: String::new(),
+                    installed: false,
+                    explicit: false,
+                    depth,
+                    missing: true,
+                },
+            });
+        }
+
+        let Some(package) = package else {
+            continue;
+        };
+
+        let neighbours: Vec<(String, bool, Option<String>)> = if walking_forward {
+            source.prefetch(&package.depends);
+            let links: Vec<(String, bool)> = package
+                .depends
+                .iter()
+                .map(|dep| (dep.clone(), false))
+                .chain(
+                    package
+                        .optdepends
+                        .iter()
+                        .filter(|_| include_optional)
+                        .map(|dep| (dep.clone(), true)),
+                )
+                .collect();
+            links
+                .into_iter()
+                .map(|(dep, optional)| match source.resolve(&dep) {
+                    Some((target, provided)) => (target, optional, provided.then_some(dep)),
+                    // Unresolvable dependencies still show up as missing nodes
+                    None => (dep, optional, None),
+                })
+                .collect()
+        } else {
+            let dependents = if package.installed {
+                source.installed_dependents(&name).to_vec()
+            } else {
+                source.sync_dependents(&name)
+            };
+            dependents
+                .into_iter()
+                .filter(|dependent| include_optional || !dependent.optional)
+                .map(|dependent| {
+                    let provides = (dependent.via != name).then_some(dependent.via);
+                    (dependent.name, dependent.optional, provides)
+                })
+                .collect()
+        };
+
+        if !neighbours.is_empty() && depth >= max_depth {
+            truncated = true;
+            continue;
+        }
+
+        for (other, optional, provides) in neighbours {
+            let (from, to) = if walking_forward {
+                (name.clone(), other.clone())
+            } else {
+                (other.clone(), name.clone())
+            };
+            if seen_edges.insert((from.clone(), to.clone())) {
+                edges.push(DependencyEdge {
+                    from,
+                    to,
+                    kind: if optional { "optional" } else { "depends" }.to_string(),
+                    provides,
+                });
+            }
+            let key = (other, walking_forward);
+            if !visited.contains(&key)
+                && !queue
+                    .iter()
+                    .any(|(queued, _, forward)| *queued == key.0 && *forward == key.1)
+            {
+                queue.push_back((key.0, depth + 1, walking_forward));
+            }
+        }
+    }
+
+    Ok(DependencyGraph {
+        root,
+        direction,
+        max_depth,
+        nodes,
+        edges,
+        truncated,
+    })
+}
+
+// Explain why a package is installed: the shortest dependency chain to every explicitly
+// installed package that pulls it in, walking required-by links through the local database
+#[tauri::command]
+pub async fn get_install_chains(package_name: String) -> Result<InstallChains, String> {
+    let source = DependencySource::load();
+    let package = source
+        .installed_package(&package_name)
+        .ok_or_else(|| format!("{} is not installed", package_name))?;
+    let explicit = package.explicit;
+
+    // Breadth-first, so the first time a package is reached is along a shortest chain
+    // Hard dependencies are visited before optional ones at each step
+    let mut parents: HashMap<String, (String, bool, Option<String>)> = HashMap::new();
+    let mut visited: HashSet<String> = HashSet::from([package_name.clone()]);
+    let mut queue: VecDeque<String> = VecDeque::from([package_name.clone()]);
+    let mut roots: Vec<String> = Vec::new();
+
+    while let Some(name) = queue.pop_front() {
+        let mut dependents = source.installed_dependents(&name).to_vec();
+        dependents.sort_by_key(|dependent| dependent.optional);
+
+        for dependent in dependents {
+            if !visited.insert(dependent.name.clone()) {
+                continue;
+            }
+            let provides = (dependent.via != name).then_some(dependent.via.clone());
+            parents.insert(
+                dependent.name.clone(),
+                (name.clone(), dependent.optional, provides),
+            );
+
+            if source
+                .installed_package(&dependent.name)
+                .is_some_and(|package| package.explicit)
+            {
+                roots.push(dependent.name.clone());
+            }
+            // Explicit packages can themselves be required by others, keep walking
+            queue.push_back(dependent.name);
+        }
+    }
+
+    let chains = roots
+        .into_iter()
+        .map(|root| {
+            // Follow parents from the root back down to the package
+            let mut links = Vec::new();
+            let mut current = root.clone();
+            while let Some((dependency, optional, provides)) = parents.get(&current) {
+                links.push(DependencyEdge {
+                    from: current.clone(),
+                    to: dependency.clone(),
+                    kind: if *optional { "optional" } else { "depends" }.to_string(),
+                    provides: provides.clone(),
+                });
+                current = dependency.clone();
+            }
+            DependencyChain {
+                optional: links.iter().any(|link| link.kind == "optional"),
+                root,
+                links,
+            }
+        })
+        .collect();
+
+    Ok(InstallChains {
+        package: package_name,
+        explicit,
+        chains,
+    })
+}
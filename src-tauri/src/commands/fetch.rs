@@ -0,0 +1,23 @@
+// Fetch a text resource from an http(s) URL, a file:// URL or a plain path
+// Local files let fixtures stand in for remote services
+pub(crate) async fn fetch_text(url: &str, what: &str) -> Result<String, String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {} {}: {}", what, path, e));
+    }
+    if url.starts_with('/') {
+        return std::fs::read_to_string(url)
+            .map_err(|e| format!("Failed to read {} {}: {}", what, url, e));
+    }
+
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", what, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", what, response.status()));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", what, e))
+}
@@ -0,0 +1,179 @@
+use crate::commands::localdb::read_local_packages;
+use crate::commands::process::{emit_progress, spawn_sudo, stream_child};
+use crate::models::{FileMatch, FileOwner, PackageFiles};
+use crate::parsers::{is_valid_package_name, parse_file_owner, parse_file_search};
+use std::process::Command;
+
+const SYNC_DB_DIR: &str = "/var/lib/pacman/sync";
+
+// The files databases are only there after `pacman -Fy` has been run once
+fn files_databases_synced() -> bool {
+    std::fs::read_dir(SYNC_DB_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .any(|entry| entry.file_name().to_string_lossy().ends_with(".files"))
+        })
+        .unwrap_or(false)
+}
+
+fn require_files_databases() -> Result<(), String> {
+    if files_databases_synced() {
+        Ok(())
+    } else {
+        Err("The pacman files databases have not been synced yet".to_string())
+    }
+}
+
+// Files a package installs: the local database for installed packages,
+// the sync .files databases for everything else
+#[tauri::command]
+pub async fn list_package_files(package_name: String) -> Result<PackageFiles, String> {
+    if !is_valid_package_name(&package_name) {
+        return Err(format!("Invalid package name: {}", package_name));
+    }
+
+    if let Some(package) = read_local_packages()
+        .into_iter()
+        .find(|package| package.name == package_name)
+    {
+        let files = package
+            .read_entry("files")
+            .remove("FILES")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| format!("/{}", path))
+            .collect();
+        return Ok(PackageFiles {
+            package: package_name,
+            installed: true,
+            files,
+        });
+    }
+
+    require_files_databases()?;
+    let output = Command::new("pacman")
+        .args(["-Fl", "--machinereadable", &package_name])
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Package {} not found", package_name));
+    }
+
+    let files = parse_file_search(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .map(|file| file.path)
+        .collect();
+    Ok(PackageFiles {
+        package: package_name,
+        installed: false,
+        files,
+    })
+}
+
+// Package owning a path: installed packages first, then the files databases
+#[tauri::command]
+pub async fn find_file_owner(path: String) -> Result<Option<FileOwner>, String> {
+    if !path.starts_with('/') {
+        return Err("Path must be absolute".to_string());
+    }
+
+    let output = Command::new("pacman")
+        .args(["-Qo", "--", &path])
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    let owner = Some(&output)
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_file_owner(&String::from_utf8_lossy(&output.stdout)));
+    if let Some((package, version)) = owner {
+        return Ok(Some(FileOwner {
+            path,
+            package,
+            version,
+            repository: None,
+            installed: true,
+        }));
+    }
+
+    if !files_databases_synced() {
+        return Ok(None);
+    }
+
+    Ok(search_files_database(&path)?
+        .into_iter()
+        .find(|file| file.path == path)
+        .map(|file| FileOwner {
+            path,
+            package: file.package,
+            version: file.version,
+            repository: Some(file.repository),
+            installed: false,
+        }))
+}
+
+// Search the files databases by file name, or by full path when the query contains a slash
+#[tauri::command]
+pub async fn search_files(query: String) -> Result<Vec<FileMatch>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    require_files_databases()?;
+    search_files_database(query.trim())
+}
+
+pub(crate) fn search_files_database(query: &str) -> Result<Vec<FileMatch>, String> {
+    let output = Command::new("pacman")
+        .args(["-F", "--machinereadable", "--", query])
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    // pacman exits with 1 when nothing matches
+    if !output.status.success() && !output.stderr.is_empty() {
+        return Err(format!(
+            "pacman -F failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(parse_file_search(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Download the files databases with `pacman -Fy`
+#[tauri::command]
+pub async fn sync_files_databases(password: String, window: tauri::Window) -> Result<(), String> {
+    let event = "files-progress";
+    emit_progress(
+        &window,
+        event,
+        10,
+        ":: Synchronizing files databases...".to_string(),
+        false,
+    );
+
+    let result = spawn_sudo(&password, &["pacman", "-Fy"])
+        .and_then(|child| stream_child(child, &window, event, 50));
+
+    match result {
+        Ok(output) if output.status.success() => {
+            emit_progress(
+                &window,
+                event,
+                100,
+                "Files databases synchronized".to_string(),
+                true,
+            );
+            Ok(())
+        }
+        Ok(output) => {
+            let error = output.error_message();
+            emit_progress(&window, event, 0, format!("Sync failed: {}", error), true);
+            Err(format!("Sync failed: {}", error))
+        }
+        Err(e) => {
+            emit_progress(&window, event, 0, format!("Sync failed: {}", e), true);
+            Err(format!("Sync failed: {}", e))
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::commands::aur::{aur_info, aur_search, foreign_packages};
+use crate::commands::localdb::read_local_packages;
+use crate::commands::repos::custom_repo_names;
+use crate::commands::review::aur_clone_dir;
+use crate::models::ForeignPackageAudit;
+use crate::parsers::{dependency_name, parse_info_blocks};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+// Suffixes of AUR repackagings of upstream binaries; without them the name is usually the
+// official package's
+const BINARY_SUFFIXES: [&str; 2] = ["-bin", "-appimage"];
+
+// An official package that can take over from a foreign one: (repo/name, matched by)
+type OfficialMatch = (String, &'static str);
+
+// Official sync packages by the names they replace, provide or carry
+// Custom repositories are left out; they are not official replacements
+fn official_index() -> Result<HashMap<String, Vec<OfficialMatch>>, String> {
+    let output = Command::new("pacman")
+        .arg("-Si")
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+    let custom = custom_repo_names();
+
+    let mut index: HashMap<String, Vec<OfficialMatch>> = HashMap::new();
+    for info in parse_info_blocks(&String::from_utf8_lossy(&output.stdout)) {
+        let field = |key: &str| info.get(key).cloned().unwrap_or_default();
+        let repo = field("Repository");
+        if custom.contains(&repo) {
+            continue;
+        }
+        let package = format!("{}/{}", repo, field("Name"));
+
+        let mut add = |name: &str, matched_by: &'static str| {
+            index
+                .entry(name.to_string())
+                .or_default()
+                .push((package.clone(), matched_by));
+        };
+        add(&field("Name"), "name");
+        for name in field("Replaces")
+            .split_whitespace()
+            .filter(|name| *name != "None")
+        {
+            add(dependency_name(name), "replaces");
+        }
+        for name in field("Provides")
+            .split_whitespace()
+            .filter(|name| *name != "None")
+        {
+            add(dependency_name(name), "provides");
+        }
+    }
+
+    Ok(index)
+}
+
+// Official package for a foreign one: replaces beats provides, then the name without -bin
+fn official_match(
+    name: &str,
+    index: &HashMap<String, Vec<OfficialMatch>>,
+) -> Option<OfficialMatch> {
+    let by_name = |wanted: &str, matched_by: &str| {
+        index
+            .get(wanted)?
+            .iter()
+            .find(|(_, kind)| *kind == matched_by)
+            .cloned()
+    };
+    by_name(name, "replaces")
+        .or_else(|| by_name(name, "provides"))
+        .or_else(|| {
+            BINARY_SUFFIXES
+                .iter()
+                .filter_map(|suffix| name.strip_suffix(suffix))
+                .find_map(|base| by_name(base, "name"))
+        })
+}
+
+// Evidence that a package base was built from the AUR: a clone kept by ArchStore, yay or paru
+fn built_from_aur(pkgbase: &str) -> bool {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    [
+        aur_clone_dir(pkgbase),
+        home.join(".cache/yay").join(pkgbase),
+        home.join(".cache/paru/clone").join(pkgbase),
+    ]
+    .iter()
+    .any(|dir| dir.exists())
+}
+
+// Classify every foreign (-Qm) package and suggest where to get it from instead
+// official_available: a repo package replaces or provides it, or is the non -bin original
+// aur_present:        still maintained in the AUR
+// aur_missing:        built from the AUR, but since deleted or renamed there
+// local_only:         built locally from a PKGBUILD that never came from the AUR
+#[tauri::command]
+pub async fn audit_foreign_packages() -> Result<Vec<ForeignPackageAudit>, String> {
+    let foreign = foreign_packages();
+    if foreign.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let official = official_index()?;
+    let names: Vec<String> = foreign.iter().map(|(name, _)| name.clone()).collect();
+    let aur: HashMap<String, String> = aur_info(&names)
+        .await?
+        .into_iter()
+        .map(|info| (info.name, info.version))
+        .collect();
+    let bases: HashMap<String, String> = read_local_packages()
+        .into_iter()
+        .filter_map(|package| {
+            let base = package.read_entry("desc").get("BASE")?.first()?.clone();
+            Some((package.name, base))
+        })
+        .collect();
+
+    let mut audits = Vec::new();
+    for (name, version) in foreign {
+        let aur_version = aur.get(&name).cloned();
+        let mut audit = ForeignPackageAudit {
+            name: name.clone(),
+            version,
+            status: String::new(),
+            aur_version: aur_version.clone(),
+            replacement: None,
+            replacement_source: None,
+            matched_by: None,
+            suggestion: String::new(),
+        };
+
+        if let Some((package, matched_by)) = official_match(&name, &official) {
+            audit.status = "official_available".to_string();
+            audit.suggestion = match matched_by {
+                "replaces" => format!("{} replaces it in the official repositories", package),
+                "provides" => format!("{} provides it in the official repositories", package),
+                _ => format!("{} is now packaged in the official repositories", package),
+            };
+            audit.replacement = package.split_once('/').map(|(_, name)| name.to_string());
+            audit.replacement_source = Some("official".to_string());
+            audit.matched_by = Some(matched_by.to_string());
+        } else if let Some(aur_version) = aur_version {
+            audit.status = "aur_present".to_string();
+            audit.suggestion = format!("Maintained in the AUR ({})", aur_version);
+        } else if built_from_aur(bases.get(&name).unwrap_or(&name)) {
+            audit.status = "aur_missing".to_string();
+            // A package that took over the name keeps it up to date; otherwise nothing will
+            let renamed = aur_search("provides", &name)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|info| info.name != name)
+                .max_by(|a, b| a.popularity.total_cmp(&b.popularity));
+            match renamed {
+                Some(info) => {
+                    audit.suggestion =
+                        format!("Removed from the AUR; {} provides it there now", info.name);
+                    audit.replacement = Some(info.name);
+                    audit.replacement_source = Some("aur".to_string());
+                    audit.matched_by = Some("provides".to_string());
+                }
+                None => {
+                    audit.suggestion =
+                        "Removed from the AUR and no longer receives updates; consider removing it"
+                            .to_string();
+                }
+            }
+        } else {
+            audit.status = "local_only".to_string();
+            audit.suggestion = "Built locally; updates have to come from its PKGBUILD".to_string();
+        }
+        audits.push(audit);
+    }
+
+    Ok(audits)
+}
@@ -0,0 +1,244 @@
+use crate::commands::pacman_conf::{option_list, read_pacman_conf, save_pacman_conf};
+use crate::models::HeldPackages;
+use crate::parsers::is_valid_package_name;
+use std::collections::HashSet;
+use std::process::Command;
+
+// List packages and groups held back through IgnorePkg/IgnoreGroup, plus masked Flatpaks
+#[tauri::command]
+pub fn get_held_packages() -> Result<HeldPackages, String> {
+    let conf = read_pacman_conf()?;
+    Ok(HeldPackages {
+        packages: option_list(&conf, "IgnorePkg"),
+        groups: option_list(&conf, "IgnoreGroup"),
+        flatpak: flatpak_masks(),
+    })
+}
+
+// Hold a package: kind is "package" (IgnorePkg), "group" (IgnoreGroup) or "flatpak" (flatpak mask)
+#[tauri::command]
+pub async fn hold_package(name: String, kind: String, password: String) -> Result<String, String> {
+    set_held(&name, &kind, true, &password)
+}
+
+// Release a held package again
+#[tauri::command]
+pub async fn unhold_package(
+    name: String,
+    kind: String,
+    password: String,
+) -> Result<String, String> {
+    set_held(&name, &kind, false, &password)
+}
+
+fn set_held(name: &str, kind: &str, held: bool, password: &str) -> Result<String, String> {
+    // IgnorePkg and flatpak masks accept glob patterns
+    let pattern = name.replace('*', "");
+    if pattern.is_empty() || !is_valid_package_name(&pattern) {
+        return Err(format!("Invalid package name: {}", name));
+    }
+
+    let key = match kind {
+        "package" => "IgnorePkg",
+        "group" => "IgnoreGroup",
+        "flatpak" => return set_flatpak_mask(name, held),
+        _ => return Err(format!("Unknown hold type: {}", kind)),
+    };
+
+    let mut conf = read_pacman_conf()?;
+    let mut items = conf.list("options", key);
+    let present = items.iter().any(|item| item == name);
+
+    let included = !present && option_list(&conf, key).iter().any(|item| item == name);
+
+    match (held, present) {
+        (true, true) => return Ok(format!("{} is already held", name)),
+        (true, false) if included => return Ok(format!("{} is already held", name)),
+        (false, false) if included => {
+            return Err(format!(
+                "{} is held by a file Include'd from pacman.conf; remove it there",
+                name
+            ));
+        }
+        (false, false) => return Ok(format!("{} is not held", name)),
+        (true, false) => items.push(name.to_string()),
+        (false, true) => items.retain(|item| item != name),
+    }
+
+    conf.set_list("options", key, &items);
+    save_pacman_conf(password, &conf)?;
+
+    Ok(if held {
+        format!("{} added to {}", name, key)
+    } else {
+        format!("{} removed from {}", name, key)
+    })
+}
+
+// Installations ("system", "user") that have an app matching the pattern installed
+fn flatpak_installations(pattern: &str) -> Vec<String> {
+    let mut installations: Vec<String> = Command::new("flatpak")
+        .args(["list", "--app", "--columns=application,installation"])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .filter(|(app_id, _)| glob_match(pattern, app_id.trim()))
+                .map(|(_, installation)| installation.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    installations.sort();
+    installations.dedup();
+    installations
+}
+
+// Masks are per installation, so the mask goes where the app lives; apps that are not
+// installed yet are masked system-wide, flatpak's default
+fn set_flatpak_mask(name: &str, held: bool) -> Result<String, String> {
+    let installations: Vec<String> = if held {
+        let installed = flatpak_installations(name);
+        if installed.is_empty() {
+            vec!["system".to_string()]
+        } else {
+            installed
+        }
+    } else {
+        ["system", "user"]
+            .into_iter()
+            .filter(|installation| {
+                flatpak_masks_in(installation)
+                    .iter()
+                    .any(|mask| mask == name)
+            })
+            .map(str::to_string)
+            .collect()
+    };
+    if installations.is_empty() {
+        return Ok(format!("{} is not masked", name));
+    }
+
+    for installation in &installations {
+        let mut command = Command::new("flatpak");
+        command.args(["mask", &format!("--{}", installation)]);
+        if !held {
+            command.arg("--remove");
+        }
+        let output = command
+            .arg(name)
+            .output()
+            .map_err(|e| format!("Failed to execute flatpak: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "flatpak mask failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+
+    Ok(if held {
+        format!(
+            "{} masked from Flatpak updates ({})",
+            name,
+            installations.join(", ")
+        )
+    } else {
+        format!("{} unmasked", name)
+    })
+}
+
+// `flatpak mask` without a pattern lists the patterns of one installation
+fn flatpak_masks_in(installation: &str) -> Vec<String> {
+    Command::new("flatpak")
+        .args(["mask", &format!("--{}", installation)])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.ends_with(':'))
+                .map(|line| line.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Masked patterns of the system and user installations
+fn flatpak_masks() -> Vec<String> {
+    let mut masks = flatpak_masks_in("system");
+    for mask in flatpak_masks_in("user") {
+        if !masks.contains(&mask) {
+            masks.push(mask);
+        }
+    }
+    masks
+}
+
+// Glob match as used by IgnorePkg and flatpak masks (only * is special)
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            name.starts_with(prefix)
+                && (0..=name.len() - prefix.len())
+                    .any(|skip| glob_match(rest, &name[prefix.len() + skip..]))
+        }
+    }
+}
+
+// Everything that is held back: IgnorePkg patterns, members of IgnoreGroup groups and flatpak masks
+pub(crate) struct HeldSet {
+    patterns: Vec<String>,
+    group_members: HashSet<String>,
+    flatpak_patterns: Vec<String>,
+}
+
+impl HeldSet {
+    pub fn load() -> HeldSet {
+        let held = get_held_packages().unwrap_or(HeldPackages {
+            packages: Vec::new(),
+            groups: Vec::new(),
+            flatpak: Vec::new(),
+        });
+
+        let group_members = if held.groups.is_empty() {
+            HashSet::new()
+        } else {
+            Command::new("pacman")
+                .arg("-Sgq")
+                .args(&held.groups)
+                .output()
+                .map(|output| {
+                    String::from_utf8_lossy(&output.stdout)
+                        .lines()
+                        .map(|line| line.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        HeldSet {
+            patterns: held.packages,
+            group_members,
+            flatpak_patterns: held.flatpak,
+        }
+    }
+
+    pub fn is_held(&self, name: &str, source: &str) -> bool {
+        if source == "flatpak" {
+            self.flatpak_patterns
+                .iter()
+                .any(|pattern| glob_match(pattern, name))
+        } else {
+            self.group_members.contains(name)
+                || self
+                    .patterns
+                    .iter()
+                    .any(|pattern| glob_match(pattern, name))
+        }
+    }
+}
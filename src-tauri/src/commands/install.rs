@@ -1,3 +1,5 @@
+use crate::commands::aur_build::run_aur_pipeline;
+use crate::commands::process::new_job_id;
 use crate::models::InstallProgress;
 use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
@@ -29,7 +31,8 @@ pub async fn install_package(
     );
 
     let result = match source.as_str() {
-        "official" => {
+        // pacman installs from custom repositories the same way
+        "official" | "custom" => {
             emit_progress(
                 30,
                 "Installing from official repositories...".to_string(),
@@ -110,118 +113,29 @@ pub async fn install_package(
         }
         "aur" => {
             emit_progress(30, "Installing from AUR...".to_string(), false);
-            // Detect available AUR helper
-            let helper = if Command::new("yay").arg("--version").output().is_ok() {
-                "yay"
-            } else if Command::new("paru").arg("--version").output().is_ok() {
-                "paru"
-            } else {
-                emit_progress(
-                    0,
-                    "No AUR helper found. Please install yay or paru.".to_string(),
-                    true,
-                );
-                return Err("No AUR helper found. Please install yay or paru.".to_string());
+            let job_id = new_job_id();
+            let result = run_aur_pipeline(
+                std::slice::from_ref(&package_name),
+                &password,
+                &window,
+                "install-progress",
+                &job_id,
+            )
+            .await;
+            return match result {
+                Ok(_) => {
+                    emit_progress(
+                        100,
+                        "Installation completed successfully!".to_string(),
+                        true,
+                    );
+                    Ok(())
+                }
+                Err(e) => {
+                    emit_progress(0, format!("Installation failed: {}", e), true);
+                    Err(format!("Installation failed: {}", e))
+                }
             };
-            emit_progress(
-                40,
-                format!("Using {} to install {}...", helper, package_name),
-                false,
-            );
-
-            // Create a wrapper script that handles password input for sudo
-            // First authenticate sudo, then run AUR helper as regular user
-            let script_content = format!(
-                r#"#!/bin/bash
-echo '{}' | sudo -S -v
-{} -S --noconfirm {}
-"#,
-                password, helper, package_name
-            );
-
-            // Write script to temporary file
-            let script_path = format!(
-                "/tmp/archstore_install_{}.sh",
-                package_name.replace("/", "_")
-            );
-            std::fs::write(&script_path, script_content)
-                .map_err(|e| format!("Failed to write temp script: {}", e))?;
-
-            // Make script executable
-            Command::new("chmod")
-                .args(&["+x", &script_path])
-                .output()
-                .map_err(|e| format!("Failed to make script executable: {}", e))?;
-
-            // Execute the script with real-time streaming
-            use std::thread;
-
-            let mut child = Command::new(&script_path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|e| format!("Failed to spawn AUR helper: {}", e))?;
-
-            // Stream both stdout and stderr in real-time
-            let stdout = child.stdout.take();
-            let stderr = child.stderr.take();
-
-            let window_clone = window.clone();
-            let stdout_handle = stdout.map(|stdout| {
-                thread::spawn(move || {
-                    let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            if !line.trim().is_empty() {
-                                let _ = window_clone.emit(
-                                    "install-progress",
-                                    InstallProgress {
-                                        percentage: 60,
-                                        message: line,
-                                        completed: false,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                })
-            });
-
-            let window_clone = window.clone();
-            let stderr_handle = stderr.map(|stderr| {
-                thread::spawn(move || {
-                    let reader = BufReader::new(stderr);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            if !line.trim().is_empty() && !line.contains("[sudo] password") {
-                                let _ = window_clone.emit(
-                                    "install-progress",
-                                    InstallProgress {
-                                        percentage: 60,
-                                        message: line,
-                                        completed: false,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                })
-            });
-
-            // Wait for streaming threads to complete
-            if let Some(handle) = stdout_handle {
-                let _ = handle.join();
-            }
-            if let Some(handle) = stderr_handle {
-                let _ = handle.join();
-            }
-
-            let result = child.wait_with_output();
-
-            // Clean up script
-            let _ = std::fs::remove_file(&script_path);
-
-            result
         }
         "flatpak" => {
             emit_progress(30, "Installing from Flatpak...".to_string(), false);
@@ -351,7 +265,7 @@ pub async fn remove_package(
     );
 
     let result = match source.as_str() {
-        "official" => {
+        "official" | "custom" => {
             emit_progress(
                 30,
                 "Removing from official repositories...".to_string(),
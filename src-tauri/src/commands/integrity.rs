@@ -0,0 +1,324 @@
+use crate::commands::cache::cache_dirs;
+use crate::commands::localdb::{InstalledPackage, read_installed_packages, read_local_packages};
+use crate::commands::process::{emit_progress, run_sudo_step, spawn_sudo};
+use crate::models::{InstallProgress, IntegrityIssue, IntegrityReport};
+use crate::parsers::{
+    is_valid_package_name, parse_backup_files, parse_info_blocks, parse_integrity_backup,
+    parse_integrity_summary, parse_integrity_warning, parse_package_filename,
+};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::thread;
+use tauri::Emitter;
+
+const PROGRESS_EVENT: &str = "integrity-progress";
+
+// Verify installed files with `pacman -Qkk`: missing files, checksums, permissions, mtimes
+// Checks one package, or every package when package_name is None
+// With a password the check runs through sudo, so root-only files can be read
+#[tauri::command]
+pub async fn check_package_integrity(
+    package_name: Option<String>,
+    password: Option<String>,
+    window: tauri::Window,
+) -> Result<IntegrityReport, String> {
+    if let Some(name) = package_name
+        .as_ref()
+        .filter(|name| !is_valid_package_name(name))
+    {
+        return Err(format!("Invalid package name: {}", name));
+    }
+
+    let local_packages = read_local_packages();
+    let total = match &package_name {
+        Some(_) => 1,
+        None => local_packages.len().max(1),
+    };
+
+    let mut args = vec!["pacman", "-Qkk"];
+    if let Some(name) = &package_name {
+        args.push(name);
+    }
+
+    let mut child = match &password {
+        Some(password) => spawn_sudo(password, &args)?,
+        None => Command::new(args[0])
+            .args(&args[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to execute pacman: {}", e))?,
+    };
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        0,
+        format!("Checking {} packages...", total),
+        false,
+    );
+
+    // Problems are reported on stderr; stdout has one summary line per package and
+    // "backup file:" lines for changed backup files, which pacman does not count as problems
+    let stderr_handle = child.stderr.take().map(|stderr| {
+        thread::spawn(move || {
+            let mut collected = String::new();
+            let _ = BufReader::new(stderr).read_to_string(&mut collected);
+            collected
+        })
+    });
+
+    let mut checked: u32 = 0;
+    let mut backup_changes = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some((package, path, problem)) = parse_integrity_backup(&line) {
+                backup_changes.push(IntegrityIssue {
+                    package,
+                    path,
+                    problem,
+                    backup: true,
+                });
+                continue;
+            }
+            let Some(package) = parse_integrity_summary(&line) else {
+                continue;
+            };
+            checked += 1;
+            let _ = window.emit(
+                PROGRESS_EVENT,
+                InstallProgress {
+                    percentage: (checked as usize * 100 / total).min(99) as u32,
+                    message: format!("Checked {}", package),
+                    completed: false,
+                },
+            );
+        }
+    }
+
+    let stderr = stderr_handle
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for pacman: {}", e))?;
+
+    // pacman exits non-zero when it found problems, so only fail when nothing was checked
+    if checked == 0 && !status.success() {
+        let error = stderr
+            .lines()
+            .find(|line| line.starts_with("error:"))
+            .unwrap_or(stderr.trim())
+            .to_string();
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            0,
+            format!("Integrity check failed: {}", error),
+            true,
+        );
+        return Err(format!("Integrity check failed: {}", error));
+    }
+
+    // Fallback for stderr warnings about backup files, e.g. a missing one or a pacman
+    // that reports them there
+    let wanted: HashSet<String> = stderr
+        .lines()
+        .filter_map(parse_integrity_warning)
+        .map(|(package, _, _)| package)
+        .collect();
+    let backups: HashMap<String, HashSet<String>> = local_packages
+        .iter()
+        .filter(|package| wanted.contains(&package.name))
+        .map(|package| {
+            let files = package.read_entry("files");
+            (
+                package.name.clone(),
+                parse_backup_files(&files).into_iter().collect(),
+            )
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    for (package, path, problem) in stderr.lines().filter_map(parse_integrity_warning) {
+        let backup = backups
+            .get(&package)
+            .is_some_and(|files| files.contains(&path));
+        let issue = IntegrityIssue {
+            package,
+            path,
+            problem,
+            backup,
+        };
+        if backup {
+            backup_changes.push(issue);
+        } else {
+            issues.push(issue);
+        }
+    }
+
+    let mut affected_packages: Vec<String> =
+        issues.iter().map(|issue| issue.package.clone()).collect();
+    affected_packages.sort();
+    affected_packages.dedup();
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        100,
+        format!(
+            "Checked {} packages: {} problems in {} packages, {} changed config files",
+            checked,
+            issues.len(),
+            affected_packages.len(),
+            backup_changes.len()
+        ),
+        true,
+    );
+
+    Ok(IntegrityReport {
+        checked_packages: checked,
+        issues,
+        backup_changes,
+        affected_packages,
+    })
+}
+
+// Reinstall packages reported as damaged, at exactly their installed version
+// Package files come from the cache; repository packages fall back to -S only when the
+// sync database still has the installed version
+#[tauri::command]
+pub async fn reinstall_packages(
+    packages: Vec<String>,
+    password: String,
+    window: tauri::Window,
+) -> Result<String, String> {
+    if packages.is_empty() {
+        return Err("No packages selected".to_string());
+    }
+    if let Some(name) = packages.iter().find(|name| !is_valid_package_name(name)) {
+        return Err(format!("Invalid package name: {}", name));
+    }
+
+    let foreign: HashSet<String> = Command::new("pacman")
+        .arg("-Qmq")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let installed: HashMap<String, InstalledPackage> = read_installed_packages()
+        .into_iter()
+        .map(|package| (package.name.clone(), package))
+        .collect();
+
+    // The installed version comes from the cache; the repositories are only used when they
+    // still carry that exact version, anything else would be a partial upgrade
+    let mut cached_files = Vec::new();
+    let mut repo_packages = Vec::new();
+    let mut outdated = Vec::new();
+    let mut skipped = Vec::new();
+    for name in &packages {
+        let Some(package) = installed.get(name) else {
+            return Err(format!("{} is not installed", name));
+        };
+        if let Some(path) = cached_package_file(&package.name, &package.version, &package.arch) {
+            cached_files.push(path);
+        } else if foreign.contains(name) {
+            skipped.push(name.clone());
+        } else {
+            match sync_version(name) {
+                Some(version) if version == package.version => repo_packages.push(name.as_str()),
+                Some(version) => outdated.push(format!(
+                    "{} ({} installed, {} in the repositories)",
+                    name, package.version, version
+                )),
+                None => skipped.push(name.clone()),
+            }
+        }
+    }
+    if !outdated.is_empty() {
+        return Err(format!(
+            "The installed version is neither cached nor in the repositories any more: {}. Run a full system update instead.",
+            outdated.join(", ")
+        ));
+    }
+
+    if !cached_files.is_empty() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            10,
+            format!(
+                "Reinstalling {} packages from the cache...",
+                cached_files.len()
+            ),
+            false,
+        );
+        let mut args = vec!["pacman", "-U", "--noconfirm"];
+        args.extend(cached_files.iter().map(|path| path.as_str()));
+        run_sudo_step(&password, &args, &window, PROGRESS_EVENT, 40)?;
+    }
+
+    if !repo_packages.is_empty() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            60,
+            format!("Reinstalling {} packages...", repo_packages.len()),
+            false,
+        );
+        let mut args = vec!["pacman", "-S", "--noconfirm"];
+        args.extend(repo_packages);
+        run_sudo_step(&password, &args, &window, PROGRESS_EVENT, 80)?;
+    }
+
+    let message = if skipped.is_empty() {
+        "Reinstall completed successfully!".to_string()
+    } else {
+        format!(
+            "Reinstall completed; no package file found for {} (rebuild them from the AUR)",
+            skipped.join(", ")
+        )
+    };
+    emit_progress(&window, PROGRESS_EVENT, 100, message.clone(), true);
+    Ok(message)
+}
+
+// Cached package file for the installed version of a package
+fn cached_package_file(name: &str, version: &str, arch: &str) -> Option<String> {
+    cache_dirs()
+        .into_iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            let file_name = path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            !file_name.ends_with(".sig")
+                && parse_package_filename(&file_name)
+                    .is_some_and(|parsed| parsed == (name.into(), version.into(), arch.into()))
+        })
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+// Version of a package in the sync databases
+fn sync_version(name: &str) -> Option<String> {
+    let output = Command::new("pacman").args(["-Si", name]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_info_blocks(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .next()
+        .and_then(|mut info| info.remove("Version"))
+}
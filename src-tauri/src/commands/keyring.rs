@@ -0,0 +1,238 @@
+use crate::commands::cache::cache_dirs;
+use crate::commands::process::{emit_progress, run_sudo, run_sudo_step};
+use crate::commands::system::run_official_update;
+use crate::models::{KeyringKey, KeyringStatus, SignatureProblem};
+use crate::parsers::version::vercmp;
+use crate::parsers::{
+    classify_signature_error, parse_gpg_keys, parse_info_blocks, parse_package_filename,
+};
+use std::cmp::Ordering;
+use std::path::Path;
+use std::process::Command;
+
+const GNUPG_DIR: &str = "/etc/pacman.d/gnupg";
+const KEYRING_PACKAGE: &str = "archlinux-keyring";
+const PROGRESS_EVENT: &str = "keyring-progress";
+const EXPIRY_WARNING_DAYS: i64 = 90;
+
+// Recognise signature and keyring failures in an update error,
+// so the frontend can offer the keyring recovery job
+#[tauri::command]
+pub fn classify_update_error(error: String) -> Option<SignatureProblem> {
+    classify_signature_error(&error)
+}
+
+fn keyring_initialized() -> bool {
+    ["pubring.gpg", "pubring.kbx"]
+        .iter()
+        .any(|file| Path::new(GNUPG_DIR).join(file).exists())
+}
+
+fn package_version(args: &[&str]) -> Option<String> {
+    let output = Command::new("pacman").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_info_blocks(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .next()
+        .and_then(|mut info| info.remove("Version"))
+}
+
+// Key counts, validity and expiry of the pacman keyring, plus the archlinux-keyring version
+// The keyring is readable without root on most systems; pass a password if it is not
+#[tauri::command]
+pub async fn get_keyring_status(password: Option<String>) -> Result<KeyringStatus, String> {
+    let initialized = keyring_initialized();
+
+    let keys: Vec<KeyringKey> = if initialized {
+        let args = [
+            "gpg",
+            "--homedir",
+            GNUPG_DIR,
+            "--no-permission-warning",
+            "--lock-never",
+            "--batch",
+            "--with-colons",
+            "--list-keys",
+        ];
+        let output = match &password {
+            Some(password) => run_sudo(password, &args)?,
+            None => Command::new(args[0])
+                .args(&args[1..])
+                .output()
+                .map_err(|e| format!("Failed to execute gpg: {}", e))?,
+        };
+        if !output.status.success() && output.stdout.is_empty() {
+            return Err(format!(
+                "Failed to read the pacman keyring: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        parse_gpg_keys(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        Vec::new()
+    };
+
+    let count = |validities: &[&str]| {
+        keys.iter()
+            .filter(|key| validities.contains(&key.validity.as_str()))
+            .count() as u32
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let warning_until = now + EXPIRY_WARNING_DAYS * 24 * 60 * 60;
+    let expiring_soon: Vec<KeyringKey> = keys
+        .iter()
+        .filter(|key| matches!(key.validity.as_str(), "full" | "ultimate"))
+        .filter(|key| {
+            key.expires
+                .is_some_and(|expires| expires > now && expires <= warning_until)
+        })
+        .cloned()
+        .collect();
+
+    let keyring_version = package_version(&["-Qi", KEYRING_PACKAGE]);
+    let available_version = package_version(&["-Si", KEYRING_PACKAGE]);
+    let keyring_outdated = match (&keyring_version, &available_version) {
+        (Some(installed), Some(available)) => vercmp(installed, available) == Ordering::Less,
+        _ => false,
+    };
+
+    Ok(KeyringStatus {
+        initialized,
+        total_keys: keys.len() as u32,
+        trusted_keys: count(&["full", "ultimate"]),
+        marginal_keys: count(&["marginal"]),
+        expired_keys: count(&["expired"]),
+        revoked_keys: count(&["revoked"]),
+        expiring_soon,
+        keys,
+        keyring_version,
+        available_version,
+        keyring_outdated,
+    })
+}
+
+// Cached copies (and their signatures) of package files that failed the signature check
+fn cached_files(files: &[String]) -> Result<Vec<String>, String> {
+    let mut paths = Vec::new();
+    for file in files {
+        if file.contains('/') || parse_package_filename(file).is_none() {
+            return Err(format!("Invalid package file: {}", file));
+        }
+        for dir in cache_dirs() {
+            for name in [file.clone(), format!("{}.sig", file)] {
+                let path = dir.join(name);
+                if path.exists() {
+                    paths.push(path.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    Ok(paths)
+}
+
+// Recover from signature failures: upgrade archlinux-keyring on its own,
+// repopulate the keyring, drop the cached files that failed and optionally retry the update
+#[tauri::command]
+pub async fn recover_keyring(
+    files: Vec<String>,
+    retry_update: Option<bool>,
+    take_snapshot: Option<bool>,
+    allow_snapshot_failure: Option<bool>,
+    skip_news_check: Option<bool>,
+    password: String,
+    window: tauri::Window,
+) -> Result<String, String> {
+    let stale_files = cached_files(&files)?;
+
+    if !keyring_initialized() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            5,
+            ":: Initializing the pacman keyring...".to_string(),
+            false,
+        );
+        run_sudo_step(
+            &password,
+            &["pacman-key", "--init"],
+            &window,
+            PROGRESS_EVENT,
+            10,
+        )?;
+    }
+
+    // Updating only the keyring is the one partial upgrade Arch recommends
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        15,
+        format!(":: Upgrading {}...", KEYRING_PACKAGE),
+        false,
+    );
+    run_sudo_step(
+        &password,
+        &["pacman", "-Sy", "--needed", "--noconfirm", KEYRING_PACKAGE],
+        &window,
+        PROGRESS_EVENT,
+        30,
+    )?;
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        45,
+        ":: Populating the keyring...".to_string(),
+        false,
+    );
+    run_sudo_step(
+        &password,
+        &["pacman-key", "--populate"],
+        &window,
+        PROGRESS_EVENT,
+        55,
+    )?;
+
+    if !stale_files.is_empty() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            65,
+            format!(":: Removing {} cached files...", stale_files.len()),
+            false,
+        );
+        let mut args = vec!["rm", "-f", "--"];
+        args.extend(stale_files.iter().map(|path| path.as_str()));
+        run_sudo_step(&password, &args, &window, PROGRESS_EVENT, 70)?;
+    }
+
+    if retry_update.unwrap_or(false) {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            75,
+            ":: Retrying the system update...".to_string(),
+            false,
+        );
+        // Same path as update_official, so the news gate and the snapshot still apply
+        run_official_update(
+            &password,
+            take_snapshot.unwrap_or(false),
+            allow_snapshot_failure.unwrap_or(false),
+            skip_news_check.unwrap_or(false),
+            &window,
+            PROGRESS_EVENT,
+        )
+        .await?;
+    }
+
+    let message = if retry_update.unwrap_or(false) {
+        "Keyring refreshed and system updated".to_string()
+    } else {
+        "Keyring refreshed".to_string()
+    };
+    emit_progress(&window, PROGRESS_EVENT, 100, message.clone(), true);
+    Ok(message)
+}
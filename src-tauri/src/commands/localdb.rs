@@ -0,0 +1,126 @@
+use crate::parsers::{dependency_name, parse_db_entry};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub(crate) const LOCAL_DB_DIR: &str = "/var/lib/pacman/local";
+pub(crate) const SYNC_DB_DIR: &str = "/var/lib/pacman/sync";
+
+// An installed package as recorded in pacman's local database
+pub(crate) struct LocalPackage {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl LocalPackage {
+    // Another entry of this package, such as "files"
+    pub fn read_entry(&self, file: &str) -> HashMap<String, Vec<String>> {
+        std::fs::read_to_string(self.dir.join(file))
+            .map(|content| parse_db_entry(&content))
+            .unwrap_or_default()
+    }
+}
+
+// Fields of an installed package's desc entry used for dependency questions
+pub(crate) struct InstalledPackage {
+    pub name: String,
+    pub version: String,
+    pub arch: String,
+    pub description: String,
+    pub explicit: bool,
+    pub size: u64,
+    pub depends: Vec<String>,
+    pub optdepends: Vec<String>, // names only, descriptions stripped
+    pub provides: Vec<String>,   // names only, versions stripped
+}
+
+impl InstalledPackage {
+    pub fn reason(&self) -> &'static str {
+        if self.explicit {
+            "explicit"
+        } else {
+            "dependency"
+        }
+    }
+}
+
+// Read the desc entry of every installed package
+pub(crate) fn read_local_packages() -> Vec<LocalPackage> {
+    let entries = match std::fs::read_dir(LOCAL_DB_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut packages: Vec<LocalPackage> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let content = std::fs::read_to_string(dir.join("desc")).ok()?;
+            let desc = parse_db_entry(&content);
+            let name = desc.get("NAME")?.first()?.clone();
+            Some(LocalPackage { name, dir })
+        })
+        .collect();
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+// Installed packages with their desc fields parsed
+pub(crate) fn read_installed_packages() -> Vec<InstalledPackage> {
+    read_local_packages()
+        .into_iter()
+        .map(|package| {
+            let desc = package.read_entry("desc");
+            let first = |key: &str| {
+                desc.get(key)
+                    .and_then(|values| values.first())
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let names = |key: &str| -> Vec<String> {
+                desc.get(key)
+                    .into_iter()
+                    .flatten()
+                    .map(|value| {
+                        // "python-pillow: for image support", "sh", "libfoo.so=1-64"
+                        let value = value.split(':').next().unwrap_or(value);
+                        dependency_name(value).to_string()
+                    })
+                    .collect()
+            };
+
+            InstalledPackage {
+                version: first("VERSION"),
+                arch: first("ARCH"),
+                description: first("DESC"),
+                // REASON is 1 for dependencies and absent for explicitly installed packages
+                explicit: first("REASON") != "1",
+                size: first("SIZE").parse().unwrap_or(0),
+                depends: desc.get("DEPENDS").cloned().unwrap_or_default(),
+                optdepends: names("OPTDEPENDS"),
+                provides: names("PROVIDES"),
+                name: package.name,
+            }
+        })
+        .collect()
+}
+
+// Map package names and provided names to the installed packages that satisfy them
+pub(crate) fn provider_index(packages: &[InstalledPackage]) -> HashMap<String, Vec<String>> {
+    let mut index: HashMap<String, Vec<String>> = HashMap::new();
+    for package in packages {
+        index
+            .entry(package.name.clone())
+            .or_default()
+            .push(package.name.clone());
+        for provided in &package.provides {
+            if provided != &package.name {
+                index
+                    .entry(provided.clone())
+                    .or_default()
+                    .push(package.name.clone());
+            }
+        }
+    }
+    index
+}
@@ -0,0 +1,207 @@
+use crate::commands::fetch::fetch_text;
+use crate::commands::pacman_conf::check_server;
+use crate::commands::process::{emit_progress, unified_diff, write_root_file};
+use crate::models::{MirrorEntry, MirrorFilter, MirrorStatus, MirrorlistPreview, RankedMirror};
+use crate::parsers::{format_mirrorlist, parse_mirror_status, parse_mirrorlist};
+use std::time::{Duration, Instant};
+
+const MIRRORLIST: &str = "/etc/pacman.d/mirrorlist";
+const DEFAULT_STATUS_URL: &str = "https://archlinux.org/mirrors/status/json/";
+const PROGRESS_EVENT: &str = "mirror-progress";
+const DEFAULT_RANK_COUNT: usize = 20;
+const MEASURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Mirror status location, overridable with ARCHSTORE_MIRROR_STATUS_URL
+// (http(s) URL, file:// URL or plain path)
+fn status_url() -> String {
+    std::env::var("ARCHSTORE_MIRROR_STATUS_URL").unwrap_or_else(|_| DEFAULT_STATUS_URL.to_string())
+}
+
+// Servers currently in /etc/pacman.d/mirrorlist, commented-out ones included
+#[tauri::command]
+pub fn get_mirrorlist() -> Result<Vec<MirrorEntry>, String> {
+    std::fs::read_to_string(MIRRORLIST)
+        .map(|content| parse_mirrorlist(&content))
+        .map_err(|e| format!("Failed to read {}: {}", MIRRORLIST, e))
+}
+
+fn matches_filter(mirror: &MirrorStatus, filter: &MirrorFilter) -> bool {
+    // pacman cannot use rsync mirrors
+    let protocol_ok = if filter.protocols.is_empty() {
+        mirror.protocol == "https" || mirror.protocol == "http"
+    } else {
+        mirror.protocol != "rsync" && filter.protocols.contains(&mirror.protocol)
+    };
+    let country_ok = filter.countries.is_empty()
+        || filter.countries.iter().any(|country| {
+            country.eq_ignore_ascii_case(&mirror.country)
+                || country.eq_ignore_ascii_case(&mirror.country_code)
+        });
+    let completion_ok = filter
+        .min_completion
+        .is_none_or(|min| mirror.completion_pct.is_some_and(|pct| pct >= min));
+    let delay_ok = filter
+        .max_delay
+        .is_none_or(|max| mirror.delay.is_some_and(|delay| delay <= max));
+
+    mirror.active && protocol_ok && country_ok && completion_ok && delay_ok
+}
+
+async fn filtered_status(filter: &MirrorFilter) -> Result<Vec<MirrorStatus>, String> {
+    let json = fetch_text(&status_url(), "mirror status").await?;
+    let mut mirrors: Vec<MirrorStatus> = parse_mirror_status(&json)?
+        .into_iter()
+        .filter(|mirror| matches_filter(mirror, filter))
+        .collect();
+
+    // Unscored mirrors have not synced recently, so they go last
+    mirrors.sort_by(|a, b| {
+        a.score
+            .unwrap_or(f64::MAX)
+            .total_cmp(&b.score.unwrap_or(f64::MAX))
+    });
+    Ok(mirrors)
+}
+
+// Mirrors from the Arch mirror status, filtered and sorted by their status score
+#[tauri::command]
+pub async fn get_mirror_status(filter: MirrorFilter) -> Result<Vec<MirrorStatus>, String> {
+    filtered_status(&filter).await
+}
+
+fn server_line(url: &str) -> String {
+    format!("{}/$repo/os/$arch", url.trim_end_matches('/'))
+}
+
+// Latency is the time to the response headers, throughput the download rate of core.db
+async fn measure(client: &reqwest::Client, url: &str) -> Result<(u64, u64), String> {
+    let db_url = format!("{}/core/os/x86_64/core.db", url.trim_end_matches('/'));
+    let start = Instant::now();
+    let response = client
+        .get(&db_url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let latency = start.elapsed();
+
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", db_url, response.status()));
+    }
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Download failed: {}", e))?;
+    let transfer = start
+        .elapsed()
+        .saturating_sub(latency)
+        .as_secs_f64()
+        .max(0.001);
+
+    Ok((
+        latency.as_millis() as u64,
+        (body.len() as f64 / transfer) as u64,
+    ))
+}
+
+// Measure latency and throughput of the best scored mirrors matching the filter
+// Mirrors are tested one at a time so they do not compete for bandwidth
+// Results are sorted fastest first; unreachable mirrors are kept at the end with their error
+#[tauri::command]
+pub async fn rank_mirrors(
+    filter: MirrorFilter,
+    count: Option<usize>,
+    window: tauri::Window,
+) -> Result<Vec<RankedMirror>, String> {
+    let candidates: Vec<MirrorStatus> = filtered_status(&filter)
+        .await?
+        .into_iter()
+        .take(count.unwrap_or(DEFAULT_RANK_COUNT))
+        .collect();
+    if candidates.is_empty() {
+        return Err("No mirrors match the filter".to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(MEASURE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let total = candidates.len();
+    let mut ranked = Vec::with_capacity(total);
+    for (index, mirror) in candidates.into_iter().enumerate() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            (index * 100 / total) as u32,
+            format!("Testing {} ({}/{})", mirror.url, index + 1, total),
+            false,
+        );
+
+        let result = measure(&client, &mirror.url).await;
+        ranked.push(RankedMirror {
+            server: server_line(&mirror.url),
+            country: mirror.country,
+            score: mirror.score,
+            latency_ms: result.as_ref().ok().map(|(latency, _)| *latency),
+            throughput: result.as_ref().ok().map(|(_, throughput)| *throughput),
+            error: result.err(),
+        });
+    }
+
+    ranked.sort_by(|a, b| {
+        b.throughput
+            .unwrap_or(0)
+            .cmp(&a.throughput.unwrap_or(0))
+            .then(
+                a.latency_ms
+                    .unwrap_or(u64::MAX)
+                    .cmp(&b.latency_ms.unwrap_or(u64::MAX)),
+            )
+    });
+
+    let reachable = ranked
+        .iter()
+        .filter(|mirror| mirror.error.is_none())
+        .count();
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        100,
+        format!("Tested {} mirrors, {} reachable", total, reachable),
+        true,
+    );
+    Ok(ranked)
+}
+
+fn mirrorlist_content(servers: &[String]) -> Result<String, String> {
+    if servers.is_empty() {
+        return Err("The mirrorlist needs at least one server".to_string());
+    }
+    for server in servers {
+        check_server(server)?;
+    }
+    Ok(format_mirrorlist(
+        servers,
+        &chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+    ))
+}
+
+// Show the diff writing these servers (in order) would make to the mirrorlist
+#[tauri::command]
+pub fn preview_mirrorlist(servers: Vec<String>) -> Result<MirrorlistPreview, String> {
+    let content = mirrorlist_content(&servers)?;
+    let current = std::fs::read_to_string(MIRRORLIST).unwrap_or_default();
+    Ok(MirrorlistPreview {
+        diff: unified_diff(MIRRORLIST, &current, &content)?,
+        content,
+    })
+}
+
+// Write a ranked mirrorlist, keeping a backup of the previous one
+// Returns the path of the backup
+#[tauri::command]
+pub async fn save_mirrorlist(servers: Vec<String>, password: String) -> Result<String, String> {
+    let content = mirrorlist_content(&servers)?;
+    write_root_file(&password, MIRRORLIST, &content)
+}
@@ -1,14 +1,69 @@
+pub mod archive;
+pub(crate) mod aur;
+pub mod aur_build;
+pub mod aur_resolve;
+pub mod cache;
+pub mod depgraph;
+pub(crate) mod fetch;
+pub mod files;
+pub mod foreign;
+pub mod hold;
 pub mod install;
+pub mod integrity;
+pub mod keyring;
+pub(crate) mod localdb;
+pub mod mirrors;
+pub mod news;
+pub mod orphans;
+pub mod pacdiff;
 pub mod package;
+pub mod pacman_conf;
+pub(crate) mod process;
+pub mod reason;
+pub mod rebuild;
+pub mod repos;
+pub mod review;
+pub mod risk;
 pub mod search;
+pub mod snapshot;
+pub(crate) mod storage;
 pub mod system;
+pub mod vcs;
 
+pub use archive::{downgrade_package, list_archive_versions};
+pub use aur_build::{install_aur_packages, rebuild_aur_packages};
+pub use aur_resolve::get_aur_build_plan;
+pub use cache::{analyze_package_cache, clean_package_cache};
+pub use depgraph::{get_dependency_graph, get_install_chains};
+pub use files::{find_file_owner, list_package_files, search_files, sync_files_databases};
+pub use foreign::audit_foreign_packages;
+pub use hold::{get_held_packages, hold_package, unhold_package};
 pub use install::{install_package, remove_package};
+pub use integrity::{check_package_integrity, reinstall_packages};
+pub use keyring::{classify_update_error, get_keyring_status, recover_keyring};
+pub use mirrors::{
+    get_mirror_status, get_mirrorlist, preview_mirrorlist, rank_mirrors, save_mirrorlist,
+};
+pub use news::{get_arch_news, get_pending_news, mark_news_read};
+pub use orphans::{list_orphans, remove_orphans};
+pub use pacdiff::{get_config_diff, resolve_config_change, scan_config_changes};
 pub use package::{get_app_icon, get_package_info};
+pub use pacman_conf::{apply_pacman_conf_changes, get_pacman_config, preview_pacman_conf_changes};
+pub use reason::{preview_install_reason, set_install_reason};
+pub use rebuild::check_rebuilds;
+pub use repos::{
+    add_custom_repository, list_custom_repositories, remove_custom_repository,
+    search_custom_packages,
+};
+pub use review::{approve_aur_review, review_aur_package, revoke_aur_approval};
+pub use risk::get_aur_risk_report;
 pub use search::{
-    get_installed_packages, search_aur_packages, search_flatpak_packages, search_official_packages,
+    get_installed_packages, search_aur_packages, search_command, search_flatpak_packages,
+    search_official_packages,
 };
+pub use snapshot::{get_snapshot_status, list_snapshots};
 pub use system::{
     check_system_capabilities, check_updates, enable_multilib, update_aur, update_flatpak,
-    update_official, update_system,
+    update_official, update_selected, update_system,
 };
+pub use vcs::check_vcs_updates;
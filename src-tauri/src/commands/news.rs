@@ -0,0 +1,157 @@
+use crate::commands::fetch::fetch_text;
+use crate::commands::process::emit_progress;
+use crate::commands::storage::{load_state, save_state};
+use crate::models::NewsItem;
+use crate::parsers::parse_news_feed;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::process::Command;
+
+const DEFAULT_NEWS_URL: &str = "https://archlinux.org/feeds/news/";
+const NEWS_STATE_FILE: &str = "news.json";
+const PACMAN_LOG: &str = "/var/log/pacman.log";
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct NewsState {
+    read: Vec<String>,
+    last_successful_update: Option<String>, // RFC 3339
+}
+
+// Feed location, overridable with ARCHSTORE_NEWS_URL (http(s) URL, file:// URL or plain path)
+fn news_url() -> String {
+    std::env::var("ARCHSTORE_NEWS_URL").unwrap_or_else(|_| DEFAULT_NEWS_URL.to_string())
+}
+
+fn installed_package_names() -> HashSet<String> {
+    Command::new("pacman")
+        .arg("-Qq")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Start of the last full system upgrade recorded in pacman.log, as RFC 3339
+// Lines look like: [2024-05-01T12:34:56+0200] [PACMAN] starting full system upgrade
+fn last_upgrade_from_pacman_log() -> Option<String> {
+    let log = std::fs::read_to_string(PACMAN_LOG).ok()?;
+    log.lines()
+        .rev()
+        .find(|line| line.contains("starting full system upgrade"))
+        .and_then(|line| line.strip_prefix('[')?.split(']').next())
+        .and_then(|timestamp| {
+            chrono::DateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%z").ok()
+        })
+        .map(|date| date.with_timezone(&chrono::Utc).to_rfc3339())
+}
+
+async fn load_news() -> Result<Vec<NewsItem>, String> {
+    let feed = fetch_text(&news_url(), "Arch news").await?;
+    let state: NewsState = load_state(NEWS_STATE_FILE);
+    let installed = installed_package_names();
+    let last_update = state
+        .last_successful_update
+        .clone()
+        .or_else(last_upgrade_from_pacman_log);
+
+    let mut items = parse_news_feed(&feed);
+    for item in &mut items {
+        item.read = state.read.contains(&item.id);
+        item.since_last_update = last_update
+            .as_ref()
+            .is_none_or(|last_update| item.published > *last_update);
+        item.affected_packages
+            .retain(|name| installed.contains(name));
+    }
+
+    Ok(items)
+}
+
+// Fetch Arch news with read state and installed-package matches
+#[tauri::command]
+pub async fn get_arch_news() -> Result<Vec<NewsItem>, String> {
+    load_news().await
+}
+
+// Unread news published since the last successful update
+#[tauri::command]
+pub async fn get_pending_news() -> Result<Vec<NewsItem>, String> {
+    Ok(load_news()
+        .await?
+        .into_iter()
+        .filter(|item| !item.read && item.since_last_update)
+        .collect())
+}
+
+// Remember news items as read
+#[tauri::command]
+pub fn mark_news_read(ids: Vec<String>) -> Result<(), String> {
+    let mut state: NewsState = load_state(NEWS_STATE_FILE);
+    for id in ids {
+        if !state.read.contains(&id) {
+            state.read.push(id);
+        }
+    }
+    save_state(NEWS_STATE_FILE, &state)
+}
+
+// Called after a successful -Syu so older news no longer gates updates
+pub(crate) fn record_successful_update() {
+    let mut state: NewsState = load_state(NEWS_STATE_FILE);
+    state.last_successful_update = Some(chrono::Utc::now().to_rfc3339());
+    let _ = save_state(NEWS_STATE_FILE, &state);
+}
+
+// Refuse to update while unread news since the last update is pending
+// A feed that cannot be fetched does not block the update
+pub(crate) async fn check_news_before_update(
+    window: &tauri::Window,
+    event: &str,
+) -> Result<(), String> {
+    emit_progress(
+        window,
+        event,
+        12,
+        ":: Checking Arch Linux news...".to_string(),
+        false,
+    );
+
+    let pending = match get_pending_news().await {
+        Ok(pending) => pending,
+        Err(e) => {
+            emit_progress(
+                window,
+                event,
+                12,
+                format!("Could not check Arch news, continuing: {}", e),
+                false,
+            );
+            return Ok(());
+        }
+    };
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let titles: Vec<String> = pending
+        .iter()
+        .map(|item| {
+            if item.manual_intervention {
+                format!("{} (manual intervention required)", item.title)
+            } else {
+                item.title.clone()
+            }
+        })
+        .collect();
+    let message = format!(
+        "Update blocked: read the latest Arch news first: {}",
+        titles.join("; ")
+    );
+    emit_progress(window, event, 0, message.clone(), true);
+    Err(message)
+}
@@ -0,0 +1,137 @@
+use crate::commands::localdb::read_installed_packages;
+use crate::commands::process::{emit_progress, spawn_sudo, stream_child};
+use crate::models::{Orphan, OrphanReport};
+use crate::parsers::format_bytes;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+// Installed dependencies nothing requires anymore, like `pacman -Qdtq`
+// Packages that are only optional dependencies of something else are included and marked
+#[tauri::command]
+pub async fn list_orphans() -> Result<OrphanReport, String> {
+    // -Qdtt also lists packages that are only optionally required
+    let names = query_names(&["-Qdttq"])?;
+
+    let installed = read_installed_packages();
+
+    let mut optional_for: HashMap<String, Vec<String>> = HashMap::new();
+    for package in &installed {
+        for dep in package.optdepends.iter().filter(|dep| names.contains(*dep)) {
+            optional_for
+                .entry(dep.clone())
+                .or_default()
+                .push(package.name.clone());
+        }
+    }
+
+    let mut orphans: Vec<Orphan> = installed
+        .into_iter()
+        .filter(|package| names.contains(&package.name))
+        .map(|package| Orphan {
+            installed_size: format_bytes(package.size),
+            size_bytes: package.size,
+            optional_for: optional_for.remove(&package.name).unwrap_or_default(),
+            name: package.name,
+            version: package.version,
+            description: package.description,
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let total_bytes = orphans.iter().map(|orphan| orphan.size_bytes).sum();
+    Ok(OrphanReport {
+        orphans,
+        total_bytes,
+        total_size: format_bytes(total_bytes),
+    })
+}
+
+fn query_names(args: &[&str]) -> Result<HashSet<String>, String> {
+    let output = Command::new("pacman")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    // pacman exits with 1 when the query matches nothing
+    if !output.status.success() && !output.stderr.is_empty() {
+        return Err(format!(
+            "pacman query failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+// Remove the selected orphans together with their own unneeded dependencies
+// Progress is streamed as "remove-progress" like remove_package
+#[tauri::command]
+pub async fn remove_orphans(
+    packages: Vec<String>,
+    password: String,
+    window: tauri::Window,
+) -> Result<(), String> {
+    let event = "remove-progress";
+
+    if packages.is_empty() {
+        return Err("No packages selected".to_string());
+    }
+
+    // Only remove packages that are still orphans
+    let orphans = query_names(&["-Qdttq"])?;
+    if let Some(name) = packages.iter().find(|name| !orphans.contains(*name)) {
+        return Err(format!("{} is not an orphaned package", name));
+    }
+
+    emit_progress(
+        &window,
+        event,
+        10,
+        format!("Removing {} orphaned packages...", packages.len()),
+        false,
+    );
+
+    let mut args = vec!["pacman", "-Rns", "--noconfirm"];
+    args.extend(packages.iter().map(|name| name.as_str()));
+
+    let result =
+        spawn_sudo(&password, &args).and_then(|child| stream_child(child, &window, event, 50));
+
+    match result {
+        Ok(output) if output.status.success() => {
+            emit_progress(
+                &window,
+                event,
+                100,
+                "Removal completed successfully!".to_string(),
+                true,
+            );
+            Ok(())
+        }
+        Ok(output) => {
+            let error = output.error_message();
+            emit_progress(
+                &window,
+                event,
+                0,
+                format!("Removal failed: {}", error),
+                true,
+            );
+            Err(format!("Removal failed: {}", error))
+        }
+        Err(e) => {
+            emit_progress(
+                &window,
+                event,
+                0,
+                format!("Failed to execute removal: {}", e),
+                true,
+            );
+            Err(format!("Failed to execute removal: {}", e))
+        }
+    }
+}
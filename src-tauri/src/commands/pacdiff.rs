@@ -0,0 +1,204 @@
+use crate::commands::localdb::read_local_packages;
+use crate::commands::process::run_sudo;
+use crate::commands::storage::PrivateDir;
+use crate::models::ConfigFileChange;
+use crate::parsers::parse_backup_files;
+use std::path::Path;
+use std::process::Command;
+
+// Find .pacnew and .pacsave files next to the backup files pacman tracks
+#[tauri::command]
+pub async fn scan_config_changes() -> Result<Vec<ConfigFileChange>, String> {
+    let mut changes = Vec::new();
+
+    for package in read_local_packages() {
+        let files = package.read_entry("files");
+        for path in parse_backup_files(&files) {
+            changes.extend(find_candidates(&path, &package.name));
+        }
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path).then(a.new_path.cmp(&b.new_path)));
+    changes.dedup_by(|a, b| a.new_path == b.new_path);
+    Ok(changes)
+}
+
+// path.pacnew, path.pacsave and numbered path.pacsave.N siblings
+fn find_candidates(path: &str, package: &str) -> Vec<ConfigFileChange> {
+    let live = Path::new(path);
+    let (parent, file_name) = match (live.parent(), live.file_name()) {
+        (Some(parent), Some(file_name)) => (parent, file_name.to_string_lossy().to_string()),
+        _ => return Vec::new(),
+    };
+
+    let pacnew = format!("{}.pacnew", file_name);
+    let pacsave = format!("{}.pacsave", file_name);
+    let pacsave_numbered = format!("{}.pacsave.", file_name);
+
+    let entries = match std::fs::read_dir(parent) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let kind = if name == pacnew {
+                "pacnew"
+            } else if name == pacsave || name.starts_with(&pacsave_numbered) {
+                "pacsave"
+            } else {
+                return None;
+            };
+
+            let modified = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .map(|time| {
+                    chrono::DateTime::<chrono::Local>::from(time)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+
+            Some(ConfigFileChange {
+                path: path.to_string(),
+                new_path: entry.path().to_string_lossy().to_string(),
+                kind: kind.to_string(),
+                package: package.to_string(),
+                live_exists: live.exists(),
+                modified,
+            })
+        })
+        .collect()
+}
+
+// Only operate on files the scanner reported, never on arbitrary paths
+async fn find_change(path: &str, new_path: &str) -> Result<ConfigFileChange, String> {
+    scan_config_changes()
+        .await?
+        .into_iter()
+        .find(|change| change.path == path && change.new_path == new_path)
+        .ok_or_else(|| format!("{} is not a pending .pacnew/.pacsave file", new_path))
+}
+
+// Unified diff between the live file and its .pacnew/.pacsave
+// Files that are not world-readable are diffed through sudo when a password is given
+#[tauri::command]
+pub async fn get_config_diff(
+    path: String,
+    new_path: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    let change = find_change(&path, &new_path).await?;
+    let live = if change.live_exists {
+        change.path.as_str()
+    } else {
+        "/dev/null"
+    };
+
+    let args = [
+        "diff",
+        "-u",
+        "--label",
+        &change.path,
+        "--label",
+        &change.new_path,
+        live,
+        &change.new_path,
+    ];
+    let readable = std::fs::File::open(&change.new_path).is_ok()
+        && (!change.live_exists || std::fs::File::open(&change.path).is_ok());
+
+    let output = match (&password, readable) {
+        (_, true) => Command::new(args[0])
+            .args(&args[1..])
+            .output()
+            .map_err(|e| format!("Failed to run diff: {}", e))?,
+        (Some(password), false) => run_sudo(password, &args)?,
+        (None, false) => {
+            return Err(format!(
+                "{} is not readable without administrator privileges",
+                change.path
+            ));
+        }
+    };
+
+    // diff exits with 1 when the files differ
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => Err(format!(
+            "diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+// Resolve a .pacnew/.pacsave file
+// "keep": keep the live file and delete the new one
+// "replace": replace the live file with the new one
+// "merge": write merged_content to the live file and delete the new one
+#[tauri::command]
+pub async fn resolve_config_change(
+    path: String,
+    new_path: String,
+    action: String,
+    merged_content: Option<String>,
+    password: String,
+) -> Result<String, String> {
+    let change = find_change(&path, &new_path).await?;
+
+    match action.as_str() {
+        "keep" => {
+            let output = run_sudo(&password, &["rm", "-f", "--", &change.new_path])?;
+            check_output(&output, "remove", &change.new_path)?;
+            Ok(format!("Kept {}, removed {}", change.path, change.new_path))
+        }
+        "replace" => {
+            let output = run_sudo(
+                &password,
+                &["mv", "-f", "--", &change.new_path, &change.path],
+            )?;
+            check_output(&output, "replace", &change.path)?;
+            Ok(format!("Replaced {} with {}", change.path, change.new_path))
+        }
+        "merge" => {
+            let content = merged_content
+                .ok_or_else(|| "Merged content is required for a merge".to_string())?;
+
+            // Stage the merged file privately, then copy it over the live file
+            // cp keeps the owner and mode of an existing destination
+            let staging_dir = PrivateDir::create("merge")?;
+            let staging = staging_dir
+                .write_file("merged", content.as_bytes())
+                .map_err(|e| format!("Failed to stage merged file: {}", e))?;
+            let staging_path = staging.to_string_lossy().to_string();
+
+            run_sudo(&password, &["cp", "--", &staging_path, &change.path])
+                .and_then(|output| check_output(&output, "write", &change.path))?;
+            drop(staging_dir);
+
+            let output = run_sudo(&password, &["rm", "-f", "--", &change.new_path])?;
+            check_output(&output, "remove", &change.new_path)?;
+            Ok(format!(
+                "Saved merged {}, removed {}",
+                change.path, change.new_path
+            ))
+        }
+        _ => Err(format!("Unknown action: {}", action)),
+    }
+}
+
+fn check_output(output: &std::process::Output, action: &str, path: &str) -> Result<(), String> {
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to {} {}: {}",
+            action,
+            path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::commands::aur::{aur_info, aur_package, foreign_packages};
 use crate::models::Package;
 use crate::parsers::parse_package_info;
 use std::path::PathBuf;
@@ -6,26 +7,16 @@ use std::process::Command;
 // Get package details
 #[tauri::command]
 pub async fn get_package_info(package_name: String, source: String) -> Result<Package, String> {
+    if source == "aur" {
+        return aur_package_info(&package_name).await;
+    }
+
     let output = match source.as_str() {
-        "official" => Command::new("pacman")
+        // Custom repositories are queried like the official ones
+        "official" | "custom" => Command::new("pacman")
             .args(&["-Si", &package_name])
             .output()
             .map_err(|e| e.to_string())?,
-        "aur" => {
-            // Try yay first, fall back to paru
-            let helper = if Command::new("yay").arg("--version").output().is_ok() {
-                "yay"
-            } else if Command::new("paru").arg("--version").output().is_ok() {
-                "paru"
-            } else {
-                return Err("No AUR helper found. Please install yay or paru.".to_string());
-            };
-
-            Command::new(helper)
-                .args(&["-Si", &package_name])
-                .output()
-                .map_err(|e| e.to_string())?
-        }
         "flatpak" => Command::new("flatpak")
             .args(&["info", &package_name])
             .output()
@@ -42,7 +33,7 @@ pub async fn get_package_info(package_name: String, source: String) -> Result<Pa
 
     // Check if package is installed
     package.installed = match source.as_str() {
-        "official" => {
+        "official" | "custom" => {
             if let Ok(check) = Command::new("pacman")
                 .args(&["-Qs", &format!("^{}$", package_name)])
                 .output()
@@ -52,32 +43,6 @@ pub async fn get_package_info(package_name: String, source: String) -> Result<Pa
                 false
             }
         }
-        "aur" => {
-            // Use AUR helper -Ss to check if package is from AUR and installed
-            // We need to verify it's actually from aur/ not extra/ or other repos
-            let helper = if Command::new("yay").arg("--version").output().is_ok() {
-                "yay"
-            } else if Command::new("paru").arg("--version").output().is_ok() {
-                "paru"
-            } else {
-                return Ok(package);
-            };
-
-            if let Ok(check) = Command::new(helper).args(&["-Ss", &package_name]).output() {
-                if check.status.success() {
-                    let output_str = String::from_utf8_lossy(&check.stdout);
-                    // Look for lines starting with "aur/" and containing the package name
-                    output_str.lines().any(|line| {
-                        line.starts_with(&format!("aur/{}", package_name))
-                            && line.to_lowercase().contains("[installed]")
-                    })
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
         "flatpak" => {
             if let Ok(check) = Command::new("flatpak").args(&["list", "--app"]).output() {
                 if check.status.success() {
@@ -96,6 +61,19 @@ pub async fn get_package_info(package_name: String, source: String) -> Result<Pa
     Ok(package)
 }
 
+// AUR details come from the RPC; installed means present as a foreign package
+async fn aur_package_info(package_name: &str) -> Result<Package, String> {
+    let info = aur_info(&[package_name.to_string()])
+        .await?
+        .into_iter()
+        .find(|info| info.name == package_name)
+        .ok_or_else(|| format!("{} was not found in the AUR", package_name))?;
+    let installed = foreign_packages()
+        .iter()
+        .any(|(name, _)| name == package_name);
+    Ok(aur_package(&info, installed))
+}
+
 // Get app icon path from system
 #[tauri::command]
 pub async fn get_app_icon(app_name: String) -> Result<String, String> {
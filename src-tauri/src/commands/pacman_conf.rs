@@ -0,0 +1,328 @@
+use crate::commands::hold::glob_match;
+use crate::commands::process::{unified_diff, write_root_file};
+use crate::models::{IncludeFile, PacmanConfEdit, PacmanConfPreview, PacmanConfig, Repository};
+use crate::parsers::PacmanConf;
+use std::path::Path;
+
+pub(crate) const PACMAN_CONF: &str = "/etc/pacman.conf";
+
+// Bare options that can be switched on and off
+const FLAG_OPTIONS: [&str; 6] = [
+    "Color",
+    "VerbosePkgLists",
+    "CheckSpace",
+    "NoProgressBar",
+    "ILoveCandy",
+    "DisableDownloadTimeout",
+];
+
+pub(crate) fn read_pacman_conf() -> Result<PacmanConf, String> {
+    std::fs::read_to_string(PACMAN_CONF)
+        .map(|content| PacmanConf::parse(&content))
+        .map_err(|e| format!("Failed to read {}: {}", PACMAN_CONF, e))
+}
+
+// [options] values, including those of files Include'd from [options] (such as drop-in
+// directories), which pacman reads as if they were written in place
+// Only pacman.conf itself is edited; values from Include'd files are read-only
+pub(crate) fn option_values(conf: &PacmanConf, key: &str) -> Vec<String> {
+    let mut values = conf.values("options", key);
+    for pattern in conf.values("options", "Include") {
+        for path in include_paths(&pattern) {
+            if let Ok(content) = std::fs::read_to_string(&path) {
+                values.extend(
+                    PacmanConf::parse(&format!("[options]\n{}", content)).values("options", key),
+                );
+            }
+        }
+    }
+    values
+}
+
+// Space-separated list directives such as IgnorePkg, see option_values
+pub(crate) fn option_list(conf: &PacmanConf, key: &str) -> Vec<String> {
+    option_values(conf, key)
+        .iter()
+        .flat_map(|value| value.split_whitespace().map(|item| item.to_string()))
+        .collect()
+}
+
+// Include accepts a glob; * in the file name is expanded in sorted order like glob(3)
+fn include_paths(pattern: &str) -> Vec<String> {
+    let path = Path::new(pattern);
+    let (Some(dir), Some(file_pattern)) = (path.parent(), path.file_name()) else {
+        return Vec::new();
+    };
+    let file_pattern = file_pattern.to_string_lossy();
+    if !file_pattern.contains('*') {
+        return vec![pattern.to_string()];
+    }
+
+    let mut paths: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| glob_match(&file_pattern, &entry.file_name().to_string_lossy()))
+                .map(|entry| entry.path().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+// Write pacman.conf back through sudo, keeping a backup of the previous version
+pub(crate) fn save_pacman_conf(password: &str, conf: &PacmanConf) -> Result<String, String> {
+    write_root_file(password, PACMAN_CONF, &conf.to_string())
+}
+
+// Servers of an Include'd file such as /etc/pacman.d/mirrorlist
+fn read_include(path: &str) -> IncludeFile {
+    // Mirrorlists have no section header, so read them as the body of one
+    let servers = std::fs::read_to_string(path)
+        .map(|content| {
+            PacmanConf::parse(&format!("[include]\n{}", content)).values("include", "Server")
+        })
+        .unwrap_or_default();
+
+    IncludeFile {
+        path: path.to_string(),
+        servers,
+    }
+}
+
+fn repository(conf: &PacmanConf, name: &str, enabled: bool) -> Repository {
+    Repository {
+        name: name.to_string(),
+        enabled,
+        sig_level: conf.values(name, "SigLevel").into_iter().next(),
+        servers: conf.values(name, "Server"),
+        includes: conf
+            .values(name, "Include")
+            .iter()
+            .map(|path| read_include(path))
+            .collect(),
+    }
+}
+
+pub(crate) fn pacman_config(conf: &PacmanConf) -> PacmanConfig {
+    let first = |key: &str| option_values(conf, key).into_iter().next();
+
+    PacmanConfig {
+        parallel_downloads: first("ParallelDownloads").and_then(|value| value.parse().ok()),
+        color: first("Color").is_some(),
+        verbose_pkg_lists: first("VerbosePkgLists").is_some(),
+        check_space: first("CheckSpace").is_some(),
+        sig_level: first("SigLevel"),
+        repositories: conf
+            .repositories()
+            .into_iter()
+            .map(|(name, enabled)| repository(conf, &name, enabled))
+            .collect(),
+    }
+}
+
+// Current pacman.conf options and repositories, including Include'd server lists
+#[tauri::command]
+pub async fn get_pacman_config() -> Result<PacmanConfig, String> {
+    Ok(pacman_config(&read_pacman_conf()?))
+}
+
+// Repository and section names as pacman accepts them
+pub(crate) fn is_valid_repo_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+}
+
+// Values end up on a single line of pacman.conf
+fn check_value(value: &str) -> Result<(), String> {
+    if value.contains(['\n', '\r', '#']) || value.trim().is_empty() {
+        Err(format!("Invalid value: {}", value))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn check_server(server: &str) -> Result<(), String> {
+    check_value(server)?;
+    if ["http://", "https://", "ftp://", "file://"]
+        .iter()
+        .any(|scheme| server.starts_with(scheme))
+    {
+        Ok(())
+    } else {
+        Err(format!("Unsupported server URL: {}", server))
+    }
+}
+
+fn repo_name(edit: &PacmanConfEdit) -> Result<&str, String> {
+    match edit.repo.as_deref() {
+        Some(repo) if is_valid_repo_name(repo) && repo != "options" => Ok(repo),
+        Some(repo) => Err(format!("Invalid repository name: {}", repo)),
+        None => Err("A repository is required".to_string()),
+    }
+}
+
+fn enabled_repo<'a>(conf: &PacmanConf, repo: Option<&'a String>) -> Result<&'a str, String> {
+    let repo = repo.ok_or_else(|| "A repository is required".to_string())?;
+    if !is_valid_repo_name(repo) {
+        return Err(format!("Invalid repository name: {}", repo));
+    }
+    match conf.repositories().iter().find(|(name, _)| name == repo) {
+        Some((_, true)) => Ok(repo),
+        Some((_, false)) => Err(format!("Enable [{}] before editing it", repo)),
+        None => Err(format!("Repository [{}] not found", repo)),
+    }
+}
+
+// Apply one edit
+// "set_repo_enabled": repo, enabled
+// "remove_repo": repo
+// "set_sig_level": repo (none for [options]), value (none to comment it out)
+// "set_servers": repo, values
+// "set_option": key, value (ParallelDownloads) or enabled (Color, VerbosePkgLists, ...)
+fn apply_edit(conf: &mut PacmanConf, edit: &PacmanConfEdit) -> Result<(), String> {
+    match edit.action.as_str() {
+        "set_repo_enabled" => {
+            let repo = repo_name(edit)?;
+            let enabled = edit.enabled.unwrap_or(true);
+            if !conf.set_section_enabled(repo, enabled) {
+                return Err(format!("Repository [{}] not found", repo));
+            }
+            Ok(())
+        }
+        "remove_repo" => {
+            let repo = repo_name(edit)?;
+            if !conf.remove_section(repo) {
+                return Err(format!("Repository [{}] not found", repo));
+            }
+            Ok(())
+        }
+        "set_sig_level" => {
+            let section = match &edit.repo {
+                Some(_) => enabled_repo(conf, edit.repo.as_ref())?,
+                None => "options",
+            };
+            if let Some(value) = &edit.value {
+                check_value(value)?;
+            }
+            conf.set_directive(
+                section,
+                "SigLevel",
+                edit.value.as_deref(),
+                edit.value.is_none(),
+            );
+            Ok(())
+        }
+        "set_servers" => {
+            let repo = enabled_repo(conf, edit.repo.as_ref())?;
+            for server in &edit.values {
+                check_server(server)?;
+            }
+            conf.set_values(repo, "Server", &edit.values);
+            Ok(())
+        }
+        "set_option" => {
+            let key = edit
+                .key
+                .as_deref()
+                .ok_or_else(|| "An option name is required".to_string())?;
+            let enabled = edit.enabled.unwrap_or(true);
+
+            if key == "ParallelDownloads" {
+                let value = match (&edit.value, enabled) {
+                    (Some(value), true) => Some(value.as_str()),
+                    (None, true) => return Err("ParallelDownloads needs a value".to_string()),
+                    (_, false) => None,
+                };
+                if let Some(value) = value {
+                    value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or_else(|| format!("Invalid ParallelDownloads value: {}", value))?;
+                }
+                conf.set_directive("options", key, value, !enabled);
+                Ok(())
+            } else if FLAG_OPTIONS.contains(&key) {
+                conf.set_directive("options", key, None, !enabled);
+                Ok(())
+            } else {
+                Err(format!("Unsupported option: {}", key))
+            }
+        }
+        _ => Err(format!("Unknown action: {}", edit.action)),
+    }
+}
+
+fn edited_conf(edits: &[PacmanConfEdit]) -> Result<(PacmanConf, PacmanConf), String> {
+    let original = read_pacman_conf()?;
+    let mut conf = original.clone();
+    for edit in edits {
+        apply_edit(&mut conf, edit)?;
+    }
+    Ok((original, conf))
+}
+
+// Show the diff a set of edits would make to pacman.conf
+#[tauri::command]
+pub async fn preview_pacman_conf_changes(
+    edits: Vec<PacmanConfEdit>,
+) -> Result<PacmanConfPreview, String> {
+    let (original, conf) = edited_conf(&edits)?;
+    let content = conf.to_string();
+    Ok(PacmanConfPreview {
+        diff: unified_diff(PACMAN_CONF, &original.to_string(), &content)?,
+        config: pacman_config(&conf),
+        content,
+    })
+}
+
+// Write the edits to pacman.conf atomically, keeping a backup
+// Returns the path of the backup
+#[tauri::command]
+pub async fn apply_pacman_conf_changes(
+    edits: Vec<PacmanConfEdit>,
+    password: String,
+) -> Result<String, String> {
+    let (original, conf) = edited_conf(&edits)?;
+    if original.to_string() == conf.to_string() {
+        return Err("No changes to write".to_string());
+    }
+    save_pacman_conf(&password, &conf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::storage::PrivateDir;
+
+    #[test]
+    fn option_values_follow_include_globs() {
+        let dir = PrivateDir::create("pacman-conf-test").unwrap();
+        std::fs::create_dir(dir.path().join("conf.d")).unwrap();
+        for (name, content) in [
+            ("20-ignore.conf", "IgnorePkg = mesa\n"),
+            ("10-ignore.conf", "IgnorePkg = linux linux-headers\n"),
+            ("notes.txt", "IgnorePkg = never\n"),
+        ] {
+            std::fs::write(dir.path().join("conf.d").join(name), content).unwrap();
+        }
+        let single = dir
+            .write_file("extra.conf", b"IgnorePkg = firefox\n")
+            .unwrap();
+
+        let conf = PacmanConf::parse(&format!(
+            "[options]\nIgnorePkg = glibc\nInclude = {}/conf.d/*.conf\nInclude = {}\n\n[core]\nInclude = /etc/pacman.d/mirrorlist\n",
+            dir.path().display(),
+            single.display()
+        ));
+        assert_eq!(
+            option_list(&conf, "IgnorePkg"),
+            vec!["glibc", "linux", "linux-headers", "mesa", "firefox"]
+        );
+    }
+}
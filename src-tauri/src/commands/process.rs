@@ -0,0 +1,229 @@
+use crate::commands::storage::PrivateDir;
+use crate::models::InstallProgress;
+use crate::parsers::classify_signature_error;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::thread;
+use tauri::Emitter;
+
+// Error text for a failed pacman run, prefixed with an explanation when the
+// failure is a signature or keyring problem
+pub(crate) fn describe_pacman_failure(error: &str) -> String {
+    match classify_signature_error(error) {
+        Some(problem) => format!("{}\n{}", problem.summary, error),
+        None => error.to_string(),
+    }
+}
+
+// Output collected from a child process whose lines were streamed to the frontend
+pub(crate) struct StreamedOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl StreamedOutput {
+    // Best available error text: stderr first, stdout as a fallback
+    pub fn error_message(&self) -> String {
+        if !self.stderr.trim().is_empty() {
+            self.stderr.trim().to_string()
+        } else if !self.stdout.trim().is_empty() {
+            self.stdout.trim().to_string()
+        } else {
+            format!("Command exited with {}", self.status)
+        }
+    }
+}
+
+// Spawn a command through sudo, feeding the password on stdin
+pub(crate) fn spawn_sudo(password: &str, args: &[&str]) -> Result<Child, String> {
+    let mut child = Command::new("sudo")
+        .arg("-S")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn sudo: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{}", password).map_err(|e| format!("Failed to write password: {}", e))?;
+    }
+
+    Ok(child)
+}
+
+// Run a command through sudo and wait for it without streaming
+pub(crate) fn run_sudo(password: &str, args: &[&str]) -> Result<Output, String> {
+    spawn_sudo(password, args)?
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for sudo: {}", e))
+}
+
+// Stream stdout and stderr of a child line by line as progress events, then wait for it
+pub(crate) fn stream_child(
+    mut child: Child,
+    window: &tauri::Window,
+    event: &str,
+    percentage: u32,
+) -> Result<StreamedOutput, String> {
+    let stdout_handle = child
+        .stdout
+        .take()
+        .map(|stdout| spawn_line_reader(stdout, window.clone(), event.to_string(), percentage));
+    let stderr_handle = child
+        .stderr
+        .take()
+        .map(|stderr| spawn_line_reader(stderr, window.clone(), event.to_string(), percentage));
+
+    let stdout = stdout_handle
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    let stderr = stderr_handle
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for process: {}", e))?;
+
+    Ok(StreamedOutput {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+// One privileged step of a multi-step job: stream it through sudo and, on failure,
+// finish the job with "<command> failed: <error>" and return that message
+pub(crate) fn run_sudo_step(
+    password: &str,
+    args: &[&str],
+    window: &tauri::Window,
+    event: &str,
+    percentage: u32,
+) -> Result<(), String> {
+    let output = spawn_sudo(password, args)
+        .and_then(|child| stream_child(child, window, event, percentage))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "{} failed: {}",
+        args.join(" "),
+        describe_pacman_failure(&output.error_message())
+    );
+    emit_progress(window, event, 0, message.clone(), true);
+    Err(message)
+}
+
+fn spawn_line_reader<R: std::io::Read + Send + 'static>(
+    reader: R,
+    window: tauri::Window,
+    event: String,
+    percentage: u32,
+) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut collected = String::new();
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            if line.trim().is_empty() || line.contains("[sudo] password") {
+                continue;
+            }
+            collected.push_str(&line);
+            collected.push('\n');
+            let _ = window.emit(
+                &event,
+                InstallProgress {
+                    percentage,
+                    message: line,
+                    completed: false,
+                },
+            );
+        }
+        collected
+    })
+}
+
+// Emit a single progress event
+pub(crate) fn emit_progress(
+    window: &tauri::Window,
+    event: &str,
+    percentage: u32,
+    message: String,
+    completed: bool,
+) {
+    let _ = window.emit(
+        event,
+        InstallProgress {
+            percentage,
+            message,
+            completed,
+        },
+    );
+}
+
+// Identifier for a privileged job, used to label snapshots and logs
+// Nanoseconds keep jobs started within the same second apart (snapshot paths use the ID)
+pub(crate) fn new_job_id() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S-%9f").to_string()
+}
+
+// Replace a root-owned file atomically through sudo
+// The previous version is kept as <path>.archstore-bak, whose path is returned
+pub(crate) fn write_root_file(password: &str, path: &str, content: &str) -> Result<String, String> {
+    let staging_dir = PrivateDir::create("root-file")?;
+    let staging = staging_dir
+        .write_file("staged", content.as_bytes())
+        .map_err(|e| format!("Failed to stage {}: {}", path, e))?;
+    let staging_path = staging.to_string_lossy().to_string();
+
+    // Copy next to the target first so the final rename is atomic
+    let script = r#"set -e
+target="$1"
+staged="$2"
+install -m 644 -o root -g root -- "$staged" "$target.archstore-new"
+if [ -e "$target" ]; then
+    cp -a -- "$target" "$target.archstore-bak"
+    chmod --reference="$target" -- "$target.archstore-new"
+    chown --reference="$target" -- "$target.archstore-new"
+fi
+mv -f -- "$target.archstore-new" "$target""#;
+
+    let output = run_sudo(password, &["sh", "-c", script, "sh", path, &staging_path])?;
+    drop(staging_dir);
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to write {}: {}",
+            path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(format!("{}.archstore-bak", path))
+}
+
+// Unified diff between the current and the proposed content of a file, for previews
+pub(crate) fn unified_diff(path: &str, old: &str, new: &str) -> Result<String, String> {
+    let staging_dir = PrivateDir::create("diff")?;
+    let old_path = staging_dir.write_file("old", old.as_bytes())?;
+    let new_path = staging_dir.write_file("new", new.as_bytes())?;
+
+    let output = Command::new("diff")
+        .arg("-u")
+        .args(["--label", path, "--label", path])
+        .arg(&old_path)
+        .arg(&new_path)
+        .output()
+        .map_err(|e| format!("Failed to run diff: {}", e))?;
+
+    // diff exits with 1 when the files differ
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        _ => Err(format!(
+            "diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
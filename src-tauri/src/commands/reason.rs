@@ -0,0 +1,104 @@
+use crate::commands::localdb::{InstalledPackage, provider_index, read_installed_packages};
+use crate::commands::process::run_sudo;
+use crate::models::InstallReasonChange;
+use crate::parsers::dependency_name;
+use std::collections::{HashMap, HashSet};
+
+// Show what marking packages as dependencies or explicitly installed would change
+// reason is "dependency" (pacman -D --asdeps) or "explicit" (pacman -D --asexplicit)
+#[tauri::command]
+pub async fn preview_install_reason(
+    packages: Vec<String>,
+    reason: String,
+) -> Result<InstallReasonChange, String> {
+    let installed = read_installed_packages();
+    let explicit = reason_flag(&reason)? == "--asexplicit";
+
+    let known: HashSet<&str> = installed
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect();
+    if let Some(name) = packages.iter().find(|name| !known.contains(name.as_str())) {
+        return Err(format!("{} is not installed", name));
+    }
+
+    let before = orphans(&installed, &HashMap::new());
+    let overrides: HashMap<&str, bool> = packages
+        .iter()
+        .map(|name| (name.as_str(), explicit))
+        .collect();
+    let after = orphans(&installed, &overrides);
+
+    let mut orphaned: Vec<String> = after.difference(&before).cloned().collect();
+    let mut no_longer_orphaned: Vec<String> = before.difference(&after).cloned().collect();
+    orphaned.sort();
+    no_longer_orphaned.sort();
+
+    Ok(InstallReasonChange {
+        packages,
+        reason,
+        orphaned,
+        no_longer_orphaned,
+    })
+}
+
+// Change the install reason of several packages at once
+#[tauri::command]
+pub async fn set_install_reason(
+    packages: Vec<String>,
+    reason: String,
+    password: String,
+) -> Result<InstallReasonChange, String> {
+    if packages.is_empty() {
+        return Err("No packages selected".to_string());
+    }
+    // Also validates that every package is installed
+    let change = preview_install_reason(packages, reason).await?;
+    let flag = reason_flag(&change.reason)?;
+
+    let mut args = vec!["pacman", "-D", flag];
+    args.extend(change.packages.iter().map(|name| name.as_str()));
+    let output = run_sudo(&password, &args)?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to change install reason: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(change)
+}
+
+fn reason_flag(reason: &str) -> Result<&'static str, String> {
+    match reason {
+        "dependency" => Ok("--asdeps"),
+        "explicit" => Ok("--asexplicit"),
+        _ => Err(format!("Unknown install reason: {}", reason)),
+    }
+}
+
+// Packages `pacman -Qdtt` would list (optdepends do not keep a package), matching the
+// orphans view, with some install reasons overridden
+fn orphans(installed: &[InstalledPackage], overrides: &HashMap<&str, bool>) -> HashSet<String> {
+    let providers = provider_index(installed);
+    let required: HashSet<&str> = installed
+        .iter()
+        .flat_map(|package| package.depends.iter())
+        .filter_map(|dep| providers.get(dependency_name(dep)))
+        .flatten()
+        .map(|name| name.as_str())
+        .collect();
+
+    installed
+        .iter()
+        .filter(|package| {
+            let explicit = overrides
+                .get(package.name.as_str())
+                .copied()
+                .unwrap_or(package.explicit);
+            !explicit && !required.contains(package.name.as_str())
+        })
+        .map(|package| package.name.clone())
+        .collect()
+}
@@ -0,0 +1,171 @@
+use crate::commands::aur::foreign_packages;
+use crate::commands::localdb::{read_installed_packages, read_local_packages};
+use crate::models::RebuildCandidate;
+use crate::parsers::{ElfClass, parse_ldconfig_cache, parse_readelf_dynamic};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+// Files per readelf call
+const READELF_BATCH_SIZE: usize = 200;
+// Searched by the dynamic linker even when its cache is out of date, per ELF class
+const LIBRARY_DIRS: [(ElfClass, &str); 2] = [
+    (ElfClass::Elf64, "/usr/lib"),
+    (ElfClass::Elf32, "/usr/lib32"),
+];
+
+// Regular files starting with the ELF magic; symlinks point at files checked on their own
+fn is_elf(path: &Path) -> bool {
+    if !path
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.file_type().is_file())
+    {
+        return false;
+    }
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok()
+        && &magic == b"\x7fELF"
+}
+
+// NEEDED libraries of ELF files that neither the linker cache, the library directories, the
+// file's RPATH/RUNPATH nor the package itself provide: (file, missing sonames)
+// Cache entries and library directories only count for the file's ELF class, so a 64-bit
+// program is not satisfied by the lib32 build of a library
+fn missing_libraries(
+    files: &[String],
+    cache: &HashSet<(String, ElfClass)>,
+    bundled: &HashSet<String>,
+) -> Vec<(String, Vec<String>)> {
+    let mut missing = Vec::new();
+
+    for batch in files.chunks(READELF_BATCH_SIZE) {
+        let Ok(output) = Command::new("readelf")
+            .args(["-h", "-d", "--wide"])
+            .args(batch)
+            .output()
+        else {
+            continue;
+        };
+        let output = String::from_utf8_lossy(&output.stdout);
+
+        for info in parse_readelf_dynamic(&output, &batch[0]) {
+            // An unknown class accepts libraries of either class
+            let matches_class = |class: ElfClass| info.class.is_none_or(|own| own == class);
+            let origin = Path::new(&info.file)
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let dirs: Vec<String> = LIBRARY_DIRS
+                .iter()
+                .filter(|(class, _)| matches_class(*class))
+                .map(|(_, dir)| dir.to_string())
+                .chain(info.search_dirs.iter().map(|dir| {
+                    dir.replace("${ORIGIN}", &origin)
+                        .replace("$ORIGIN", &origin)
+                }))
+                .collect();
+
+            let libraries: Vec<String> = info
+                .needed
+                .into_iter()
+                .filter(|soname| {
+                    ![ElfClass::Elf32, ElfClass::Elf64].into_iter().any(|class| {
+                        matches_class(class) && cache.contains(&(soname.clone(), class))
+                    }) && !bundled.contains(soname)
+                        && !dirs.iter().any(|dir| Path::new(dir).join(soname).exists())
+                })
+                .collect();
+            if !libraries.is_empty() {
+                missing.push((info.file, libraries));
+            }
+        }
+    }
+
+    missing
+}
+
+// "3.12" for python 3.12.4-1
+fn python_version(version: &str) -> Option<String> {
+    let mut parts = version.split(['.', '-']);
+    Some(format!("{}.{}", parts.next()?, parts.next()?))
+}
+
+// Foreign packages that probably need a rebuild: ELF files linking libraries that are no
+// longer installed (soname bumps of icu, boost, ...) or modules left in an old python's
+// site-packages after a python update
+#[tauri::command]
+pub async fn check_rebuilds() -> Result<Vec<RebuildCandidate>, String> {
+    let foreign: HashMap<String, String> = foreign_packages().into_iter().collect();
+    if foreign.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cache = Command::new("ldconfig")
+        .arg("-p")
+        .output()
+        .map(|output| parse_ldconfig_cache(&String::from_utf8_lossy(&output.stdout)))
+        .map_err(|e| format!("Failed to execute ldconfig: {}", e))?;
+    let python = read_installed_packages()
+        .into_iter()
+        .find(|package| package.name == "python")
+        .and_then(|package| python_version(&package.version));
+    let site_packages = Regex::new(r"^usr/lib/python(\d+\.\d+)/site-packages/").unwrap();
+
+    let mut candidates = Vec::new();
+    for package in read_local_packages()
+        .into_iter()
+        .filter(|package| foreign.contains_key(&package.name))
+    {
+        let files = package
+            .read_entry("files")
+            .remove("FILES")
+            .unwrap_or_default();
+        let paths: Vec<String> = files
+            .iter()
+            .filter(|path| !path.ends_with('/'))
+            .map(|path| format!("/{}", path))
+            .collect();
+
+        // Libraries the package ships itself, e.g. under /opt/<app> with a launcher script
+        // setting LD_LIBRARY_PATH
+        let bundled: HashSet<String> = paths
+            .iter()
+            .filter_map(|path| path.rsplit('/').next())
+            .map(str::to_string)
+            .collect();
+        let elf_files: Vec<String> = paths
+            .into_iter()
+            .filter(|path| !path.starts_with("/usr/lib/debug/") && is_elf(Path::new(path)))
+            .collect();
+        let broken = missing_libraries(&elf_files, &cache, &bundled);
+
+        let stale_python = python.as_ref().and_then(|current| {
+            files
+                .iter()
+                .filter_map(|path| site_packages.captures(path))
+                .map(|captures| captures[1].to_string())
+                .find(|version| version != current)
+        });
+
+        if broken.is_empty() && stale_python.is_none() {
+            continue;
+        }
+        let missing: BTreeSet<String> = broken
+            .iter()
+            .flat_map(|(_, libraries)| libraries.iter().cloned())
+            .collect();
+        candidates.push(RebuildCandidate {
+            version: foreign[&package.name].clone(),
+            name: package.name,
+            missing_libraries: missing.into_iter().collect(),
+            broken_files: broken.into_iter().map(|(file, _)| file).collect(),
+            stale_python,
+        });
+    }
+
+    Ok(candidates)
+}
@@ -0,0 +1,456 @@
+use crate::commands::localdb::SYNC_DB_DIR;
+use crate::commands::pacman_conf::{
+    check_server, is_valid_repo_name, read_pacman_conf, save_pacman_conf,
+};
+use crate::commands::process::{emit_progress, run_sudo, run_sudo_step};
+use crate::commands::storage::{PrivateDir, load_state, save_state};
+use crate::models::{CustomRepository, NewCustomRepository, Package};
+use crate::parsers::{PacmanConf, parse_pacman_search_by_repo};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+const REPOS_STATE_FILE: &str = "repositories.json";
+const PROGRESS_EVENT: &str = "repo-progress";
+
+// Repositories shipped (commented or not) by Arch itself
+const OFFICIAL_REPOS: [&str; 14] = [
+    "core",
+    "extra",
+    "multilib",
+    "core-testing",
+    "extra-testing",
+    "multilib-testing",
+    "core-staging",
+    "extra-staging",
+    "multilib-staging",
+    "gnome-unstable",
+    "kde-unstable",
+    "community",
+    "testing",
+    "community-testing",
+];
+
+// What was set up along with a repository, so removing it can undo everything
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+struct RepoSetup {
+    key_id: Option<String>,
+    packages: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ReposState {
+    repositories: HashMap<String, RepoSetup>,
+}
+
+pub(crate) fn is_official_repo(name: &str) -> bool {
+    OFFICIAL_REPOS.contains(&name)
+}
+
+// Enabled repositories that are not part of Arch
+pub(crate) fn custom_repo_names() -> HashSet<String> {
+    read_pacman_conf()
+        .map(|conf| {
+            conf.repositories()
+                .into_iter()
+                .filter(|(name, enabled)| *enabled && !is_official_repo(name))
+                .map(|(name, _)| name)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Custom repositories from pacman.conf, with the key and packages ArchStore set up for them
+#[tauri::command]
+pub fn list_custom_repositories() -> Result<Vec<CustomRepository>, String> {
+    let conf = read_pacman_conf()?;
+    let state: ReposState = load_state(REPOS_STATE_FILE);
+
+    Ok(conf
+        .repositories()
+        .into_iter()
+        .filter(|(name, _)| !is_official_repo(name))
+        .map(|(name, enabled)| {
+            let setup = state.repositories.get(&name).cloned().unwrap_or_default();
+            CustomRepository {
+                enabled,
+                sig_level: conf.values(&name, "SigLevel").into_iter().next(),
+                servers: conf.values(&name, "Server"),
+                includes: conf.values(&name, "Include"),
+                key_id: setup.key_id,
+                packages: setup.packages,
+                name,
+            }
+        })
+        .collect())
+}
+
+// GPG key IDs and fingerprints are hexadecimal
+fn is_valid_key_id(key_id: &str) -> bool {
+    let key_id = key_id.trim_start_matches("0x");
+    (8..=40).contains(&key_id.len()) && key_id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn validate(repo: &NewCustomRepository) -> Result<(), String> {
+    if !is_valid_repo_name(&repo.name) || repo.name == "options" || is_official_repo(&repo.name) {
+        return Err(format!("Invalid repository name: {}", repo.name));
+    }
+    if repo.servers.is_empty() && repo.include.is_none() {
+        return Err("A repository needs at least one Server or an Include file".to_string());
+    }
+    for server in &repo.servers {
+        check_server(server)?;
+    }
+    if let Some(include) = repo
+        .include
+        .as_ref()
+        .filter(|include| !include.starts_with('/') || include.contains(['\n', '#']))
+    {
+        return Err(format!("Invalid Include path: {}", include));
+    }
+    if let Some(sig_level) = repo
+        .sig_level
+        .as_ref()
+        .filter(|sig_level| sig_level.contains(['\n', '#']) || sig_level.trim().is_empty())
+    {
+        return Err(format!("Invalid SigLevel: {}", sig_level));
+    }
+    if let Some(key_id) = repo
+        .key_id
+        .as_ref()
+        .filter(|key_id| !is_valid_key_id(key_id))
+    {
+        return Err(format!("Invalid key ID: {}", key_id));
+    }
+    if let Some(keyserver) = repo.keyserver.as_ref().filter(|keyserver| {
+        !["hkp://", "hkps://", "https://"]
+            .iter()
+            .any(|scheme| keyserver.starts_with(scheme))
+    }) {
+        return Err(format!("Invalid keyserver: {}", keyserver));
+    }
+    for package in &repo.setup_packages {
+        let remote = package.starts_with("https://") || package.starts_with("http://");
+        if !(remote || package.starts_with('/')) || !package.contains(".pkg.tar") {
+            return Err(format!("Invalid package file: {}", package));
+        }
+    }
+    Ok(())
+}
+
+fn installed_names() -> HashSet<String> {
+    Command::new("pacman")
+        .arg("-Qq")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Add a third-party repository such as Chaotic-AUR or a company repo
+// Imports and locally signs its key, installs its keyring/mirrorlist packages,
+// adds the section to pacman.conf and syncs the new repository's database
+#[tauri::command]
+pub async fn add_custom_repository(
+    repo: NewCustomRepository,
+    password: String,
+    window: tauri::Window,
+) -> Result<String, String> {
+    validate(&repo)?;
+
+    let mut conf = read_pacman_conf()?;
+    if conf
+        .repositories()
+        .iter()
+        .any(|(name, _)| *name == repo.name)
+    {
+        return Err(format!("[{}] already exists in pacman.conf", repo.name));
+    }
+
+    let mut setup = RepoSetup::default();
+    if let Err(error) = set_up_repository(&repo, &mut conf, &mut setup, &password, &window) {
+        // Roll back the packages and key import, so a failed add leaves the system as it was
+        let notes = roll_back_setup(&setup, &password);
+        if notes.is_empty() {
+            return Err(error);
+        }
+        return Err(format!("{} ({})", error, notes.join(", ")));
+    }
+
+    let mut state: ReposState = load_state(REPOS_STATE_FILE);
+    state.repositories.insert(repo.name.clone(), setup);
+    save_state(REPOS_STATE_FILE, &state)?;
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        75,
+        format!(":: Synchronizing [{}] database...", repo.name),
+        false,
+    );
+    sync_repository(&conf, &repo.name, &password, &window)?;
+
+    let message = format!("Repository [{}] added", repo.name);
+    emit_progress(&window, PROGRESS_EVENT, 100, message.clone(), true);
+    Ok(message)
+}
+
+// Download only the given repository's database, through a pacman.conf holding just
+// [options] and that section
+// A plain -Sy would also refresh every other repository and leave a partial upgrade behind
+fn sync_repository(
+    conf: &PacmanConf,
+    name: &str,
+    password: &str,
+    window: &tauri::Window,
+) -> Result<(), String> {
+    let mut single = conf.clone();
+    for (other, _) in conf.repositories() {
+        if other != name {
+            single.remove_section(&other);
+        }
+    }
+    let dir = PrivateDir::create("repo-sync")?;
+    let config = dir.write_file("pacman.conf", single.to_string().as_bytes())?;
+    let config = config.to_string_lossy();
+    run_sudo_step(
+        password,
+        &["pacman", "-Sy", "--config", config.as_ref()],
+        window,
+        PROGRESS_EVENT,
+        85,
+    )
+}
+
+// Undo the setup steps of a failed add, returning what was undone or left behind
+fn roll_back_setup(setup: &RepoSetup, password: &str) -> Vec<String> {
+    let mut notes = Vec::new();
+    if !setup.packages.is_empty() {
+        let mut args = vec!["pacman", "-Rns", "--noconfirm"];
+        args.extend(setup.packages.iter().map(|package| package.as_str()));
+        let packages = setup.packages.join(", ");
+        notes.push(match run_sudo(password, &args) {
+            Ok(output) if output.status.success() => format!("{} removed again", packages),
+            _ => format!("{} could not be removed", packages),
+        });
+    }
+    if let Some(key_id) = &setup.key_id {
+        notes.push(
+            match run_sudo(password, &["pacman-key", "--delete", key_id]) {
+                Ok(output) if output.status.success() => format!("key {} removed again", key_id),
+                _ => format!("key {} could not be removed", key_id),
+            },
+        );
+    }
+    notes
+}
+
+// Key, setup packages and pacman.conf section of a new repository
+// setup records what was done so far, for rolling back when a later step fails
+fn set_up_repository(
+    repo: &NewCustomRepository,
+    conf: &mut PacmanConf,
+    setup: &mut RepoSetup,
+    password: &str,
+    window: &tauri::Window,
+) -> Result<(), String> {
+    if let Some(key_id) = &repo.key_id {
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            10,
+            format!(":: Importing key {}...", key_id),
+            false,
+        );
+        let known = run_sudo(password, &["pacman-key", "--list-keys", key_id])
+            .is_ok_and(|output| output.status.success());
+        let mut args = vec!["pacman-key", "--recv-keys", key_id.as_str()];
+        if let Some(keyserver) = &repo.keyserver {
+            args.extend(["--keyserver", keyserver.as_str()]);
+        }
+        run_sudo_step(password, &args, window, PROGRESS_EVENT, 15)?;
+        // A key that was already in the keyring is neither rolled back nor deleted with the repo
+        if !known {
+            setup.key_id = Some(key_id.clone());
+        }
+
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            25,
+            format!(":: Locally signing key {}...", key_id),
+            false,
+        );
+        run_sudo_step(
+            password,
+            &["pacman-key", "--lsign-key", key_id.as_str()],
+            window,
+            PROGRESS_EVENT,
+            30,
+        )?;
+    }
+
+    // Keyring and mirrorlist packages have to be there before the Include file is used
+    if !repo.setup_packages.is_empty() {
+        emit_progress(
+            window,
+            PROGRESS_EVENT,
+            40,
+            ":: Installing repository packages...".to_string(),
+            false,
+        );
+        let before = installed_names();
+        let mut args = vec!["pacman", "-U", "--noconfirm"];
+        args.extend(repo.setup_packages.iter().map(|package| package.as_str()));
+        run_sudo_step(password, &args, window, PROGRESS_EVENT, 50)?;
+
+        let mut added: Vec<String> = installed_names().difference(&before).cloned().collect();
+        added.sort();
+        setup.packages = added;
+    }
+
+    emit_progress(
+        window,
+        PROGRESS_EVENT,
+        60,
+        format!(":: Adding [{}] to pacman.conf...", repo.name),
+        false,
+    );
+    let mut directives: Vec<(String, String)> = Vec::new();
+    if let Some(sig_level) = &repo.sig_level {
+        directives.push(("SigLevel".to_string(), sig_level.trim().to_string()));
+    }
+    for server in &repo.servers {
+        directives.push(("Server".to_string(), server.clone()));
+    }
+    if let Some(include) = &repo.include {
+        directives.push(("Include".to_string(), include.clone()));
+    }
+    conf.add_section(&repo.name, &directives);
+    save_pacman_conf(password, conf).map(|_| ())
+}
+
+// Remove a custom repository together with the key and packages added for it
+#[tauri::command]
+pub async fn remove_custom_repository(
+    name: String,
+    password: String,
+    window: tauri::Window,
+) -> Result<String, String> {
+    if !is_valid_repo_name(&name) || is_official_repo(&name) {
+        return Err(format!("Invalid repository name: {}", name));
+    }
+
+    let mut conf = read_pacman_conf()?;
+    let mut state: ReposState = load_state(REPOS_STATE_FILE);
+    let setup = state.repositories.get(&name).cloned().unwrap_or_default();
+
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        10,
+        format!(":: Removing [{}] from pacman.conf...", name),
+        false,
+    );
+    if !conf.remove_section(&name) {
+        return Err(format!("Repository [{}] not found", name));
+    }
+    save_pacman_conf(&password, &conf)?;
+
+    // Packages are removed after the section, so pacman no longer needs their mirrorlist
+    let installed = installed_names();
+    let packages: Vec<&str> = setup
+        .packages
+        .iter()
+        .filter(|package| installed.contains(*package))
+        .map(|package| package.as_str())
+        .collect();
+    if !packages.is_empty() {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            30,
+            ":: Removing repository packages...".to_string(),
+            false,
+        );
+        let mut args = vec!["pacman", "-Rns", "--noconfirm"];
+        args.extend(packages);
+        run_sudo_step(&password, &args, &window, PROGRESS_EVENT, 40)?;
+    }
+
+    if let Some(key_id) = &setup.key_id {
+        emit_progress(
+            &window,
+            PROGRESS_EVENT,
+            60,
+            format!(":: Removing key {}...", key_id),
+            false,
+        );
+        run_sudo_step(
+            &password,
+            &["pacman-key", "--delete", key_id.as_str()],
+            &window,
+            PROGRESS_EVENT,
+            65,
+        )?;
+    }
+
+    state.repositories.remove(&name);
+    save_state(REPOS_STATE_FILE, &state)?;
+
+    // pacman ignores databases of repositories it no longer knows, so nothing needs a sync;
+    // the stale files are only removed
+    emit_progress(
+        &window,
+        PROGRESS_EVENT,
+        80,
+        format!(":: Removing [{}] database files...", name),
+        false,
+    );
+    let files: Vec<String> = ["db", "db.sig", "files", "files.sig"]
+        .iter()
+        .map(|extension| format!("{}/{}.{}", SYNC_DB_DIR, name, extension))
+        .collect();
+    let mut args = vec!["rm", "-f", "--"];
+    args.extend(files.iter().map(|file| file.as_str()));
+    run_sudo_step(&password, &args, &window, PROGRESS_EVENT, 90)?;
+
+    let message = format!("Repository [{}] removed", name);
+    emit_progress(&window, PROGRESS_EVENT, 100, message.clone(), true);
+    Ok(message)
+}
+
+// Search packages from custom repositories, reported as their own "custom" source
+// The repository name is kept in category
+#[tauri::command]
+pub async fn search_custom_packages(query: String) -> Result<Vec<Package>, String> {
+    let custom = custom_repo_names();
+    if custom.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = Command::new("pacman")
+        .args(["-Ss", &query])
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    Ok(
+        parse_pacman_search_by_repo(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .filter(|(repo, _)| custom.contains(repo))
+            .map(|(repo, mut package)| {
+                package.source = "custom".to_string();
+                package.category = repo;
+                package
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,295 @@
+use crate::commands::aur::{aur_git_url, aur_info};
+use crate::commands::process::unified_diff;
+use crate::commands::risk::{metadata_findings, risk_report, source_findings};
+use crate::commands::storage::{cache_dir, load_state, save_state};
+use crate::commands::vcs::local_revisions;
+use crate::models::{AurPackageInfo, AurReview, ReviewFile, VcsRevision};
+use crate::parsers::{is_valid_package_name, parse_package_filename};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+const REVIEWS_STATE_FILE: &str = "aur_reviews.json";
+
+// Build files as they were the last time ArchStore built the package
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+struct BuiltSources {
+    commit: String,
+    version: String,
+    built_at: String, // RFC 3339
+    files: BTreeMap<String, String>,
+    #[serde(default)]
+    packages: BTreeMap<String, String>, // package name -> built version
+    #[serde(default)]
+    vcs: Vec<VcsRevision>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+struct PackageReview {
+    approved_commit: Option<String>,
+    built: Option<BuiltSources>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ReviewsState {
+    packages: BTreeMap<String, PackageReview>,
+}
+
+// Local clone of an AUR package base
+pub(crate) fn aur_clone_dir(pkgbase: &str) -> PathBuf {
+    cache_dir().join("aur").join(pkgbase)
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Clone or fast-forward the AUR git repo of a package base, returning its HEAD commit
+pub(crate) fn sync_aur_repo(pkgbase: &str) -> Result<String, String> {
+    if !is_valid_package_name(pkgbase) {
+        return Err(format!("Invalid package base: {}", pkgbase));
+    }
+    let dir = aur_clone_dir(pkgbase);
+    let dir_str = dir.to_string_lossy().to_string();
+
+    if dir.join(".git").exists() {
+        git(&["-C", &dir_str, "fetch", "--quiet", "origin"])?;
+        git(&["-C", &dir_str, "reset", "--quiet", "--hard", "origin/HEAD"])?;
+    } else {
+        if let Some(parent) = dir.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        git(&["clone", "--quiet", &aur_git_url(pkgbase), &dir_str])?;
+    }
+
+    if !dir.join("PKGBUILD").exists() {
+        return Err(format!("{} has no PKGBUILD in the AUR", pkgbase));
+    }
+    git(&["-C", &dir_str, "rev-parse", "HEAD"])
+}
+
+// PKGBUILD plus every install script: *.install files and the one named by install=
+pub(crate) fn review_sources(pkgbase: &str) -> BTreeMap<String, String> {
+    let dir = aur_clone_dir(pkgbase);
+    let srcinfo = std::fs::read_to_string(dir.join(".SRCINFO")).unwrap_or_default();
+    let declared: Vec<String> = srcinfo
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("install = "))
+        .map(|name| name.trim().to_string())
+        .collect();
+
+    let mut names = vec!["PKGBUILD".to_string()];
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        names.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| name.ends_with(".install") || declared.contains(name)),
+        );
+    }
+
+    names
+        .into_iter()
+        .filter(|name| !name.contains('/'))
+        .filter_map(|name| {
+            std::fs::read_to_string(dir.join(&name))
+                .ok()
+                .map(|content| (name, content))
+        })
+        .collect()
+}
+
+async fn package_info(package_name: &str) -> Result<AurPackageInfo, String> {
+    if !is_valid_package_name(package_name) {
+        return Err(format!("Invalid package name: {}", package_name));
+    }
+    aur_info(&[package_name.to_string()])
+        .await?
+        .into_iter()
+        .find(|info| info.name == package_name)
+        .ok_or_else(|| format!("{} was not found in the AUR", package_name))
+}
+
+// Fetch the PKGBUILD and install scripts of an AUR package and diff them
+// against the version ArchStore built last
+#[tauri::command]
+pub async fn review_aur_package(package_name: String) -> Result<AurReview, String> {
+    let info = package_info(&package_name).await?;
+    let pkgbase = info.package_base.clone();
+    let commit = sync_aur_repo(&pkgbase)?;
+    let current = review_sources(&pkgbase);
+
+    let mut findings = source_findings(&current);
+    findings.extend(metadata_findings(&info));
+
+    let state: ReviewsState = load_state(REVIEWS_STATE_FILE);
+    let review = state.packages.get(&pkgbase).cloned().unwrap_or_default();
+    let built_files = review
+        .built
+        .as_ref()
+        .map(|built| built.files.clone())
+        .unwrap_or_default();
+
+    let mut files = Vec::new();
+    for (name, content) in &current {
+        let (status, diff) = match built_files.get(name) {
+            None => ("new", None),
+            Some(old) if old == content => ("unchanged", None),
+            Some(old) => ("changed", Some(unified_diff(name, old, content)?)),
+        };
+        files.push(ReviewFile {
+            name: name.clone(),
+            status: status.to_string(),
+            content: content.clone(),
+            diff,
+        });
+    }
+    for (name, old) in built_files
+        .iter()
+        .filter(|(name, _)| !current.contains_key(*name))
+    {
+        files.push(ReviewFile {
+            name: name.clone(),
+            status: "removed".to_string(),
+            content: String::new(),
+            diff: Some(unified_diff(name, old, "")?),
+        });
+    }
+
+    Ok(AurReview {
+        package: package_name,
+        approved: review.approved_commit.as_deref() == Some(commit.as_str()),
+        last_built_commit: review.built.as_ref().map(|built| built.commit.clone()),
+        last_built_version: review.built.as_ref().map(|built| built.version.clone()),
+        pkgbase,
+        version: info.version,
+        commit,
+        files,
+        risk: risk_report(findings, true),
+    })
+}
+
+// Approve the reviewed commit of a package base for building
+// The commit must still be the one in the local clone, so nothing unseen gets approved
+#[tauri::command]
+pub fn approve_aur_review(pkgbase: String, commit: String) -> Result<(), String> {
+    if !is_valid_package_name(&pkgbase) {
+        return Err(format!("Invalid package base: {}", pkgbase));
+    }
+    let dir = aur_clone_dir(&pkgbase);
+    let head = git(&["-C", &dir.to_string_lossy(), "rev-parse", "HEAD"])
+        .map_err(|_| format!("Review {} before approving it", pkgbase))?;
+    if head != commit {
+        return Err(format!(
+            "{} changed since it was reviewed; review it again",
+            pkgbase
+        ));
+    }
+
+    let mut state: ReviewsState = load_state(REVIEWS_STATE_FILE);
+    state.packages.entry(pkgbase).or_default().approved_commit = Some(commit);
+    save_state(REVIEWS_STATE_FILE, &state)
+}
+
+// Withdraw an approval, e.g. when the user rejects a review
+#[tauri::command]
+pub fn revoke_aur_approval(pkgbase: String) -> Result<(), String> {
+    if !is_valid_package_name(&pkgbase) {
+        return Err(format!("Invalid package base: {}", pkgbase));
+    }
+    let mut state: ReviewsState = load_state(REVIEWS_STATE_FILE);
+    if let Some(review) = state.packages.get_mut(&pkgbase) {
+        review.approved_commit = None;
+    }
+    save_state(REVIEWS_STATE_FILE, &state)
+}
+
+// Bring the clone up to date and make sure it is exactly the commit the user approved,
+// so the build runs the files that were reviewed
+pub(crate) fn verify_approved(pkgbase: &str) -> Result<(), String> {
+    let state: ReviewsState = load_state(REVIEWS_STATE_FILE);
+    let Some(approved) = state
+        .packages
+        .get(pkgbase)
+        .and_then(|review| review.approved_commit.clone())
+    else {
+        return Err(format!("{} has not been reviewed", pkgbase));
+    };
+
+    if sync_aur_repo(pkgbase)? != approved {
+        return Err(format!(
+            "{} changed in the AUR since it was approved",
+            pkgbase
+        ));
+    }
+    Ok(())
+}
+
+// Keep the approved build files as the baseline for the next review, along with the
+// built package versions and the upstream revisions of VCS sources
+pub(crate) fn record_build(pkgbase: &str, files: &[String]) -> Result<(), String> {
+    let mut state: ReviewsState = load_state(REVIEWS_STATE_FILE);
+    let review = state.packages.entry(pkgbase.to_string()).or_default();
+    let Some(commit) = review.approved_commit.clone() else {
+        return Ok(());
+    };
+    // A later review may have moved the clone past what was approved
+    let dir = aur_clone_dir(pkgbase);
+    if git(&["-C", &dir.to_string_lossy(), "rev-parse", "HEAD"])? != commit {
+        return Ok(());
+    }
+
+    let srcinfo = std::fs::read_to_string(dir.join(".SRCINFO")).unwrap_or_default();
+    let field = |key: &str| {
+        srcinfo
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key).map(str::trim))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let version = match field("epoch = ").as_str() {
+        "" => format!("{}-{}", field("pkgver = "), field("pkgrel = ")),
+        epoch => format!("{}:{}-{}", epoch, field("pkgver = "), field("pkgrel = ")),
+    };
+
+    review.built = Some(BuiltSources {
+        commit,
+        version,
+        built_at: chrono::Local::now().to_rfc3339(),
+        files: review_sources(pkgbase),
+        packages: files
+            .iter()
+            .filter_map(|path| parse_package_filename(path.rsplit('/').next().unwrap_or(path)))
+            .map(|(name, version, _)| (name, version))
+            .collect(),
+        vcs: local_revisions(pkgbase),
+    });
+    save_state(REVIEWS_STATE_FILE, &state)
+}
+
+// VCS revisions of the last ArchStore build, if the installed package came from it
+pub(crate) fn built_revisions(
+    pkgbase: &str,
+    name: &str,
+    version: &str,
+) -> Option<Vec<VcsRevision>> {
+    let state: ReviewsState = load_state(REVIEWS_STATE_FILE);
+    let built = state.packages.get(pkgbase)?.built.clone()?;
+    if built.packages.get(name).map(String::as_str) != Some(version) {
+        return None;
+    }
+    Some(built.vcs)
+}
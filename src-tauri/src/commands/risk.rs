@@ -0,0 +1,116 @@
+use crate::commands::aur::aur_info;
+use crate::commands::review::{review_sources, sync_aur_repo};
+use crate::models::{AurPackageInfo, RiskFinding, RiskReport};
+use crate::parsers::{is_valid_package_name, scan_install_script, scan_pkgbuild};
+use std::collections::BTreeMap;
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn metadata_finding(severity: &str, message: String) -> RiskFinding {
+    RiskFinding {
+        severity: severity.to_string(),
+        category: "maintenance".to_string(),
+        message,
+        file: None,
+        line: None,
+        excerpt: None,
+    }
+}
+
+// Findings from AUR metadata: orphaned, out-of-date, very new or barely used packages
+pub(crate) fn metadata_findings(info: &AurPackageInfo) -> Vec<RiskFinding> {
+    let mut findings = Vec::new();
+    let now = chrono::Utc::now().timestamp();
+
+    if info.maintainer.is_none() {
+        findings.push(metadata_finding(
+            "warning",
+            "Orphaned: the package has no maintainer".to_string(),
+        ));
+    }
+    if let Some(flagged) = info.out_of_date {
+        let date = chrono::DateTime::from_timestamp(flagged, 0)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        findings.push(metadata_finding(
+            "warning",
+            format!("Flagged out-of-date since {}", date),
+        ));
+    }
+
+    let age_days = (now - info.first_submitted) / DAY;
+    if age_days < 14 {
+        findings.push(metadata_finding(
+            "warning",
+            format!("Submitted to the AUR {} days ago", age_days),
+        ));
+    } else if age_days < 60 {
+        findings.push(metadata_finding(
+            "info",
+            format!("Submitted to the AUR {} days ago", age_days),
+        ));
+    }
+
+    match info.num_votes {
+        0 => findings.push(metadata_finding("warning", "No votes".to_string())),
+        votes @ 1..5 => findings.push(metadata_finding("info", format!("Only {} votes", votes))),
+        _ => {}
+    }
+
+    findings
+}
+
+// Findings from the PKGBUILD and install scripts of a package base
+pub(crate) fn source_findings(files: &BTreeMap<String, String>) -> Vec<RiskFinding> {
+    files
+        .iter()
+        .flat_map(|(name, content)| {
+            if name == "PKGBUILD" {
+                scan_pkgbuild(content)
+            } else {
+                scan_install_script(name, content)
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn risk_report(findings: Vec<RiskFinding>, scripts_scanned: bool) -> RiskReport {
+    let score: u32 = findings
+        .iter()
+        .map(|finding| match finding.severity.as_str() {
+            "danger" => 30,
+            "warning" => 10,
+            _ => 2,
+        })
+        .sum();
+    let level = match score {
+        30.. => "high",
+        10.. => "medium",
+        _ => "low",
+    };
+
+    RiskReport {
+        level: level.to_string(),
+        score,
+        findings,
+        scripts_scanned,
+    }
+}
+
+// Full risk report of an AUR package: static checks of its build files plus AUR metadata
+#[tauri::command]
+pub async fn get_aur_risk_report(package_name: String) -> Result<RiskReport, String> {
+    if !is_valid_package_name(&package_name) {
+        return Err(format!("Invalid package name: {}", package_name));
+    }
+    let info = aur_info(std::slice::from_ref(&package_name))
+        .await?
+        .into_iter()
+        .find(|info| info.name == package_name)
+        .ok_or_else(|| format!("{} was not found in the AUR", package_name))?;
+
+    sync_aur_repo(&info.package_base)?;
+    let mut findings = source_findings(&review_sources(&info.package_base));
+    findings.extend(metadata_findings(&info));
+    Ok(risk_report(findings, true))
+}
@@ -1,5 +1,10 @@
+use crate::commands::aur::{aur_package, aur_search, foreign_packages};
+use crate::commands::files::search_files_database;
+use crate::commands::localdb::read_installed_packages;
+use crate::commands::repos::custom_repo_names;
 use crate::models::Package;
-use crate::parsers::{parse_aur_search, parse_flatpak_search, parse_pacman_search};
+use crate::parsers::{is_valid_package_name, parse_flatpak_search, parse_pacman_search_by_repo};
+use std::collections::HashMap;
 use std::process::Command;
 
 // Search packages from official repos using pacman
@@ -14,8 +19,14 @@ pub async fn search_official_packages(query: String) -> Result<Vec<Package>, Str
         return Ok(Vec::new());
     }
 
+    // Results from custom repositories are reported by search_custom_packages
+    let custom = custom_repo_names();
     let result = String::from_utf8_lossy(&output.stdout);
-    let mut packages = parse_pacman_search(&result);
+    let mut packages: Vec<Package> = parse_pacman_search_by_repo(&result)
+        .into_iter()
+        .filter(|(repo, _)| !custom.contains(repo))
+        .map(|(_, package)| package)
+        .collect();
 
     // Get installed packages to mark them
     if let Ok(installed_output) = Command::new("pacman").args(&["-Q"]).output() {
@@ -37,31 +48,31 @@ pub async fn search_official_packages(query: String) -> Result<Vec<Package>, Str
     Ok(packages)
 }
 
-// Search AUR packages using yay or paru
+// Search AUR packages through the AUR RPC
 #[tauri::command]
 pub async fn search_aur_packages(query: String) -> Result<Vec<Package>, String> {
-    // Try yay first, fall back to paru
-    let helper = if Command::new("yay").arg("--version").output().is_ok() {
-        "yay"
-    } else if Command::new("paru").arg("--version").output().is_ok() {
-        "paru"
-    } else {
-        return Err("No AUR helper found. Please install yay or paru.".to_string());
-    };
-
-    let output = Command::new(helper)
-        .args(&["-Ss", &query])
-        .output()
-        .map_err(|e| format!("Failed to execute {}: {}", helper, e))?;
-
-    if !output.status.success() {
+    let query = query.trim();
+    // The RPC rejects searches shorter than two characters
+    if query.chars().count() < 2 {
         return Ok(Vec::new());
     }
 
-    let result = String::from_utf8_lossy(&output.stdout);
-    let packages = parse_aur_search(&result);
+    let installed: HashMap<String, &str> = read_installed_packages()
+        .iter()
+        .map(|package| (package.name.clone(), package.reason()))
+        .collect();
 
-    Ok(packages)
+    let mut results = aur_search("name-desc", query).await?;
+    results.sort_by(|a, b| b.popularity.total_cmp(&a.popularity));
+
+    Ok(results
+        .iter()
+        .map(|info| {
+            let mut package = aur_package(info, installed.contains_key(&info.name));
+            package.install_reason = installed.get(&info.name).unwrap_or(&"").to_string();
+            package
+        })
+        .collect())
 }
 
 // Search Flatpak packages
@@ -110,6 +121,10 @@ pub async fn search_flatpak_packages(query: String) -> Result<Vec<Package>, Stri
 #[tauri::command]
 pub async fn get_installed_packages() -> Result<Vec<Package>, String> {
     let mut all_packages = Vec::new();
+    let reasons: HashMap<String, &str> = read_installed_packages()
+        .iter()
+        .map(|package| (package.name.clone(), package.reason()))
+        .collect();
 
     // Get pacman packages
     if let Ok(output) = Command::new("pacman").args(&["-Q"]).output() {
@@ -130,46 +145,33 @@ pub async fn get_installed_packages() -> Result<Vec<Package>, String> {
                         maintainer: String::new(),
                         size: String::new(),
                         last_updated: String::new(),
+                        held: false,
+                        install_reason: reasons.get(parts[0]).unwrap_or(&"").to_string(),
+                        risk: None,
                     });
                 }
             }
         }
     }
 
-    // Get AUR packages using AUR helper -Qm (lists AUR packages with versions)
-    // Detect AUR helper first
-    let aur_helper = if Command::new("yay").arg("--version").output().is_ok() {
-        Some("yay")
-    } else if Command::new("paru").arg("--version").output().is_ok() {
-        Some("paru")
-    } else {
-        None
-    };
-
-    if let Some(helper) = aur_helper {
-        if let Ok(output) = Command::new(helper).args(&["-Qm"]).output() {
-            if output.status.success() {
-                let result = String::from_utf8_lossy(&output.stdout);
-                for line in result.lines() {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 2 {
-                        all_packages.push(Package {
-                            name: parts[0].to_string(),
-                            version: parts[1].to_string(),
-                            description: String::new(),
-                            source: "aur".to_string(),
-                            installed: true,
-                            category: String::new(),
-                            downloads: 0,
-                            rating: 0.0,
-                            maintainer: String::new(),
-                            size: String::new(),
-                            last_updated: String::new(),
-                        });
-                    }
-                }
-            }
-        }
+    // Foreign packages (-Qm) are the ones built from the AUR or installed by hand
+    for (name, version) in foreign_packages() {
+        all_packages.push(Package {
+            install_reason: reasons.get(&name).unwrap_or(&"").to_string(),
+            name,
+            version,
+            description: String::new(),
+            source: "aur".to_string(),
+            installed: true,
+            category: String::new(),
+            downloads: 0,
+            rating: 0.0,
+            maintainer: String::new(),
+            size: String::new(),
+            last_updated: String::new(),
+            held: false,
+            risk: None,
+        });
     }
 
     // Get Flatpak packages
@@ -191,6 +193,9 @@ pub async fn get_installed_packages() -> Result<Vec<Package>, String> {
                         maintainer: String::new(),
                         size: String::new(),
                         last_updated: String::new(),
+                        held: false,
+                        install_reason: String::new(),
+                        risk: None,
                     });
                 }
             }
@@ -199,3 +204,156 @@ pub async fn get_installed_packages() -> Result<Vec<Package>, String> {
 
     Ok(all_packages)
 }
+
+// Find packages that provide a command, e.g. "rg" -> ripgrep
+// Official packages come from the files databases (usr/bin/<name>), AUR packages from their
+// provides metadata and Flatpak apps from the command they export
+#[tauri::command]
+pub async fn search_command(command: String) -> Result<Vec<Package>, String> {
+    let command = command.trim().to_string();
+    if !is_valid_package_name(&command) {
+        return Err(format!("Invalid command name: {}", command));
+    }
+
+    let installed: HashMap<String, &str> = read_installed_packages()
+        .iter()
+        .map(|package| (package.name.clone(), package.reason()))
+        .collect();
+    let mut packages = Vec::new();
+
+    // Official repositories; skipped until the files databases have been synced
+    let path = format!("/usr/bin/{}", command);
+    for file in search_files_database(&path).unwrap_or_default() {
+        if file.path != path || packages.iter().any(|p: &Package| p.name == file.package) {
+            continue;
+        }
+        packages.push(Package {
+            name: file.package.clone(),
+            version: file.version,
+            description: format!("Provides {}", path),
+            source: "official".to_string(),
+            installed: installed.contains_key(&file.package),
+            category: file.repository,
+            downloads: 0,
+            rating: 0.0,
+            maintainer: String::new(),
+            size: String::new(),
+            last_updated: String::new(),
+            held: false,
+            install_reason: installed.get(&file.package).unwrap_or(&"").to_string(),
+            risk: None,
+        });
+    }
+
+    // AUR packages listing the command in provides; the network may be unavailable
+    if let Ok(results) = aur_search("provides", &command).await {
+        for info in results {
+            if packages.iter().any(|p| p.name == info.name) {
+                continue;
+            }
+            let mut package = aur_package(&info, installed.contains_key(&info.name));
+            package.install_reason = installed.get(&info.name).unwrap_or(&"").to_string();
+            packages.push(package);
+        }
+    }
+
+    packages.extend(flatpak_command_providers(&command));
+    Ok(packages)
+}
+
+// Flatpak apps whose metadata declares the command, installed apps first
+fn flatpak_command_providers(command: &str) -> Vec<Package> {
+    let mut packages = Vec::new();
+
+    let installed_ids: Vec<String> = Command::new("flatpak")
+        .args(["list", "--app", "--columns=application"])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for app_id in &installed_ids {
+        let metadata = Command::new("flatpak")
+            .args(["info", "--show-metadata", app_id])
+            .output();
+        if metadata
+            .is_ok_and(|output| exports_command(&String::from_utf8_lossy(&output.stdout), command))
+        {
+            packages.push(flatpak_package(app_id, true));
+        }
+    }
+
+    // Remote apps: check the metadata of the best search matches
+    let search = Command::new("flatpak")
+        .args(["search", "--columns=application,remotes", command])
+        .output();
+    let candidates: Vec<(String, String)> = search
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| {
+                    let (app_id, remotes) = line.split_once('\t')?;
+                    let remote = remotes.split(',').next()?.trim();
+                    Some((app_id.trim().to_string(), remote.to_string()))
+                })
+                .filter(|(app_id, _)| !installed_ids.contains(app_id))
+                .take(10)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for (app_id, remote) in candidates {
+        let metadata = Command::new("flatpak")
+            .args(["remote-info", "--show-metadata", &remote, &app_id])
+            .output();
+        if metadata
+            .is_ok_and(|output| exports_command(&String::from_utf8_lossy(&output.stdout), command))
+        {
+            packages.push(flatpak_package(&app_id, false));
+        }
+    }
+
+    packages
+}
+
+// Flatpak metadata is a keyfile; the [Application] group names the command the app runs
+fn exports_command(metadata: &str, command: &str) -> bool {
+    let mut in_application = false;
+    for line in metadata.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_application = line == "[Application]";
+            continue;
+        }
+        match line.strip_prefix("command=") {
+            Some(value) if in_application => {
+                return value.rsplit('/').next().unwrap_or(value) == command;
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+fn flatpak_package(app_id: &str, installed: bool) -> Package {
+    Package {
+        name: app_id.to_string(),
+        version: String::new(),
+        description: "Exports the command".to_string(),
+        source: "flatpak".to_string(),
+        installed,
+        category: String::new(),
+        downloads: 0,
+        rating: 0.0,
+        maintainer: String::new(),
+        size: String::new(),
+        last_updated: String::new(),
+        held: false,
+        install_reason: String::new(),
+        risk: None,
+    }
+}
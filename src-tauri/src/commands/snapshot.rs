@@ -0,0 +1,375 @@
+use crate::commands::process::{emit_progress, run_sudo};
+use crate::models::{Snapshot, SnapshotStatus};
+use std::path::Path;
+use std::process::Command;
+
+// Description prefix used to recognise snapshots created by ArchStore
+const SNAPSHOT_LABEL: &str = "archstore pre-update";
+// Where plain btrfs snapshots of / are stored
+const BTRFS_SNAPSHOT_DIR: &str = "/.archstore-snapshots";
+const SNAPPER_CONFIG_DIR: &str = "/etc/snapper/configs";
+
+#[derive(Debug, Clone, PartialEq)]
+enum SnapshotBackend {
+    Snapper(String), // snapper config name
+    Timeshift,
+    Btrfs,
+}
+
+impl SnapshotBackend {
+    fn name(&self) -> &'static str {
+        match self {
+            SnapshotBackend::Snapper(_) => "snapper",
+            SnapshotBackend::Timeshift => "timeshift",
+            SnapshotBackend::Btrfs => "btrfs",
+        }
+    }
+}
+
+fn has_command(command: &str) -> bool {
+    Command::new("which")
+        .arg(command)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+// Snapper configs are plain files named after the config
+fn snapper_configs() -> Vec<String> {
+    let mut configs: Vec<String> = std::fs::read_dir(SNAPPER_CONFIG_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    configs.sort();
+    configs
+}
+
+fn root_is_btrfs() -> bool {
+    Command::new("findmnt")
+        .args(["-no", "FSTYPE", "/"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "btrfs")
+        .unwrap_or(false)
+}
+
+// Prefer snapper, then timeshift, then raw btrfs snapshots of /
+fn detect_backend() -> Option<SnapshotBackend> {
+    if has_command("snapper") {
+        let configs = snapper_configs();
+        let config = configs
+            .iter()
+            .find(|config| config.as_str() == "root")
+            .or_else(|| configs.first());
+        if let Some(config) = config {
+            return Some(SnapshotBackend::Snapper(config.clone()));
+        }
+    }
+
+    if has_command("timeshift") {
+        return Some(SnapshotBackend::Timeshift);
+    }
+
+    if has_command("btrfs") && root_is_btrfs() {
+        return Some(SnapshotBackend::Btrfs);
+    }
+
+    None
+}
+
+// Report which snapshot tool would be used before updates
+#[tauri::command]
+pub async fn get_snapshot_status() -> Result<SnapshotStatus, String> {
+    let backend = detect_backend();
+    Ok(SnapshotStatus {
+        available: backend.is_some(),
+        backend: backend
+            .as_ref()
+            .map(|backend| backend.name().to_string())
+            .unwrap_or_default(),
+        snapper_configs: snapper_configs(),
+    })
+}
+
+// List snapshots that ArchStore created before updates
+#[tauri::command]
+pub async fn list_snapshots(password: String) -> Result<Vec<Snapshot>, String> {
+    match detect_backend() {
+        Some(SnapshotBackend::Snapper(config)) => list_snapper_snapshots(&password, &config),
+        Some(SnapshotBackend::Timeshift) => list_timeshift_snapshots(&password),
+        Some(SnapshotBackend::Btrfs) => Ok(list_btrfs_snapshots()),
+        None => Ok(Vec::new()),
+    }
+}
+
+// Name timeshift gave the new snapshot
+// Only its "Tagged snapshot '2024-01-01_10-00-00': ondemand" line carries it; other lines
+// may quote paths or devices
+fn timeshift_snapshot_name(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Tagged snapshot '"))
+        .and_then(|rest| rest.split_once('\''))
+        .map(|(name, _)| name.to_string())
+}
+
+// Create a labelled snapshot that records the update job ID
+pub(crate) fn create_snapshot(password: &str, job_id: &str) -> Result<Snapshot, String> {
+    let backend = detect_backend().ok_or_else(|| {
+        "No snapshot tool found (snapper, timeshift or a btrfs root is required)".to_string()
+    })?;
+    let description = format!("{} {}", SNAPSHOT_LABEL, job_id);
+    let created = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let id = match &backend {
+        SnapshotBackend::Snapper(config) => {
+            let userdata = format!("archstore=yes,job={}", job_id);
+            let output = run_sudo(
+                password,
+                &[
+                    "snapper",
+                    "-c",
+                    config,
+                    "create",
+                    "--type",
+                    "single",
+                    "--cleanup-algorithm",
+                    "number",
+                    "--description",
+                    &description,
+                    "--userdata",
+                    &userdata,
+                    "--print-number",
+                ],
+            )?;
+            check_output("snapper", &output)?;
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        SnapshotBackend::Timeshift => {
+            let output = run_sudo(
+                password,
+                &[
+                    "timeshift",
+                    "--create",
+                    "--comments",
+                    &description,
+                    "--tags",
+                    "O",
+                ],
+            )?;
+            check_output("timeshift", &output)?;
+            timeshift_snapshot_name(&String::from_utf8_lossy(&output.stdout))
+                .unwrap_or_else(|| job_id.to_string())
+        }
+        SnapshotBackend::Btrfs => {
+            let target = format!("{}/{}", BTRFS_SNAPSHOT_DIR, job_id);
+            let output = run_sudo(password, &["mkdir", "-p", BTRFS_SNAPSHOT_DIR])?;
+            check_output("mkdir", &output)?;
+            let output = run_sudo(
+                password,
+                &["btrfs", "subvolume", "snapshot", "-r", "/", &target],
+            )?;
+            check_output("btrfs", &output)?;
+            target
+        }
+    };
+
+    Ok(Snapshot {
+        id,
+        backend: backend.name().to_string(),
+        job_id: job_id.to_string(),
+        description,
+        created,
+    })
+}
+
+// Take the pre-update snapshot for an update job, reporting on the given progress event
+// Returns an error when the update must not go ahead
+pub(crate) fn snapshot_before_update(
+    password: &str,
+    job_id: &str,
+    allow_failure: bool,
+    window: &tauri::Window,
+    event: &str,
+) -> Result<(), String> {
+    emit_progress(
+        window,
+        event,
+        15,
+        ":: Creating pre-update snapshot...".to_string(),
+        false,
+    );
+
+    match create_snapshot(password, job_id) {
+        Ok(snapshot) => {
+            emit_progress(
+                window,
+                event,
+                18,
+                format!(
+                    "Created {} snapshot {} for job {}",
+                    snapshot.backend, snapshot.id, job_id
+                ),
+                false,
+            );
+            Ok(())
+        }
+        Err(e) if allow_failure => {
+            emit_progress(
+                window,
+                event,
+                18,
+                format!("Snapshot failed, continuing without it: {}", e),
+                false,
+            );
+            Ok(())
+        }
+        Err(e) => {
+            let message = format!("Update blocked because the snapshot failed: {}", e);
+            emit_progress(window, event, 0, message.clone(), true);
+            Err(message)
+        }
+    }
+}
+
+fn check_output(tool: &str, output: &std::process::Output) -> Result<(), String> {
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+// Extract the job ID from a description written by create_snapshot
+fn job_from_description(description: &str) -> Option<String> {
+    description
+        .strip_prefix(SNAPSHOT_LABEL)
+        .map(|job| job.trim().to_string())
+}
+
+fn list_snapper_snapshots(password: &str, config: &str) -> Result<Vec<Snapshot>, String> {
+    let output = run_sudo(password, &["snapper", "--jsonout", "-c", config, "list"])?;
+    check_output("snapper", &output)?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse snapper output: {}", e))?;
+
+    let snapshots = json[config]
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let description = entry["description"].as_str().unwrap_or_default();
+                    let job_id = job_from_description(description)?;
+                    Some(Snapshot {
+                        id: entry["number"].to_string(),
+                        backend: "snapper".to_string(),
+                        job_id,
+                        description: description.to_string(),
+                        created: entry["date"].as_str().unwrap_or_default().to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(snapshots)
+}
+
+fn list_timeshift_snapshots(password: &str) -> Result<Vec<Snapshot>, String> {
+    let output = run_sudo(password, &["timeshift", "--list"])?;
+    check_output("timeshift", &output)?;
+
+    // Rows look like: 0    >  2024-01-01_10-00-00  O     archstore pre-update <job>
+    let result = String::from_utf8_lossy(&output.stdout);
+    let snapshots = result
+        .lines()
+        .filter_map(|line| {
+            let (_, row) = line.split_once('>')?;
+            let mut parts = row.split_whitespace();
+            let name = parts.next()?.to_string();
+            let rest: Vec<&str> = parts.collect();
+            let description_start = rest.iter().position(|part| part.starts_with("archstore"))?;
+            let description = rest[description_start..].join(" ");
+            let job_id = job_from_description(&description)?;
+            Some(Snapshot {
+                created: chrono::NaiveDateTime::parse_from_str(&name, "%Y-%m-%d_%H-%M-%S")
+                    .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|_| name.clone()),
+                id: name,
+                backend: "timeshift".to_string(),
+                job_id,
+                description,
+            })
+        })
+        .collect();
+
+    Ok(snapshots)
+}
+
+fn list_btrfs_snapshots() -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = std::fs::read_dir(BTRFS_SNAPSHOT_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| {
+                    let job_id = entry.file_name().to_string_lossy().to_string();
+                    let path = Path::new(BTRFS_SNAPSHOT_DIR).join(&job_id);
+                    let created = entry
+                        .metadata()
+                        .and_then(|metadata| metadata.modified())
+                        .map(|time| {
+                            chrono::DateTime::<chrono::Local>::from(time)
+                                .format("%Y-%m-%d %H:%M:%S")
+                                .to_string()
+                        })
+                        .unwrap_or_default();
+                    Snapshot {
+                        id: path.to_string_lossy().to_string(),
+                        backend: "btrfs".to_string(),
+                        description: format!("{} {}", SNAPSHOT_LABEL, job_id),
+                        job_id,
+                        created,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    snapshots.sort_by(|a, b| b.job_id.cmp(&a.job_id));
+    snapshots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeshift_name_comes_from_the_tagged_line() {
+        let output = "Using system disk as snapshot device for creating snapshots in RSYNC mode
+Mounted '/dev/sda2' at '/run/timeshift/12345/backup'
+Creating new snapshot...(RSYNC)
+Saving to device: /dev/sda2, mounted at path: /run/timeshift/12345/backup
+Synching files with rsync...
+Created control file: /run/timeshift/12345/backup/timeshift/snapshots/2024-01-01_10-00-00/info.json
+RSYNC Snapshot saved successfully (12s)
+Tagged snapshot '2024-01-01_10-00-00': ondemand
+------------------------------------------------------------------------------
+";
+        assert_eq!(
+            timeshift_snapshot_name(output),
+            Some("2024-01-01_10-00-00".to_string())
+        );
+        assert_eq!(
+            timeshift_snapshot_name("Mounted '/dev/sda2' at '/run/timeshift/backup'\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// Directory for ArchStore's own state: $XDG_DATA_HOME/archstore or ~/.local/share/archstore
+pub(crate) fn data_dir() -> PathBuf {
+    let base = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_default();
+            PathBuf::from(home).join(".local/share")
+        });
+    base.join("archstore")
+}
+
+// Directory for caches such as AUR clones: $XDG_CACHE_HOME/archstore or ~/.cache/archstore
+pub(crate) fn cache_dir() -> PathBuf {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_default();
+            PathBuf::from(home).join(".cache")
+        });
+    base.join("archstore")
+}
+
+static PRIVATE_DIR_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+// A fresh 0700 directory under the cache dir for staging files that are later read as root
+// Shared locations such as /tmp let other users swap files between checks and use; the
+// directory is removed again when dropped
+pub(crate) struct PrivateDir {
+    path: PathBuf,
+}
+
+impl PrivateDir {
+    pub fn create(prefix: &str) -> Result<Self, String> {
+        let parent = cache_dir().join("tmp");
+        std::fs::create_dir_all(&parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos())
+            .unwrap_or_default();
+        let path = parent.join(format!(
+            "{}-{}-{}-{}",
+            prefix,
+            std::process::id(),
+            nanos,
+            PRIVATE_DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        // create (not create_all) fails if something already sits at the path
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let dir = PrivateDir { path };
+
+        let owner = std::fs::symlink_metadata(&dir.path).map(|metadata| metadata.uid());
+        let uid = std::fs::metadata("/proc/self").map(|metadata| metadata.uid());
+        match (owner, uid) {
+            (Ok(owner), Ok(uid)) if owner == uid => Ok(dir),
+            _ => Err(format!(
+                "{} is not owned by the current user",
+                dir.path.display()
+            )),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Write a new 0600 file inside the directory; never follows or reuses an existing path
+    pub fn write_file(&self, name: &str, content: &[u8]) -> Result<PathBuf, String> {
+        let path = self.path.join(name);
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+impl Drop for PrivateDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+// Load a JSON state file, falling back to the default when it is missing or unreadable
+pub(crate) fn load_state<T: DeserializeOwned + Default>(name: &str) -> T {
+    std::fs::read_to_string(data_dir().join(name))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// Save a JSON state file, writing to a temporary file first so it is never left half-written
+pub(crate) fn save_state<T: Serialize>(name: &str, value: &T) -> Result<(), String> {
+    let dir = data_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    let path = dir.join(name);
+    let tmp_path = dir.join(format!("{}.tmp", name));
+
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
@@ -1,6 +1,22 @@
+use crate::commands::aur::aur_updates;
+use crate::commands::aur_build::{run_aur_pipeline, split_reviewed_targets};
+use crate::commands::aur_resolve::repo_provider;
+use crate::commands::hold::HeldSet;
+use crate::commands::localdb::{LOCAL_DB_DIR, SYNC_DB_DIR};
+use crate::commands::news::{check_news_before_update, record_successful_update};
+use crate::commands::pacman_conf::{read_pacman_conf, save_pacman_conf};
+use crate::commands::process::{
+    describe_pacman_failure, emit_progress, new_job_id, run_sudo, spawn_sudo, stream_child,
+};
+use crate::commands::snapshot::snapshot_before_update;
+use crate::commands::storage::PrivateDir;
 use crate::models::Package;
-use std::io::{BufRead, BufReader, Write};
+use crate::parsers::{dependency_name, is_valid_package_name, parse_info_blocks};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 use std::process::{Command, Stdio};
+use std::thread;
 use tauri::Emitter;
 
 // Check for available updates
@@ -29,38 +45,33 @@ pub async fn check_updates() -> Result<Vec<Package>, String> {
                     maintainer: String::new(),
                     size: String::new(),
                     last_updated: String::new(),
+                    held: false,
+                    install_reason: String::new(),
+                    risk: None,
                 });
             }
         }
     }
 
-    // Also check AUR updates if yay/paru is available
-    if let Ok(helper) = get_aur_helper() {
-        if let Ok(aur_output) = Command::new(helper).args(&["-Qua"]).output() {
-            if aur_output.status.success() {
-                let result = String::from_utf8_lossy(&aur_output.stdout);
-                for line in result.lines() {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 4 {
-                        updates.push(Package {
-                            name: parts[0].to_string(),
-                            version: parts[3].to_string(),
-                            description: format!(
-                                "AUR update available: {} -> {}",
-                                parts[1], parts[3]
-                            ),
-                            source: "aur".to_string(),
-                            installed: true,
-                            category: String::new(),
-                            downloads: 0,
-                            rating: 0.0,
-                            maintainer: String::new(),
-                            size: String::new(),
-                            last_updated: String::new(),
-                        });
-                    }
-                }
-            }
+    // AUR updates for foreign packages; the AUR may be unreachable
+    if let Ok(aur) = aur_updates().await {
+        for (info, current) in aur {
+            updates.push(Package {
+                description: format!("AUR update available: {} -> {}", current, info.version),
+                name: info.name,
+                version: info.version,
+                source: "aur".to_string(),
+                installed: true,
+                category: String::new(),
+                downloads: 0,
+                rating: 0.0,
+                maintainer: String::new(),
+                size: String::new(),
+                last_updated: String::new(),
+                held: false,
+                install_reason: String::new(),
+                risk: None,
+            });
         }
     }
 
@@ -86,29 +97,36 @@ pub async fn check_updates() -> Result<Vec<Package>, String> {
                         maintainer: String::new(),
                         size: String::new(),
                         last_updated: String::new(),
+                        held: false,
+                        install_reason: String::new(),
+                        risk: None,
                     });
                 }
             }
         }
     }
 
-    Ok(updates)
-}
-
-// Get available AUR helper
-fn get_aur_helper() -> Result<&'static str, String> {
-    if Command::new("yay").arg("--version").output().is_ok() {
-        Ok("yay")
-    } else if Command::new("paru").arg("--version").output().is_ok() {
-        Ok("paru")
-    } else {
-        Err("No AUR helper found".to_string())
+    // Held packages still show up, but are marked so they are not selected for updating
+    let held = HeldSet::load();
+    for update in &mut updates {
+        if held.is_held(&update.name, &update.source) {
+            update.held = true;
+            update.description = format!("Held back: {}", update.description);
+        }
     }
+
+    Ok(updates)
 }
 
 // Update system packages
 #[tauri::command]
-pub async fn update_system(password: String, window: tauri::Window) -> Result<String, String> {
+pub async fn update_system(
+    password: String,
+    take_snapshot: Option<bool>,
+    allow_snapshot_failure: Option<bool>,
+    skip_news_check: Option<bool>,
+    window: tauri::Window,
+) -> Result<String, String> {
     use crate::models::InstallProgress;
 
     let emit_progress = |percentage: u32, message: String, completed: bool| {
@@ -122,190 +140,68 @@ pub async fn update_system(password: String, window: tauri::Window) -> Result<St
         );
     };
 
-    emit_progress(10, "Starting system update...".to_string(), false);
-
-    // Update official packages
-    emit_progress(20, ":: Updating official packages...".to_string(), false);
-
-    let mut child = Command::new("sudo")
-        .arg("-S")
-        .args(&["pacman", "-Syu", "--noconfirm"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn sudo: {}", e))?;
+    let job_id = new_job_id();
+    emit_progress(
+        10,
+        format!("Starting system update (job {})...", job_id),
+        false,
+    );
 
-    if let Some(mut stdin) = child.stdin.take() {
-        writeln!(stdin, "{}", password).map_err(|e| format!("Failed to write password: {}", e))?;
+    if !skip_news_check.unwrap_or(false) {
+        check_news_before_update(&window, "update-progress").await?;
     }
 
-    // Stream both stdout and stderr in real-time using separate threads
-    use std::thread;
-
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-
-    let window_clone = window.clone();
-    let stdout_handle = stdout.map(|stdout| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if !line.trim().is_empty() {
-                        let _ = window_clone.emit(
-                            "update-progress",
-                            InstallProgress {
-                                percentage: 30,
-                                message: line,
-                                completed: false,
-                            },
-                        );
-                    }
-                }
-            }
-        })
-    });
-
-    let window_clone = window.clone();
-    let stderr_handle = stderr.map(|stderr| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if !line.trim().is_empty() && !line.contains("[sudo] password") {
-                        let _ = window_clone.emit(
-                            "update-progress",
-                            InstallProgress {
-                                percentage: 30,
-                                message: line,
-                                completed: false,
-                            },
-                        );
-                    }
-                }
-            }
-        })
-    });
-
-    // Wait for streaming threads to complete
-    if let Some(handle) = stdout_handle {
-        let _ = handle.join();
-    }
-    if let Some(handle) = stderr_handle {
-        let _ = handle.join();
+    if take_snapshot.unwrap_or(false) {
+        snapshot_before_update(
+            &password,
+            &job_id,
+            allow_snapshot_failure.unwrap_or(false),
+            &window,
+            "update-progress",
+        )?;
     }
 
-    let output = child
-        .wait_with_output()
-        .map_err(|e| format!("Failed to update official packages: {}", e))?;
+    // Update official packages
+    emit_progress(20, ":: Updating official packages...".to_string(), false);
+
+    let output = spawn_sudo(&password, &["pacman", "-Syu", "--noconfirm"])
+        .and_then(|child| stream_child(child, &window, "update-progress", 30))?;
 
     if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
+        let error = describe_pacman_failure(&output.error_message());
         emit_progress(
             0,
             format!("Official packages update failed: {}", error),
             true,
         );
-        return Err(error.to_string());
+        return Err(error);
     }
 
-    // Update AUR packages if helper is available
-    if let Ok(helper) = get_aur_helper() {
-        emit_progress(50, ":: Updating AUR packages...".to_string(), false);
-
-        // Create a wrapper script that handles password input for sudo
-        // First authenticate sudo, then run AUR helper as regular user
-        let script_content = format!(
-            r#"#!/bin/bash
-echo '{}' | sudo -S -v
-{} -Sua --noconfirm
-"#,
-            password, helper
-        );
-
-        // Write script to temporary file
-        let script_path = "/tmp/archstore_update_aur.sh";
-        if let Ok(_) = std::fs::write(&script_path, script_content) {
-            // Make script executable
-            let _ = Command::new("chmod").args(&["+x", script_path]).output();
-
-            // Execute the script with streaming output
-            let aur_child = Command::new(script_path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn();
-
-            if let Ok(mut child) = aur_child {
-                // Stream both stdout and stderr in real-time
-                let stdout = child.stdout.take();
-                let stderr = child.stderr.take();
-
-                let window_clone = window.clone();
-                let stdout_handle = stdout.map(|stdout| {
-                    thread::spawn(move || {
-                        let reader = BufReader::new(stdout);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                if !line.trim().is_empty() {
-                                    let _ = window_clone.emit(
-                                        "update-progress",
-                                        InstallProgress {
-                                            percentage: 60,
-                                            message: line,
-                                            completed: false,
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                    })
-                });
-
-                let window_clone = window.clone();
-                let stderr_handle = stderr.map(|stderr| {
-                    thread::spawn(move || {
-                        let reader = BufReader::new(stderr);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                if !line.trim().is_empty() && !line.contains("[sudo] password") {
-                                    let _ = window_clone.emit(
-                                        "update-progress",
-                                        InstallProgress {
-                                            percentage: 60,
-                                            message: line,
-                                            completed: false,
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                    })
-                });
-
-                // Wait for streaming threads to complete
-                if let Some(handle) = stdout_handle {
-                    let _ = handle.join();
-                }
-                if let Some(handle) = stderr_handle {
-                    let _ = handle.join();
-                }
-
-                let aur_output = child.wait_with_output();
-                if let Ok(output) = aur_output {
-                    if !output.status.success() {
-                        emit_progress(
-                            60,
-                            "AUR update completed with warnings (continuing...)".to_string(),
-                            false,
-                        );
-                    }
-                }
+    record_successful_update();
+
+    // Rebuild outdated AUR packages; failures there do not fail the whole update
+    // Packages whose build files were not reviewed are skipped and listed at the end
+    let mut unreviewed = Vec::new();
+    match outdated_aur_packages().await {
+        Ok(outdated) if !outdated.is_empty() => {
+            emit_progress(50, ":: Updating AUR packages...".to_string(), false);
+            match update_reviewed_aur(&outdated, &password, &window, "update-progress", &job_id)
+                .await
+            {
+                Ok(skipped) => unreviewed = skipped,
+                Err(e) => emit_progress(
+                    60,
+                    format!("AUR update failed: {} (continuing...)", e),
+                    false,
+                ),
             }
-
-            // Clean up script
-            let _ = std::fs::remove_file(&script_path);
         }
+        Ok(_) => {}
+        Err(e) => emit_progress(
+            60,
+            format!("Could not check AUR updates: {} (continuing...)", e),
+            false,
+        ),
     }
 
     // Update Flatpak packages
@@ -384,113 +280,49 @@ echo '{}' | sudo -S -v
         }
     }
 
-    emit_progress(100, ":: System updated successfully!".to_string(), true);
-    Ok("System updated successfully".to_string())
+    let message = if unreviewed.is_empty() {
+        "System updated successfully".to_string()
+    } else {
+        format!(
+            "System updated; AUR packages not updated until reviewed: {}",
+            unreviewed.join(", ")
+        )
+    };
+    emit_progress(100, format!(":: {}!", message), true);
+    Ok(message)
 }
 
 // Enable multilib repository
 #[tauri::command]
 pub async fn enable_multilib(password: String) -> Result<String, String> {
-    println!("Attempting to enable multilib...");
+    let mut conf = read_pacman_conf()?;
 
-    // Check if multilib is already enabled
-    let check = Command::new("grep")
-        .args(&["-A1", "^\\[multilib\\]$", "/etc/pacman.conf"])
-        .output()
-        .map_err(|e| format!("Failed to check pacman.conf: {}", e))?;
-
-    let check_str = String::from_utf8_lossy(&check.stdout);
-    if check_str.contains("[multilib]") && check_str.contains("Include") {
-        println!("Multilib already enabled");
+    if conf
+        .repositories()
+        .iter()
+        .any(|(name, enabled)| name == "multilib" && *enabled)
+    {
         return Ok("Multilib is already enabled".to_string());
     }
 
-    println!("Multilib not enabled, proceeding with sed commands...");
-
-    // Use sed to uncomment [multilib] section
-    let mut sed1 = Command::new("sudo")
-        .arg("-S")
-        .args(&["sed", "-i", "/^#\\[multilib\\]/s/^#//", "/etc/pacman.conf"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn sed command 1: {}", e))?;
-
-    if let Some(mut stdin) = sed1.stdin.take() {
-        writeln!(stdin, "{}", password).map_err(|e| format!("Failed to write password: {}", e))?;
-    }
-
-    let output1 = sed1
-        .wait_with_output()
-        .map_err(|e| format!("Failed to wait for sed 1: {}", e))?;
-    println!("Sed 1 exit status: {}", output1.status);
-    println!("Sed 1 stderr: {}", String::from_utf8_lossy(&output1.stderr));
-
-    if !output1.status.success() {
-        return Err(format!(
-            "Failed to uncomment [multilib]: {}",
-            String::from_utf8_lossy(&output1.stderr)
-        ));
-    }
-
-    // Use sed to uncomment Include line after [multilib]
-    let mut sed2 = Command::new("sudo")
-        .arg("-S")
-        .args(&[
-            "sed",
-            "-i",
-            "/^\\[multilib\\]$/,/^#Include/ s/^#Include/Include/",
-            "/etc/pacman.conf",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn sed command 2: {}", e))?;
-
-    if let Some(mut stdin) = sed2.stdin.take() {
-        writeln!(stdin, "{}", password).map_err(|e| format!("Failed to write password: {}", e))?;
-    }
-
-    let output2 = sed2
-        .wait_with_output()
-        .map_err(|e| format!("Failed to wait for sed 2: {}", e))?;
-    println!("Sed 2 exit status: {}", output2.status);
-    println!("Sed 2 stderr: {}", String::from_utf8_lossy(&output2.stderr));
-
-    if !output2.status.success() {
-        return Err(format!(
-            "Failed to uncomment Include line: {}",
-            String::from_utf8_lossy(&output2.stderr)
-        ));
+    // Uncomment the stock section, or add it when the file has none
+    if !conf.set_section_enabled("multilib", true) {
+        conf.add_section(
+            "multilib",
+            &[(
+                "Include".to_string(),
+                "/etc/pacman.d/mirrorlist".to_string(),
+            )],
+        );
     }
+    save_pacman_conf(&password, &conf)?;
 
     // Sync package databases
-    println!("Running pacman -Sy...");
-    let mut sync = Command::new("sudo")
-        .arg("-S")
-        .args(&["pacman", "-Sy"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn sync command: {}", e))?;
-
-    if let Some(mut stdin) = sync.stdin.take() {
-        writeln!(stdin, "{}", password).map_err(|e| format!("Failed to write password: {}", e))?;
-    }
-
-    let output3 = sync
-        .wait_with_output()
-        .map_err(|e| format!("Failed to wait for sync: {}", e))?;
-    println!("Sync exit status: {}", output3.status);
-    println!("Sync stderr: {}", String::from_utf8_lossy(&output3.stderr));
-
-    if !output3.status.success() {
+    let output = run_sudo(&password, &["pacman", "-Sy"])?;
+    if !output.status.success() {
         return Err(format!(
             "Failed to sync databases: {}",
-            String::from_utf8_lossy(&output3.stderr)
+            String::from_utf8_lossy(&output.stderr)
         ));
     }
 
@@ -499,233 +331,169 @@ pub async fn enable_multilib(password: String) -> Result<String, String> {
 
 // Update only official packages
 #[tauri::command]
-pub async fn update_official(password: String, window: tauri::Window) -> Result<String, String> {
-    use crate::models::InstallProgress;
+pub async fn update_official(
+    password: String,
+    take_snapshot: Option<bool>,
+    allow_snapshot_failure: Option<bool>,
+    skip_news_check: Option<bool>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    const EVENT: &str = "update-progress";
+
+    run_official_update(
+        &password,
+        take_snapshot.unwrap_or(false),
+        allow_snapshot_failure.unwrap_or(false),
+        skip_news_check.unwrap_or(false),
+        &window,
+        EVENT,
+    )
+    .await?;
 
-    let emit_progress = |percentage: u32, message: String, completed: bool| {
-        let _ = window.emit(
-            "update-progress",
-            InstallProgress {
-                percentage,
-                message,
-                completed,
-            },
-        );
-    };
+    emit_progress(
+        &window,
+        EVENT,
+        100,
+        ":: Official packages updated successfully!".to_string(),
+        true,
+    );
+    Ok("Official packages updated successfully".to_string())
+}
 
+// `pacman -Syu` behind the news gate and the optional pre-update snapshot
+// Shared by update_official and the keyring recovery retry; on failure the job is finished
+// on the given event, on success the caller emits the completion
+pub(crate) async fn run_official_update(
+    password: &str,
+    take_snapshot: bool,
+    allow_snapshot_failure: bool,
+    skip_news_check: bool,
+    window: &tauri::Window,
+    event: &str,
+) -> Result<(), String> {
+    let job_id = new_job_id();
     emit_progress(
+        window,
+        event,
         10,
-        ":: Starting official packages update...".to_string(),
+        format!(":: Starting official packages update (job {})...", job_id),
         false,
     );
 
-    let mut child = Command::new("sudo")
-        .arg("-S")
-        .args(&["pacman", "-Syu", "--noconfirm"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn sudo: {}", e))?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        writeln!(stdin, "{}", password).map_err(|e| format!("Failed to write password: {}", e))?;
+    if !skip_news_check {
+        check_news_before_update(window, event).await?;
     }
 
-    // Stream output in real-time
-    use std::thread;
-
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-
-    let window_clone = window.clone();
-    let stdout_handle = stdout.map(|stdout| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if !line.trim().is_empty() {
-                        let _ = window_clone.emit(
-                            "update-progress",
-                            InstallProgress {
-                                percentage: 50,
-                                message: line,
-                                completed: false,
-                            },
-                        );
-                    }
-                }
-            }
-        })
-    });
-
-    let window_clone = window.clone();
-    let stderr_handle = stderr.map(|stderr| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if !line.trim().is_empty() && !line.contains("[sudo] password") {
-                        let _ = window_clone.emit(
-                            "update-progress",
-                            InstallProgress {
-                                percentage: 50,
-                                message: line,
-                                completed: false,
-                            },
-                        );
-                    }
-                }
-            }
-        })
-    });
-
-    if let Some(handle) = stdout_handle {
-        let _ = handle.join();
-    }
-    if let Some(handle) = stderr_handle {
-        let _ = handle.join();
+    if take_snapshot {
+        snapshot_before_update(password, &job_id, allow_snapshot_failure, window, event)?;
     }
 
-    let output = child
-        .wait_with_output()
-        .map_err(|e| format!("Failed to update official packages: {}", e))?;
+    let output = spawn_sudo(password, &["pacman", "-Syu", "--noconfirm"])
+        .and_then(|child| stream_child(child, window, event, 50))?;
 
     if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
+        let error = describe_pacman_failure(&output.error_message());
         emit_progress(
+            window,
+            event,
             0,
             format!("Official packages update failed: {}", error),
             true,
         );
-        return Err(error.to_string());
+        return Err(error);
     }
 
-    emit_progress(
-        100,
-        ":: Official packages updated successfully!".to_string(),
-        true,
-    );
-    Ok("Official packages updated successfully".to_string())
+    record_successful_update();
+    Ok(())
 }
 
-// Update only AUR packages
+// Update only AUR packages, rebuilding every outdated foreign package natively
 #[tauri::command]
 pub async fn update_aur(password: String, window: tauri::Window) -> Result<String, String> {
-    use crate::models::InstallProgress;
-
-    let emit_progress = |percentage: u32, message: String, completed: bool| {
-        let _ = window.emit(
-            "update-progress",
-            InstallProgress {
-                percentage,
-                message,
-                completed,
-            },
-        );
-    };
-
-    let helper = get_aur_helper().map_err(|e| format!("No AUR helper found: {}", e))?;
+    const EVENT: &str = "update-progress";
 
-    emit_progress(10, ":: Starting AUR packages update...".to_string(), false);
-
-    // Create a wrapper script that handles password input for sudo
-    let script_content = format!(
-        r#"#!/bin/bash
-echo '{}' | sudo -S -v
-{} -Sua --noconfirm
-"#,
-        password, helper
+    emit_progress(
+        &window,
+        EVENT,
+        10,
+        ":: Starting AUR packages update...".to_string(),
+        false,
     );
 
-    let script_path = "/tmp/archstore_update_aur.sh";
-    std::fs::write(&script_path, script_content)
-        .map_err(|e| format!("Failed to create update script: {}", e))?;
-
-    let _ = Command::new("chmod").args(&["+x", script_path]).output();
-
-    let mut child = Command::new(script_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn AUR update: {}", e))?;
-
-    // Stream output in real-time
-    use std::thread;
-
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-
-    let window_clone = window.clone();
-    let stdout_handle = stdout.map(|stdout| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if !line.trim().is_empty() {
-                        let _ = window_clone.emit(
-                            "update-progress",
-                            InstallProgress {
-                                percentage: 50,
-                                message: line,
-                                completed: false,
-                            },
-                        );
-                    }
-                }
-            }
-        })
-    });
-
-    let window_clone = window.clone();
-    let stderr_handle = stderr.map(|stderr| {
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if !line.trim().is_empty() && !line.contains("[sudo] password") {
-                        let _ = window_clone.emit(
-                            "update-progress",
-                            InstallProgress {
-                                percentage: 50,
-                                message: line,
-                                completed: false,
-                            },
-                        );
-                    }
-                }
-            }
-        })
-    });
-
-    if let Some(handle) = stdout_handle {
-        let _ = handle.join();
-    }
-    if let Some(handle) = stderr_handle {
-        let _ = handle.join();
-    }
-
-    let output = child
-        .wait_with_output()
-        .map_err(|e| format!("Failed to update AUR packages: {}", e))?;
-
-    let _ = std::fs::remove_file(&script_path);
-
-    if !output.status.success() {
+    let outdated = outdated_aur_packages().await?;
+    if outdated.is_empty() {
         emit_progress(
+            &window,
+            EVENT,
             100,
-            ":: AUR update completed with warnings".to_string(),
+            ":: AUR packages are up to date".to_string(),
             true,
         );
-    } else {
+        return Ok("AUR packages are up to date".to_string());
+    }
+
+    match update_reviewed_aur(&outdated, &password, &window, EVENT, &new_job_id()).await {
+        Ok(skipped) => {
+            let message = if skipped.is_empty() {
+                "AUR packages updated successfully".to_string()
+            } else if skipped.len() == outdated.len() {
+                format!(
+                    "No AUR packages updated; review their build files first: {}",
+                    skipped.join(", ")
+                )
+            } else {
+                format!(
+                    "AUR packages updated; not updated until reviewed: {}",
+                    skipped.join(", ")
+                )
+            };
+            emit_progress(&window, EVENT, 100, format!(":: {}", message), true);
+            Ok(message)
+        }
+        Err(e) => {
+            emit_progress(&window, EVENT, 0, format!("AUR update failed: {}", e), true);
+            Err(format!("AUR update failed: {}", e))
+        }
+    }
+}
+
+// Outdated AUR packages that are not held back
+async fn outdated_aur_packages() -> Result<Vec<String>, String> {
+    let held = HeldSet::load();
+    Ok(aur_updates()
+        .await?
+        .into_iter()
+        .map(|(info, _)| info.name)
+        .filter(|name| !held.is_held(name, "aur"))
+        .collect())
+}
+
+// Build the AUR updates whose build files were reviewed and approved
+// Returns the skipped targets with their reasons, which are also reported as a warning
+async fn update_reviewed_aur(
+    targets: &[String],
+    password: &str,
+    window: &tauri::Window,
+    event: &str,
+    job_id: &str,
+) -> Result<Vec<String>, String> {
+    let (reviewed, skipped) = split_reviewed_targets(targets).await;
+    if !skipped.is_empty() {
         emit_progress(
-            100,
-            ":: AUR packages updated successfully!".to_string(),
-            true,
+            window,
+            event,
+            50,
+            format!(
+                "warning: skipping AUR packages that need review: {}",
+                skipped.join(", ")
+            ),
+            false,
         );
     }
-
-    Ok("AUR packages updated successfully".to_string())
+    if !reviewed.is_empty() {
+        run_aur_pipeline(&reviewed, password, window, event, job_id).await?;
+    }
+    Ok(skipped)
 }
 
 // Update only Flatpak packages
@@ -758,8 +526,6 @@ pub async fn update_flatpak(window: tauri::Window) -> Result<String, String> {
         .map_err(|e| format!("Failed to spawn flatpak update: {}", e))?;
 
     // Stream output in real-time
-    use std::thread;
-
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
 
@@ -833,14 +599,381 @@ pub async fn update_flatpak(window: tauri::Window) -> Result<String, String> {
     Ok("Flatpak packages updated successfully".to_string())
 }
 
+// Pending official updates against the synced database as name -> (installed version, new version)
+fn pending_official_updates(dbpath: &Path) -> Result<HashMap<String, (String, String)>, String> {
+    let output = Command::new("pacman")
+        .arg("--dbpath")
+        .arg(dbpath)
+        .arg("-Qu")
+        .output()
+        .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+    // pacman -Qu exits with 1 when nothing is upgradable
+    if !output.status.success() && output.status.code() != Some(1) {
+        return Err(format!(
+            "pacman -Qu failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 4 {
+                Some((
+                    parts[0].to_string(),
+                    (parts[1].to_string(), parts[3].to_string()),
+                ))
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+// Fresh sync databases in a private directory, the way checkupdates gets them: the local
+// database is linked in and the current sync databases are copied so only changes download
+// The real sync databases stay untouched until an update actually goes ahead
+fn sync_private_db() -> Result<PrivateDir, String> {
+    let dir = PrivateDir::create("syncdb")?;
+    std::os::unix::fs::symlink(LOCAL_DB_DIR, dir.path().join("local"))
+        .map_err(|e| format!("Failed to link the local database: {}", e))?;
+    let sync = dir.path().join("sync");
+    std::fs::create_dir(&sync)
+        .map_err(|e| format!("Failed to create {}: {}", sync.display(), e))?;
+    for entry in std::fs::read_dir(SYNC_DB_DIR)
+        .map_err(|e| format!("Failed to read {}: {}", SYNC_DB_DIR, e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+    {
+        std::fs::copy(entry.path(), sync.join(entry.file_name()))
+            .map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+    }
+
+    // -Sy wants root; fakeroot is enough for a database directory we own
+    let output = Command::new("fakeroot")
+        .args(["--", "pacman", "-Sy", "--logfile", "/dev/null", "--dbpath"])
+        .arg(dir.path())
+        .output()
+        .map_err(|e| format!("Failed to execute fakeroot: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Database sync failed: {}",
+            describe_pacman_failure(String::from_utf8_lossy(&output.stderr).trim())
+        ));
+    }
+    Ok(dir)
+}
+
+// Install checked sync databases as the real ones, so pacman installs exactly what was checked
+fn install_sync_db(password: &str, dir: &PrivateDir) -> Result<(), String> {
+    let files: Vec<String> = std::fs::read_dir(dir.path().join("sync"))
+        .map_err(|e| format!("Failed to read the checked databases: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.path().to_string_lossy().to_string())
+        .collect();
+    let mut args = vec![
+        "install",
+        "-m",
+        "644",
+        "-o",
+        "root",
+        "-g",
+        "root",
+        "-t",
+        SYNC_DB_DIR,
+        "--",
+    ];
+    args.extend(files.iter().map(|file| file.as_str()));
+    let output = run_sudo(password, &args)?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to install the synced databases: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+// Walk the dependency closure of the selected packages in the synced database and report
+// dependencies that have pending updates but were not selected
+// Dependencies are resolved to their providers first, so soname and virtual dependencies
+// (libicuuc.so=74-64, sh) catch library bumps too
+fn find_partial_upgrade_conflicts(
+    selected: &[String],
+    dbpath: &Path,
+) -> Result<Vec<String>, String> {
+    let updates = pending_official_updates(dbpath)?;
+
+    // Packages already walked, and dependency strings already resolved to them
+    let mut seen: HashSet<String> = selected.iter().cloned().collect();
+    let mut resolved: HashSet<String> = HashSet::new();
+    let mut queue: Vec<String> = selected.to_vec();
+    let mut conflicts = Vec::new();
+
+    while !queue.is_empty() {
+        // Unknown names make pacman exit non-zero, the rest is still printed
+        let output = Command::new("pacman")
+            .arg("--dbpath")
+            .arg(dbpath)
+            .arg("-Si")
+            .args(&queue)
+            .output()
+            .map_err(|e| format!("Failed to execute pacman: {}", e))?;
+
+        let mut next = Vec::new();
+        for info in parse_info_blocks(&String::from_utf8_lossy(&output.stdout)) {
+            let package = info.get("Name").cloned().unwrap_or_default();
+            let depends = info.get("Depends On").cloned().unwrap_or_default();
+
+            for dep in depends.split_whitespace().filter(|dep| *dep != "None") {
+                if !resolved.insert(dep.to_string()) {
+                    continue;
+                }
+                let Some(provider) = repo_provider(dep, Some(dbpath)) else {
+                    continue;
+                };
+                if !seen.insert(provider.clone()) {
+                    continue;
+                }
+                if let Some((old, new)) = updates.get(&provider) {
+                    let needed = if dependency_name(dep) == provider {
+                        provider.clone()
+                    } else {
+                        format!("{} (provided by {})", dep, provider)
+                    };
+                    conflicts.push(format!(
+                        "{} depends on {} which also needs an update ({} -> {})",
+                        package, needed, old, new
+                    ));
+                }
+                next.push(provider);
+            }
+        }
+        queue = next;
+    }
+
+    Ok(conflicts)
+}
+
+// Update only the selected packages
+// Official packages are refused when their dependencies also need upgrading,
+// because updating them on their own would be a partial upgrade
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_selected(
+    official: Vec<String>,
+    aur: Vec<String>,
+    flatpak: Vec<String>,
+    password: String,
+    take_snapshot: Option<bool>,
+    allow_snapshot_failure: Option<bool>,
+    skip_news_check: Option<bool>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    const EVENT: &str = "update-progress";
+
+    if let Some(invalid) = official
+        .iter()
+        .chain(aur.iter())
+        .chain(flatpak.iter())
+        .find(|name| !is_valid_package_name(name))
+    {
+        return Err(format!("Invalid package name: {}", invalid));
+    }
+
+    // Held packages are skipped: with --noconfirm pacman answers its "Install anyway?"
+    // question for ignored packages with yes, and flatpak update ignores masks for named apps
+    let held = HeldSet::load();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut unheld = |names: Vec<String>, source: &str| -> Vec<String> {
+        let (held_names, names): (Vec<String>, Vec<String>) = names
+            .into_iter()
+            .partition(|name| held.is_held(name, source));
+        skipped.extend(held_names);
+        names
+    };
+    let official = unheld(official, "official");
+    let aur = unheld(aur, "aur");
+    let flatpak = unheld(flatpak, "flatpak");
+    if !skipped.is_empty() {
+        emit_progress(
+            &window,
+            EVENT,
+            5,
+            format!("warning: skipping held packages: {}", skipped.join(", ")),
+            false,
+        );
+    }
+
+    let mut failures = Vec::new();
+    let mut unreviewed = Vec::new();
+
+    // Same news gate as the other update paths; the optional snapshot follows the checks
+    if (!official.is_empty() || !aur.is_empty()) && !skip_news_check.unwrap_or(false) {
+        check_news_before_update(&window, EVENT).await?;
+    }
+
+    // Check against a private sync first; a refusal must not leave the real sync
+    // databases newer than the installed system
+    let mut checked_db = None;
+    if !official.is_empty() {
+        emit_progress(
+            &window,
+            EVENT,
+            5,
+            ":: Synchronizing package databases...".to_string(),
+            false,
+        );
+        let dir = match sync_private_db() {
+            Ok(dir) => dir,
+            Err(error) => {
+                emit_progress(&window, EVENT, 0, error.clone(), true);
+                return Err(error);
+            }
+        };
+
+        emit_progress(
+            &window,
+            EVENT,
+            10,
+            ":: Checking dependencies of selected official packages...".to_string(),
+            false,
+        );
+
+        let conflicts = find_partial_upgrade_conflicts(&official, dir.path())?;
+        if !conflicts.is_empty() {
+            for conflict in &conflicts {
+                emit_progress(&window, EVENT, 10, format!("warning: {}", conflict), false);
+            }
+            let message = format!(
+                "Refusing to update selected packages on their own (partial upgrade): {}. Select the dependencies too or run a full system update.",
+                conflicts.join("; ")
+            );
+            emit_progress(&window, EVENT, 0, message.clone(), true);
+            return Err(message);
+        }
+        checked_db = Some(dir);
+    }
+
+    if take_snapshot.unwrap_or(false) && (!official.is_empty() || !aur.is_empty()) {
+        snapshot_before_update(
+            &password,
+            &new_job_id(),
+            allow_snapshot_failure.unwrap_or(false),
+            &window,
+            EVENT,
+        )?;
+    }
+
+    if let Some(dir) = checked_db {
+        // The update goes ahead: install against exactly the databases that were checked
+        if let Err(error) = install_sync_db(&password, &dir) {
+            emit_progress(&window, EVENT, 0, error.clone(), true);
+            return Err(error);
+        }
+        drop(dir);
+
+        emit_progress(
+            &window,
+            EVENT,
+            20,
+            format!(":: Updating {} official package(s)...", official.len()),
+            false,
+        );
+
+        let mut args = vec!["pacman", "-S", "--needed", "--noconfirm"];
+        args.extend(official.iter().map(|name| name.as_str()));
+        let output = stream_child(spawn_sudo(&password, &args)?, &window, EVENT, 30)?;
+        if !output.status.success() {
+            let error = describe_pacman_failure(&output.error_message());
+            emit_progress(
+                &window,
+                EVENT,
+                0,
+                format!("Official packages update failed: {}", error),
+                true,
+            );
+            return Err(error);
+        }
+    }
+
+    if !aur.is_empty() {
+        emit_progress(
+            &window,
+            EVENT,
+            50,
+            format!(":: Updating {} AUR package(s)...", aur.len()),
+            false,
+        );
+        match update_reviewed_aur(&aur, &password, &window, EVENT, &new_job_id()).await {
+            Ok(skipped) => unreviewed = skipped,
+            Err(e) => failures.push(format!("AUR: {}", e)),
+        }
+    }
+
+    if !flatpak.is_empty() {
+        emit_progress(
+            &window,
+            EVENT,
+            75,
+            format!(":: Updating {} Flatpak package(s)...", flatpak.len()),
+            false,
+        );
+
+        let child = Command::new("flatpak")
+            .args(["update", "-y"])
+            .args(&flatpak)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to spawn flatpak update: {}", e));
+
+        match child.and_then(|child| stream_child(child, &window, EVENT, 85)) {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => failures.push(format!("Flatpak: {}", output.error_message())),
+            Err(e) => failures.push(format!("Flatpak: {}", e)),
+        }
+    }
+
+    if !failures.is_empty() {
+        let message = format!("Some updates failed: {}", failures.join("; "));
+        emit_progress(&window, EVENT, 100, message.clone(), true);
+        return Err(message);
+    }
+
+    let mut notes = Vec::new();
+    if !skipped.is_empty() {
+        notes.push(format!("held, not updated: {}", skipped.join(", ")));
+    }
+    if !unreviewed.is_empty() {
+        notes.push(format!(
+            "not updated until reviewed: {}",
+            unreviewed.join(", ")
+        ));
+    }
+    let message = if notes.is_empty() {
+        "Selected packages updated successfully".to_string()
+    } else {
+        format!(
+            "Selected packages updated successfully ({})",
+            notes.join("; ")
+        )
+    };
+    emit_progress(&window, EVENT, 100, format!(":: {}!", message), true);
+    Ok(message)
+}
+
 // Check which package sources are available on the system
 #[tauri::command]
 pub fn check_system_capabilities() -> Result<serde_json::Value, String> {
     let mut capabilities = serde_json::json!({});
 
-    // Check for AUR helper (yay or paru)
-    let has_aur = Command::new("yay").arg("--version").output().is_ok()
-        || Command::new("paru").arg("--version").output().is_ok();
+    // AUR packages are built natively, which needs git and makepkg (base-devel)
+    let has_aur = Command::new("git").arg("--version").output().is_ok()
+        && Command::new("makepkg").arg("--version").output().is_ok();
     capabilities["has_aur_helper"] = serde_json::json!(has_aur);
 
     // Check for Flatpak
@@ -848,19 +981,11 @@ pub fn check_system_capabilities() -> Result<serde_json::Value, String> {
     capabilities["has_flatpak"] = serde_json::json!(has_flatpak);
 
     // Check if multilib is already enabled
-    let multilib_enabled = std::fs::read_to_string("/etc/pacman.conf")
-        .map(|content| {
-            let lines: Vec<&str> = content.lines().collect();
-            let mut found_multilib = false;
-            for line in lines {
-                let trimmed = line.trim();
-                if trimmed == "[multilib]" {
-                    found_multilib = true;
-                } else if found_multilib && trimmed.starts_with("Include") {
-                    return true;
-                }
-            }
-            false
+    let multilib_enabled = read_pacman_conf()
+        .map(|conf| {
+            conf.repositories()
+                .iter()
+                .any(|(name, enabled)| name == "multilib" && *enabled)
         })
         .unwrap_or(false);
     capabilities["multilib_enabled"] = serde_json::json!(multilib_enabled);
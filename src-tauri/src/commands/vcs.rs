@@ -0,0 +1,410 @@
+use crate::commands::aur::{aur_info, foreign_packages};
+use crate::commands::review::{aur_clone_dir, built_revisions, sync_aur_repo};
+use crate::models::{VcsRevision, VcsUpdate};
+use crate::parsers::parse_vcs_sources;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+// AUR naming convention for packages built from a VCS checkout
+// Only git and svn sources are parsed and compared; -hg, -bzr, -fossil and -darcs packages
+// are not checked
+const VCS_SUFFIXES: [&str; 2] = ["-git", "-svn"];
+
+fn branch_ref(branch: Option<&str>) -> String {
+    branch.map_or_else(
+        || "HEAD".to_string(),
+        |branch| format!("refs/heads/{}", branch),
+    )
+}
+
+// Commit a remote branch (or the remote HEAD) points at, without cloning
+fn remote_revision(url: &str, branch: Option<&str>) -> Result<String, String> {
+    let wanted = branch_ref(branch);
+    let output = Command::new("git")
+        .args(["ls-remote", url, &wanted])
+        // Private or moved repositories must fail instead of waiting for credentials
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to execute git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git ls-remote {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // Patterns match ref name suffixes, so "HEAD" may also list e.g. refs/remotes/origin/HEAD
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(_, name)| *name == wanted)
+        .map(|(commit, _)| commit.to_string())
+        .ok_or_else(|| format!("{} has no {}", url, wanted))
+}
+
+// Commit a local (bare mirror) repository has for a branch or HEAD
+fn mirror_revision(mirror: &Path, branch: Option<&str>) -> Option<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(mirror)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", branch_ref(branch)))
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Last revision that changed an svn URL or working copy
+// The repository-wide revision would move with every commit anywhere in the repository,
+// not only under the checked out path
+fn svn_revision(target: &str) -> Result<String, String> {
+    let output = Command::new("svn")
+        .args([
+            "info",
+            "--non-interactive",
+            "--show-item",
+            "last-changed-revision",
+            target,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute svn: {}", e))?;
+    let revision = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || revision.is_empty() {
+        return Err(format!(
+            "svn info {} failed: {}",
+            target,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(revision)
+}
+
+// Current upstream revision of a git or svn source
+fn latest_revision(vcs: &str, url: &str, branch: Option<&str>) -> Result<String, String> {
+    match vcs {
+        "svn" => svn_revision(url),
+        _ => remote_revision(url, branch),
+    }
+}
+
+// Revisions the VCS sources of a package base were at when makepkg last fetched them
+// makepkg keeps a bare mirror of each git source, or an svn working copy, next to the
+// PKGBUILD unless SRCDEST is set; without one, the current remote revision is the closest
+// estimate
+pub(crate) fn local_revisions(pkgbase: &str) -> Vec<VcsRevision> {
+    let dir = aur_clone_dir(pkgbase);
+    let srcinfo = std::fs::read_to_string(dir.join(".SRCINFO")).unwrap_or_default();
+
+    parse_vcs_sources(&srcinfo)
+        .into_iter()
+        .filter_map(|source| {
+            let branch = source.branch.as_deref();
+            let checkout = dir.join(&source.directory);
+            let local = match source.vcs.as_str() {
+                "svn" if checkout.join(".svn").exists() => {
+                    svn_revision(&checkout.to_string_lossy()).ok()
+                }
+                "svn" => None,
+                _ => mirror_revision(&checkout, branch),
+            };
+            let revision =
+                local.or_else(|| latest_revision(&source.vcs, &source.url, branch).ok())?;
+            Some(VcsRevision {
+                vcs: source.vcs,
+                url: source.url,
+                branch: source.branch,
+                revision,
+            })
+        })
+        .collect()
+}
+
+// Revision most VCS pkgver() functions embed
+// git: the commit abbreviation in "r1234.abc1234-1" or "1.2.r3.gabc1234-1"; all-digit matches
+// are more likely dates than commits
+// svn: the revision number in "r1234-1" or "1.2.r1234-1"
+fn version_revision(version: &str, vcs: &str) -> Option<String> {
+    if vcs == "svn" {
+        let pattern = Regex::new(r"(?:^|[._+])r([0-9]+)-[^-]+$").unwrap();
+        return pattern
+            .captures(version)
+            .map(|captures| captures[1].to_string());
+    }
+    let pattern = Regex::new(r"[._+]g?([0-9a-f]{7,40})-[^-]+$").unwrap();
+    pattern
+        .captures(version)
+        .map(|captures| captures[1].to_string())
+        .filter(|revision| revision.chars().any(|c| c.is_ascii_alphabetic()))
+}
+
+// VCS packages whose upstream moved since they were built
+// Regular AUR updates only see PKGBUILD changes; these need `git ls-remote` or `svn info`
+// against the sources
+#[tauri::command]
+pub async fn check_vcs_updates() -> Result<Vec<VcsUpdate>, String> {
+    let installed: Vec<(String, String)> = foreign_packages()
+        .into_iter()
+        .filter(|(name, _)| VCS_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)))
+        .collect();
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<String> = installed.iter().map(|(name, _)| name.clone()).collect();
+    let bases: HashMap<String, String> = aur_info(&names)
+        .await?
+        .into_iter()
+        .map(|info| (info.name, info.package_base))
+        .collect();
+
+    // Split packages share their sources; ask each remote once
+    let mut latest: HashMap<(String, String, Option<String>), Option<String>> = HashMap::new();
+    let mut updates = Vec::new();
+
+    for (name, version) in installed {
+        // Packages no longer in the AUR cannot be rebuilt from it
+        let Some(pkgbase) = bases.get(&name) else {
+            continue;
+        };
+
+        let revisions = match built_revisions(pkgbase, &name, &version) {
+            Some(revisions) => revisions,
+            // Built outside ArchStore: compare the main source with the commit in the version
+            None => {
+                let dir = aur_clone_dir(pkgbase);
+                if !dir.join(".SRCINFO").exists() && sync_aur_repo(pkgbase).is_err() {
+                    continue;
+                }
+                let srcinfo = std::fs::read_to_string(dir.join(".SRCINFO")).unwrap_or_default();
+                parse_vcs_sources(&srcinfo)
+                    .into_iter()
+                    .take(1)
+                    .filter_map(|source| {
+                        Some(VcsRevision {
+                            revision: version_revision(&version, &source.vcs)?,
+                            vcs: source.vcs,
+                            url: source.url,
+                            branch: source.branch,
+                        })
+                    })
+                    .collect()
+            }
+        };
+
+        let moved = moved_sources(revisions, |built| {
+            let branch = built.branch.as_deref();
+            latest
+                .entry((built.vcs.clone(), built.url.clone(), built.branch.clone()))
+                .or_insert_with(|| latest_revision(&built.vcs, &built.url, branch).ok())
+                .clone()
+        });
+        for (built, current) in moved {
+            updates.push(VcsUpdate {
+                name: name.clone(),
+                pkgbase: pkgbase.clone(),
+                version: version.clone(),
+                url: built.url,
+                branch: built.branch,
+                built_revision: built.revision,
+                latest_revision: current,
+            });
+        }
+    }
+
+    Ok(updates)
+}
+
+// Built revisions whose source moved on, paired with the current revision
+// Built git commits may be abbreviated, svn revisions are numbers compared as a whole;
+// unreachable upstreams (latest returns None) are skipped rather than reported as updates
+fn moved_sources(
+    built: Vec<VcsRevision>,
+    mut latest: impl FnMut(&VcsRevision) -> Option<String>,
+) -> Vec<(VcsRevision, String)> {
+    built
+        .into_iter()
+        .filter_map(|built| {
+            let current = latest(&built)?;
+            let same = match built.vcs.as_str() {
+                "svn" => current == built.revision,
+                _ => current.starts_with(&built.revision),
+            };
+            (!same).then_some((built, current))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::storage::PrivateDir;
+    use std::path::PathBuf;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    // A bare "upstream" with commits on main and dev, pushed from a work tree
+    struct Upstream {
+        _dir: PrivateDir,
+        bare: PathBuf,
+        work: PathBuf,
+    }
+
+    impl Upstream {
+        fn new() -> Upstream {
+            let dir = PrivateDir::create("vcs-test").unwrap();
+            let bare = dir.path().join("upstream.git");
+            let work = dir.path().join("work");
+            git(
+                dir.path(),
+                &["init", "--bare", "-b", "main", "upstream.git"],
+            );
+            git(dir.path(), &["init", "-b", "main", "work"]);
+            let upstream = Upstream {
+                _dir: dir,
+                bare,
+                work,
+            };
+            upstream.commit_and_push("main");
+            upstream
+        }
+
+        fn url(&self) -> String {
+            self.bare.to_string_lossy().to_string()
+        }
+
+        fn commit_and_push(&self, branch: &str) -> String {
+            git(&self.work, &["checkout", "-q", "-B", branch]);
+            git(&self.work, &["commit", "-q", "--allow-empty", "-m", branch]);
+            git(&self.work, &["push", "-q", &self.url(), branch]);
+            git(&self.work, &["rev-parse", "HEAD"])
+        }
+    }
+
+    #[test]
+    fn remote_revision_follows_branches_and_head() {
+        let upstream = Upstream::new();
+        let main = upstream.commit_and_push("main");
+        let dev = upstream.commit_and_push("dev");
+
+        assert_eq!(remote_revision(&upstream.url(), None), Ok(main.clone()));
+        assert_eq!(remote_revision(&upstream.url(), Some("main")), Ok(main));
+        assert_eq!(remote_revision(&upstream.url(), Some("dev")), Ok(dev));
+        assert!(remote_revision(&upstream.url(), Some("missing")).is_err());
+    }
+
+    #[test]
+    fn mirror_revision_reads_a_bare_mirror() {
+        let upstream = Upstream::new();
+        let main = upstream.commit_and_push("main");
+        let dev = upstream.commit_and_push("dev");
+
+        assert_eq!(mirror_revision(&upstream.bare, None), Some(main));
+        assert_eq!(mirror_revision(&upstream.bare, Some("dev")), Some(dev));
+        assert_eq!(mirror_revision(&upstream.bare, Some("missing")), None);
+        assert_eq!(mirror_revision(&upstream.work.join("missing"), None), None);
+    }
+
+    #[test]
+    fn moved_sources_compares_against_the_remote() {
+        let upstream = Upstream::new();
+        let built = upstream.commit_and_push("main");
+        let revision = |revision: &str, branch: Option<&str>| VcsRevision {
+            vcs: "git".to_string(),
+            url: upstream.url(),
+            branch: branch.map(str::to_string),
+            revision: revision.to_string(),
+        };
+        let latest =
+            |built: &VcsRevision| remote_revision(&built.url, built.branch.as_deref()).ok();
+
+        // Up to date, also when the built revision is abbreviated
+        assert!(moved_sources(vec![revision(&built, None)], latest).is_empty());
+        assert!(moved_sources(vec![revision(&built[..7], None)], latest).is_empty());
+
+        let current = upstream.commit_and_push("main");
+        let moved = moved_sources(vec![revision(&built[..7], Some("main"))], latest);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].0.revision, built[..7]);
+        assert_eq!(moved[0].1, current);
+
+        // Unreachable upstreams are not reported
+        let gone = VcsRevision {
+            vcs: String::new(),
+            url: upstream
+                .work
+                .join("missing.git")
+                .to_string_lossy()
+                .to_string(),
+            branch: None,
+            revision: built,
+        };
+        assert!(moved_sources(vec![gone], latest).is_empty());
+    }
+
+    #[test]
+    fn parsed_sources_resolve_against_the_remote() {
+        let upstream = Upstream::new();
+        let dev = upstream.commit_and_push("dev");
+        let srcinfo = format!(
+            "pkgbase = foo-git\n\tsource = foo::git+file://{}#branch=dev\n",
+            upstream.url()
+        );
+
+        let sources = parse_vcs_sources(&srcinfo);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(
+            remote_revision(&sources[0].url, sources[0].branch.as_deref()),
+            Ok(dev)
+        );
+    }
+
+    #[test]
+    fn moved_sources_compares_svn_revisions_whole() {
+        let built = |revision: &str| VcsRevision {
+            vcs: "svn".to_string(),
+            url: "https://example.com/svn/foo/trunk".to_string(),
+            branch: None,
+            revision: revision.to_string(),
+        };
+        let latest = |_: &VcsRevision| Some("1234".to_string());
+
+        assert!(moved_sources(vec![built("1234")], latest).is_empty());
+        // 123 is a prefix of 1234 but a different revision
+        let moved = moved_sources(vec![built("123")], latest);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].1, "1234");
+    }
+
+    #[test]
+    fn version_revision_reads_git_and_svn_versions() {
+        assert_eq!(
+            version_revision("r1234.abc1234-1", "git"),
+            Some("abc1234".to_string())
+        );
+        assert_eq!(
+            version_revision("1.2.r3.gabc1234-1", "git"),
+            Some("abc1234".to_string())
+        );
+        assert_eq!(version_revision("20240101.1234567-1", "git"), None);
+        assert_eq!(version_revision("r1234-1", "svn"), Some("1234".to_string()));
+        assert_eq!(
+            version_revision("1.2.r5678-2", "svn"),
+            Some("5678".to_string())
+        );
+        assert_eq!(version_revision("1.2-1", "svn"), None);
+    }
+}
@@ -24,10 +24,64 @@ fn main() {
             update_official,
             update_aur,
             update_flatpak,
+            update_selected,
             check_updates,
             enable_multilib,
             check_system_capabilities,
             get_app_icon,
+            list_archive_versions,
+            downgrade_package,
+            get_snapshot_status,
+            list_snapshots,
+            get_arch_news,
+            get_pending_news,
+            mark_news_read,
+            scan_config_changes,
+            get_config_diff,
+            resolve_config_change,
+            get_held_packages,
+            hold_package,
+            unhold_package,
+            list_orphans,
+            remove_orphans,
+            analyze_package_cache,
+            clean_package_cache,
+            preview_install_reason,
+            set_install_reason,
+            get_dependency_graph,
+            get_install_chains,
+            list_package_files,
+            find_file_owner,
+            search_files,
+            sync_files_databases,
+            search_command,
+            check_package_integrity,
+            reinstall_packages,
+            get_pacman_config,
+            preview_pacman_conf_changes,
+            apply_pacman_conf_changes,
+            list_custom_repositories,
+            add_custom_repository,
+            remove_custom_repository,
+            search_custom_packages,
+            get_mirrorlist,
+            get_mirror_status,
+            rank_mirrors,
+            preview_mirrorlist,
+            save_mirrorlist,
+            classify_update_error,
+            get_keyring_status,
+            recover_keyring,
+            review_aur_package,
+            approve_aur_review,
+            revoke_aur_approval,
+            get_aur_risk_report,
+            install_aur_packages,
+            get_aur_build_plan,
+            check_vcs_updates,
+            check_rebuilds,
+            rebuild_aur_packages,
+            audit_foreign_packages,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -13,6 +13,10 @@ pub struct Package {
     pub maintainer: String,
     pub size: String,
     pub last_updated: String,
+    pub held: bool,             // IgnorePkg/IgnoreGroup or flatpak mask
+    pub install_reason: String, // "explicit", "dependency", empty when not installed through pacman
+    #[serde(default)]
+    pub risk: Option<RiskReport>, // AUR packages only
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -21,3 +25,535 @@ pub struct InstallProgress {
     pub message: String,
     pub completed: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveVersion {
+    pub name: String,
+    pub version: String,
+    pub arch: String,
+    pub filename: String,
+    pub url: String,
+    pub size: String,
+    pub last_updated: String,
+    pub has_signature: bool,
+    pub cached: bool, // already present in /var/cache/pacman/pkg
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    pub id: String,
+    pub backend: String, // "snapper", "timeshift", "btrfs"
+    pub job_id: String,
+    pub description: String,
+    pub created: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotStatus {
+    pub available: bool,
+    pub backend: String,
+    pub snapper_configs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewsItem {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub published: String, // RFC 3339
+    pub summary: String,
+    pub read: bool,
+    pub manual_intervention: bool,
+    pub since_last_update: bool,
+    pub affected_packages: Vec<String>, // installed packages mentioned in the item
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigFileChange {
+    pub path: String,     // live file, e.g. /etc/pacman.conf
+    pub new_path: String, // /etc/pacman.conf.pacnew
+    pub kind: String,     // "pacnew", "pacsave"
+    pub package: String,
+    pub live_exists: bool,
+    pub modified: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HeldPackages {
+    pub packages: Vec<String>, // IgnorePkg
+    pub groups: Vec<String>,   // IgnoreGroup
+    pub flatpak: Vec<String>,  // flatpak mask patterns
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Orphan {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub installed_size: String,
+    pub size_bytes: u64,
+    pub optional_for: Vec<String>, // non-empty for optional-dependency-only orphans
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanReport {
+    pub orphans: Vec<Orphan>,
+    pub total_bytes: u64,
+    pub total_size: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedPackageFile {
+    pub filename: String,
+    pub path: String,
+    pub version: String,
+    pub size: String,
+    pub size_bytes: u64,
+    pub remove: bool, // selected for removal by the cleanup
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedPackage {
+    pub name: String,
+    pub arch: String,
+    pub installed_version: Option<String>,
+    pub files: Vec<CachedPackageFile>, // newest first
+    pub total_bytes: u64,
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HelperCache {
+    pub helper: String, // "yay", "paru"
+    pub path: String,
+    pub size: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheReport {
+    pub cache_dirs: Vec<String>,
+    pub packages: Vec<CachedPackage>,
+    pub helper_caches: Vec<HelperCache>,
+    pub total_size: String,
+    pub reclaimable_bytes: u64,
+    pub reclaimable_size: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallReasonChange {
+    pub packages: Vec<String>,
+    pub reason: String,                  // "dependency", "explicit"
+    pub orphaned: Vec<String>,           // become orphans with the new reason
+    pub no_longer_orphaned: Vec<String>, // stop being orphans with the new reason
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DependencyNode {
+    pub name: String,
+    pub version: String,
+    pub installed: bool,
+    pub explicit: bool,
+    pub depth: u32,
+    pub missing: bool, // dependency not found in any database
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DependencyEdge {
+    pub from: String,             // the package that depends
+    pub to: String,               // the package it depends on
+    pub kind: String,             // "depends", "optional"
+    pub provides: Option<String>, // virtual name when satisfied through provides
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DependencyGraph {
+    pub root: String,
+    pub direction: String, // "forward", "reverse", "both"
+    pub max_depth: u32,
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    pub truncated: bool, // the depth limit cut off further packages
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DependencyChain {
+    pub root: String, // explicitly installed package at the top of the chain
+    pub links: Vec<DependencyEdge>, // from the root down to the package
+    pub optional: bool, // some link is only an optional dependency
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallChains {
+    pub package: String,
+    pub explicit: bool, // the package itself was installed explicitly
+    pub chains: Vec<DependencyChain>, // shortest first
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileMatch {
+    pub repository: String,
+    pub package: String,
+    pub version: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileOwner {
+    pub path: String,
+    pub package: String,
+    pub version: String,
+    pub repository: Option<String>, // None for installed packages
+    pub installed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageFiles {
+    pub package: String,
+    pub installed: bool, // from the local database rather than the files databases
+    pub files: Vec<String>,
+}
+
+// Package metadata as returned by the AUR RPC interface
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct AurPackageInfo {
+    pub name: String,
+    pub package_base: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "URL", default)]
+    pub url: Option<String>,
+    #[serde(rename = "URLPath", default)]
+    pub url_path: Option<String>,
+    #[serde(default)]
+    pub maintainer: Option<String>, // None for orphaned packages
+    #[serde(default)]
+    pub num_votes: u64,
+    #[serde(default)]
+    pub popularity: f64,
+    #[serde(default)]
+    pub out_of_date: Option<i64>,
+    #[serde(default)]
+    pub first_submitted: i64,
+    #[serde(default)]
+    pub last_modified: i64,
+    #[serde(default)]
+    pub depends: Vec<String>,
+    #[serde(default)]
+    pub make_depends: Vec<String>,
+    #[serde(default)]
+    pub check_depends: Vec<String>,
+    #[serde(default)]
+    pub opt_depends: Vec<String>,
+    #[serde(default)]
+    pub provides: Vec<String>,
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+    #[serde(default)]
+    pub replaces: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityIssue {
+    pub package: String,
+    pub path: String,
+    pub problem: String, // as reported by pacman, e.g. "SHA256 checksum mismatch"
+    pub backup: bool,    // a backup (config) file, changes are expected
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityReport {
+    pub checked_packages: u32,
+    pub issues: Vec<IntegrityIssue>,         // real corruption
+    pub backup_changes: Vec<IntegrityIssue>, // modified config files
+    pub affected_packages: Vec<String>,      // packages with real corruption
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IncludeFile {
+    pub path: String,
+    pub servers: Vec<String>, // active Server lines
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Repository {
+    pub name: String,
+    pub enabled: bool,
+    pub sig_level: Option<String>,
+    pub servers: Vec<String>, // Server lines in pacman.conf itself
+    pub includes: Vec<IncludeFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PacmanConfig {
+    pub parallel_downloads: Option<u32>,
+    pub color: bool,
+    pub verbose_pkg_lists: bool,
+    pub check_space: bool,
+    pub sig_level: Option<String>,
+    pub repositories: Vec<Repository>, // in file order
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PacmanConfEdit {
+    pub action: String, // "set_repo_enabled", "remove_repo", "set_sig_level", "set_servers", "set_option"
+    #[serde(default)]
+    pub repo: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub values: Vec<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PacmanConfPreview {
+    pub diff: String,
+    pub content: String,
+    pub config: PacmanConfig, // the configuration after the edits
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewCustomRepository {
+    pub name: String,
+    #[serde(default)]
+    pub servers: Vec<String>,
+    #[serde(default)]
+    pub include: Option<String>, // e.g. /etc/pacman.d/chaotic-mirrorlist
+    #[serde(default)]
+    pub sig_level: Option<String>,
+    #[serde(default)]
+    pub key_id: Option<String>,
+    #[serde(default)]
+    pub keyserver: Option<String>,
+    #[serde(default)]
+    pub setup_packages: Vec<String>, // keyring/mirrorlist package URLs or files, installed with -U
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomRepository {
+    pub name: String,
+    pub enabled: bool,
+    pub sig_level: Option<String>,
+    pub servers: Vec<String>,
+    pub includes: Vec<String>,
+    pub key_id: Option<String>, // key imported when the repo was added
+    pub packages: Vec<String>,  // packages installed when the repo was added
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MirrorEntry {
+    pub server: String, // e.g. https://geo.mirror.pkgbuild.com/$repo/os/$arch
+    pub enabled: bool,
+    pub country: String, // from the "## Country" comment above it
+}
+
+// One entry of archlinux.org/mirrors/status/json/
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MirrorStatus {
+    pub url: String, // base URL, without $repo/os/$arch
+    pub protocol: String,
+    #[serde(default)]
+    pub last_sync: Option<String>,
+    #[serde(default)]
+    pub completion_pct: Option<f64>, // 0.0 - 1.0
+    #[serde(default)]
+    pub delay: Option<i64>, // seconds behind the tier 0 mirror
+    #[serde(default)]
+    pub score: Option<f64>, // lower is better
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub country_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MirrorFilter {
+    #[serde(default)]
+    pub countries: Vec<String>, // names or country codes, empty for all
+    #[serde(default)]
+    pub protocols: Vec<String>, // "https", "http", "rsync"; empty for http(s)
+    #[serde(default)]
+    pub min_completion: Option<f64>, // 0.0 - 1.0
+    #[serde(default)]
+    pub max_delay: Option<i64>, // seconds
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RankedMirror {
+    pub server: String,
+    pub country: String,
+    pub score: Option<f64>,
+    pub latency_ms: Option<u64>,
+    pub throughput: Option<u64>, // bytes per second
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MirrorlistPreview {
+    pub diff: String,
+    pub content: String,
+}
+
+// A signature or keyring failure recognised in pacman's output
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignatureProblem {
+    pub kind: String, // "keyring_not_initialized", "unknown_key", "expired_key", "unknown_trust", "marginal_trust", "invalid_signature", "corrupted_database", "corrupted_package"
+    pub summary: String,
+    pub packages: Vec<String>, // packages named in the errors
+    pub files: Vec<String>,    // cached package files that failed the check
+    pub keys: Vec<String>,     // key IDs named in the errors
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyringKey {
+    pub key_id: String,
+    pub fingerprint: String,
+    pub uid: String,
+    pub validity: String, // "full", "marginal", "expired", "revoked", "unknown", ...
+    pub created: Option<i64>, // Unix timestamps
+    pub expires: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyringStatus {
+    pub initialized: bool,
+    pub total_keys: u32,
+    pub trusted_keys: u32, // full or ultimate validity
+    pub marginal_keys: u32,
+    pub expired_keys: u32,
+    pub revoked_keys: u32,
+    pub expiring_soon: Vec<KeyringKey>, // trusted keys expiring within 90 days
+    pub keys: Vec<KeyringKey>,
+    pub keyring_version: Option<String>, // installed archlinux-keyring
+    pub available_version: Option<String>, // archlinux-keyring in the sync databases
+    pub keyring_outdated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewFile {
+    pub name: String,   // PKGBUILD or an install script
+    pub status: String, // "new", "changed", "unchanged", "removed" (compared to the last build)
+    pub content: String,
+    pub diff: Option<String>, // unified diff against the last built version
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AurReview {
+    pub package: String,
+    pub pkgbase: String,
+    pub version: String,
+    pub commit: String, // AUR git commit under review
+    pub last_built_commit: Option<String>,
+    pub last_built_version: Option<String>,
+    pub files: Vec<ReviewFile>,
+    pub approved: bool, // this commit was already approved
+    pub risk: RiskReport,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RiskFinding {
+    pub severity: String, // "info", "warning", "danger"
+    pub category: String, // "remote_script", "obfuscation", "system_write", "privilege", "unpinned_source", "missing_checksums", "network", "maintenance", ...
+    pub message: String,
+    pub file: Option<String>, // None for findings from AUR metadata
+    pub line: Option<u32>,
+    pub excerpt: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RiskReport {
+    pub level: String, // "low", "medium", "high"
+    pub score: u32,
+    pub findings: Vec<RiskFinding>,
+    pub scripts_scanned: bool, // false when only AUR metadata was checked
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AurBuildStep {
+    pub pkgbase: String,
+    pub version: String,
+    pub packages: Vec<String>,    // packages of the base that get installed
+    pub as_dependency: bool,      // installed with --asdeps
+    pub required_by: Vec<String>, // planned packages depending on this base
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AurBuildPlan {
+    pub targets: Vec<String>,
+    pub steps: Vec<AurBuildStep>,       // in build order
+    pub repo_dependencies: Vec<String>, // installed from the sync repositories first
+}
+
+// Progress of the native AUR pipeline; a superset of InstallProgress
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AurProgress {
+    pub job_id: String,
+    pub stage: String, // "resolve", "review", "repo_deps", "build", "install", "done", "failed"
+    pub package: Option<String>, // package base the stage is working on
+    pub step: u32,
+    pub total_steps: u32,
+    pub percentage: u32,
+    pub message: String,
+    pub completed: bool,
+}
+
+// A git or svn source of a VCS package that follows upstream, from .SRCINFO
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct VcsSource {
+    pub vcs: String,            // "git" or "svn"
+    pub directory: String,      // makepkg's checkout name, e.g. "foo" for foo::git+https://...
+    pub url: String,            // without the git+/svn+ prefix and fragment
+    pub branch: Option<String>, // None follows the remote HEAD; always None for svn
+}
+
+// Upstream revision a VCS source was built from
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VcsRevision {
+    #[serde(default)]
+    pub vcs: String, // "svn", or "git" (empty in records written before svn was supported)
+    pub url: String,
+    pub branch: Option<String>,
+    pub revision: String, // full commit or svn revision, or the one embedded in the version
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VcsUpdate {
+    pub name: String,
+    pub pkgbase: String,
+    pub version: String, // installed
+    pub url: String,
+    pub branch: Option<String>,
+    pub built_revision: String,
+    pub latest_revision: String,
+}
+
+// A foreign package that likely needs a rebuild after a library or python update
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RebuildCandidate {
+    pub name: String,
+    pub version: String,
+    pub missing_libraries: Vec<String>, // sonames no installed library provides
+    pub broken_files: Vec<String>,      // ELF files needing them
+    pub stale_python: Option<String>,   // python version of its site-packages, e.g. "3.11"
+}
+
+// Where a foreign (-Qm) package comes from and where it could come from instead
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ForeignPackageAudit {
+    pub name: String,
+    pub version: String,
+    pub status: String, // "aur_present", "aur_missing", "official_available", "local_only"
+    pub aur_version: Option<String>,
+    pub replacement: Option<String>,        // package to migrate to
+    pub replacement_source: Option<String>, // "official" or "aur"
+    pub matched_by: Option<String>,         // "name", "replaces" or "provides"
+    pub suggestion: String,
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+// Parse a libalpm database entry (desc, files, depends) into its sections
+// Entries look like:
+// %NAME%
+// pacman
+//
+// %DEPENDS%
+// bash
+// glibc
+pub fn parse_db_entry(content: &str) -> HashMap<String, Vec<String>> {
+    let mut sections: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        if line.len() > 2 && line.starts_with('%') && line.ends_with('%') {
+            let key = line.trim_matches('%').to_string();
+            sections.entry(key.clone()).or_default();
+            current = Some(key);
+        } else if line.is_empty() {
+            current = None;
+        } else if let Some(key) = &current {
+            sections
+                .entry(key.clone())
+                .or_default()
+                .push(line.to_string());
+        }
+    }
+
+    sections
+}
+
+// Backup entries are "etc/pacman.conf\t<md5sum>"; return the absolute paths
+pub fn parse_backup_files(files_entry: &HashMap<String, Vec<String>>) -> Vec<String> {
+    files_entry
+        .get("BACKUP")
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| line.split('\t').next())
+                .filter(|path| !path.is_empty())
+                .map(|path| format!("/{}", path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,85 @@
+use crate::models::ArchiveVersion;
+use crate::parsers::pacman::{format_bytes, parse_package_filename};
+use crate::parsers::version::vercmp;
+use regex::Regex;
+
+// Parse an Arch Linux Archive directory listing for a single package
+// Lines look like: <a href="name-1.0-1-x86_64.pkg.tar.zst">...</a>   10-Feb-2022 12:30   16093014
+pub fn parse_archive_listing(html: &str, package_name: &str) -> Vec<ArchiveVersion> {
+    let entry_re =
+        Regex::new(r#"<a href="([^"]+)">[^<]*</a>[ \t]*(\S+[ \t]+\S+)?[ \t]*(\S+)?"#).unwrap();
+
+    let entries: Vec<(String, String, String)> = entry_re
+        .captures_iter(html)
+        .map(|caps| {
+            (
+                percent_decode(&caps[1]),
+                caps.get(2)
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default(),
+                caps.get(3)
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let mut versions: Vec<ArchiveVersion> = entries
+        .iter()
+        .filter(|(filename, _, _)| !filename.ends_with(".sig"))
+        .filter_map(|(filename, date, size)| {
+            let (name, version, arch) = parse_package_filename(filename)?;
+            if name != package_name {
+                return None;
+            }
+
+            let signature = format!("{}.sig", filename);
+            Some(ArchiveVersion {
+                name,
+                version,
+                arch,
+                filename: filename.clone(),
+                url: String::new(),
+                size: format_size(size),
+                last_updated: date.clone(),
+                has_signature: entries.iter().any(|(f, _, _)| *f == signature),
+                cached: false,
+            })
+        })
+        .collect();
+
+    // Newest first
+    versions.sort_by(|a, b| vercmp(&b.version, &a.version));
+    versions
+}
+
+// Listings report sizes in bytes; show them the way pacman does
+fn format_size(raw: &str) -> String {
+    raw.parse::<u64>()
+        .map(format_bytes)
+        .unwrap_or_else(|_| raw.to_string())
+}
+
+// Autoindex listings escape characters such as '+' in file names
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
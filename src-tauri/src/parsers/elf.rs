@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+
+// Word size of an ELF file; 32-bit programs only load 32-bit libraries and vice versa
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElfClass {
+    Elf32,
+    Elf64,
+}
+
+// Dynamic linking information of one ELF file
+pub struct DynamicInfo {
+    pub file: String,
+    pub class: Option<ElfClass>,
+    pub needed: Vec<String>,
+    pub search_dirs: Vec<String>, // RPATH/RUNPATH
+}
+
+// ELF header class and dynamic section entries from `readelf -h -d --wide`
+// readelf only prints "File: <path>" headers when given several files; entries before the
+// first header belong to `single_file`
+//
+// File: /usr/bin/foo
+// ELF Header:
+//   Class:                             ELF64
+// ...
+// Dynamic section at offset 0x2dc8 contains 27 entries:
+//   Tag        Type                         Name/Value
+//  0x0000000000000001 (NEEDED)             Shared library: [libicuuc.so.74]
+//  0x000000000000001d (RUNPATH)            Library runpath: [$ORIGIN/../lib]
+pub fn parse_readelf_dynamic(output: &str, single_file: &str) -> Vec<DynamicInfo> {
+    let new_info = |file: &str| DynamicInfo {
+        file: file.to_string(),
+        class: None,
+        needed: Vec::new(),
+        search_dirs: Vec::new(),
+    };
+    let mut files: Vec<DynamicInfo> = Vec::new();
+    let mut current = new_info(single_file);
+
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("File: ") {
+            if !current.needed.is_empty() || !current.search_dirs.is_empty() {
+                files.push(current);
+            }
+            current = new_info(path.trim());
+            continue;
+        }
+        if let Some(class) = line.trim().strip_prefix("Class:") {
+            current.class = match class.trim() {
+                "ELF32" => Some(ElfClass::Elf32),
+                "ELF64" => Some(ElfClass::Elf64),
+                _ => None,
+            };
+            continue;
+        }
+
+        let value = line
+            .split_once('[')
+            .and_then(|(_, rest)| rest.rsplit_once(']'))
+            .map(|(value, _)| value);
+        let Some(value) = value else {
+            continue;
+        };
+        if line.contains("(NEEDED)") {
+            current.needed.push(value.to_string());
+        } else if line.contains("(RUNPATH)") || line.contains("(RPATH)") {
+            current.search_dirs.extend(
+                value
+                    .split(':')
+                    .filter(|dir| !dir.is_empty())
+                    .map(str::to_string),
+            );
+        }
+    }
+    if !current.needed.is_empty() || !current.search_dirs.is_empty() {
+        files.push(current);
+    }
+
+    files
+}
+
+// Library names in the dynamic linker cache from `ldconfig -p`, with the ELF class of each
+// 64-bit entries carry an architecture tag, 32-bit ones on x86_64 do not
+// 	libicuuc.so.74 (libc6,x86-64) => /usr/lib/libicuuc.so.74
+// 	libicuuc.so.74 (libc6) => /usr/lib32/libicuuc.so.74
+pub fn parse_ldconfig_cache(output: &str) -> HashSet<(String, ElfClass)> {
+    output
+        .lines()
+        .filter(|line| line.contains("=>"))
+        .filter_map(|line| {
+            let (soname, rest) = line.trim().split_once(' ')?;
+            let tag = rest.split_once('(')?.1.split_once(')')?.0;
+            let class = if tag.contains("64") {
+                ElfClass::Elf64
+            } else {
+                ElfClass::Elf32
+            };
+            Some((soname.to_string(), class))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER_64: &str = "ELF Header:
+  Magic:   7f 45 4c 46 02 01 01 00 00 00 00 00 00 00 00 00 
+  Class:                             ELF64
+  Data:                              2's complement, little endian
+  Type:                              DYN (Position-Independent Executable file)
+  Machine:                           Advanced Micro Devices X86-64
+";
+
+    #[test]
+    fn parses_single_file_output() {
+        let output = format!(
+            "{}
+Dynamic section at offset 0x23d98 contains 27 entries:
+  Tag        Type                         Name/Value
+ 0x0000000000000001 (NEEDED)             Shared library: [libselinux.so.1]
+ 0x0000000000000001 (NEEDED)             Shared library: [libc.so.6]
+ 0x000000000000000c (INIT)               0x4000
+ 0x000000006ffffffb (FLAGS_1)            Flags: PIE
+ 0x0000000000000000 (NULL)               0x0
+",
+            HEADER_64
+        );
+        let files = parse_readelf_dynamic(&output, "/usr/bin/ls");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file, "/usr/bin/ls");
+        assert_eq!(files[0].class, Some(ElfClass::Elf64));
+        assert_eq!(files[0].needed, vec!["libselinux.so.1", "libc.so.6"]);
+        assert!(files[0].search_dirs.is_empty());
+    }
+
+    #[test]
+    fn parses_multi_file_output() {
+        let output = format!(
+            "
+File: /usr/lib/foo/bin/foo
+{}
+Dynamic section at offset 0x2dc8 contains 27 entries:
+  Tag        Type                         Name/Value
+ 0x0000000000000001 (NEEDED)             Shared library: [libicuuc.so.74]
+ 0x000000000000001d (RUNPATH)            Library runpath: [$ORIGIN/../lib:/opt/foo/lib:]
+
+File: /usr/lib32/libbar.so.1
+ELF Header:
+  Class:                             ELF32
+  Machine:                           Intel 80386
+
+Dynamic section at offset 0x1ef0 contains 24 entries:
+  Tag        Type                         Name/Value
+ 0x00000001 (NEEDED)                     Shared library: [libc.so.6]
+ 0x0000000f (RPATH)                      Library rpath: [/usr/lib32/bar]
+ 0x0000000e (SONAME)                     Library soname: [libbar.so.1]
+
+File: /usr/lib/foo/data.o
+ELF Header:
+  Class:                             ELF64
+
+There is no dynamic section in this file.
+",
+            HEADER_64
+        );
+        let files = parse_readelf_dynamic(&output, "unused");
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].file, "/usr/lib/foo/bin/foo");
+        assert_eq!(files[0].class, Some(ElfClass::Elf64));
+        assert_eq!(files[0].needed, vec!["libicuuc.so.74"]);
+        // $ORIGIN is kept for the caller to expand against the file's directory
+        assert_eq!(files[0].search_dirs, vec!["$ORIGIN/../lib", "/opt/foo/lib"]);
+
+        assert_eq!(files[1].file, "/usr/lib32/libbar.so.1");
+        assert_eq!(files[1].class, Some(ElfClass::Elf32));
+        // SONAME is the file's own name, not a dependency
+        assert_eq!(files[1].needed, vec!["libc.so.6"]);
+        assert_eq!(files[1].search_dirs, vec!["/usr/lib32/bar"]);
+    }
+
+    #[test]
+    fn parses_ldconfig_classes() {
+        let output = "1234 libs found in cache `/etc/ld.so.cache'
+	libicuuc.so.74 (libc6,x86-64) => /usr/lib/libicuuc.so.74
+	libicuuc.so.74 (libc6) => /usr/lib32/libicuuc.so.74
+	libz.so.1 (libc6,x86-64, OS ABI: Linux 3.2.0) => /usr/lib/libz.so.1
+	libssl.so.3 (libc6) => /usr/lib32/libssl.so.3
+	libfoo.so.2 (libc6,AArch64) => /usr/lib/libfoo.so.2
+Cache generated by: ldconfig (GNU libc) stable release version 2.39
+";
+        let cache = parse_ldconfig_cache(output);
+        let expected: HashSet<(String, ElfClass)> = [
+            ("libicuuc.so.74", ElfClass::Elf64),
+            ("libicuuc.so.74", ElfClass::Elf32),
+            ("libz.so.1", ElfClass::Elf64),
+            ("libssl.so.3", ElfClass::Elf32),
+            ("libfoo.so.2", ElfClass::Elf64),
+        ]
+        .into_iter()
+        .map(|(name, class)| (name.to_string(), class))
+        .collect();
+        assert_eq!(cache, expected);
+        assert!(!cache.contains(&("libssl.so.3".to_string(), ElfClass::Elf64)));
+    }
+}
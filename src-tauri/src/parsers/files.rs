@@ -0,0 +1,29 @@
+use crate::models::FileMatch;
+
+// Parse `pacman -F --machinereadable` output
+// Each line is repo\0name\0version\0path, the path without its leading slash
+pub fn parse_file_search(output: &str) -> Vec<FileMatch> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let repository = fields.next()?;
+            let package = fields.next()?;
+            let version = fields.next()?;
+            let path = fields.next()?;
+            Some(FileMatch {
+                repository: repository.to_string(),
+                package: package.to_string(),
+                version: version.to_string(),
+                path: format!("/{}", path.trim_start_matches('/')),
+            })
+        })
+        .collect()
+}
+
+// Parse `pacman -Qo` output: "/usr/bin/ls is owned by coreutils 9.4-3"
+pub fn parse_file_owner(output: &str) -> Option<(String, String)> {
+    let (_, owner) = output.lines().next()?.split_once(" is owned by ")?;
+    let mut parts = owner.split_whitespace();
+    Some((parts.next()?.to_string(), parts.next()?.to_string()))
+}
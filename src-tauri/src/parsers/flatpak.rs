@@ -24,6 +24,9 @@ pub fn parse_flatpak_search(output: &str) -> Vec<Package> {
                 maintainer: String::new(),
                 size: String::new(),
                 last_updated: String::new(),
+                held: false,
+                install_reason: String::new(),
+                risk: None,
             });
         }
     }
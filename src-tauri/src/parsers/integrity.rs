@@ -0,0 +1,34 @@
+// One problem reported by `pacman -Qkk` on stderr
+// warning: coreutils: /usr/bin/ls (Modification time mismatch)
+// warning: foo: /usr/share/foo/data (No such file or directory)
+pub fn parse_integrity_warning(line: &str) -> Option<(String, String, String)> {
+    parse_integrity_problem(line.strip_prefix("warning: ")?)
+}
+
+// A changed backup file, reported on stdout and not counted as altered
+// backup file: pacman: /etc/pacman.conf (Modification time mismatch)
+pub fn parse_integrity_backup(line: &str) -> Option<(String, String, String)> {
+    parse_integrity_problem(line.strip_prefix("backup file: ")?)
+}
+
+fn parse_integrity_problem(rest: &str) -> Option<(String, String, String)> {
+    let (package, rest) = rest.split_once(": ")?;
+    let open = rest.rfind(" (")?;
+    let path = &rest[..open];
+    let problem = rest[open + 2..].strip_suffix(')')?;
+    if !path.starts_with('/') {
+        return None;
+    }
+    Some((package.to_string(), path.to_string(), problem.to_string()))
+}
+
+// Per-package summary line: "coreutils: 1234 total files, 0 altered files"
+// Older pacman versions print "0 missing files" with -Qk
+pub fn parse_integrity_summary(line: &str) -> Option<String> {
+    let (package, rest) = line.split_once(": ")?;
+    if rest.contains(" total files") && !package.contains(' ') {
+        Some(package.to_string())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,165 @@
+use crate::models::{KeyringKey, SignatureProblem};
+
+// Classify signature and keyring failures in pacman's error output
+// error: foo: signature from "Jane Doe <jane@archlinux.org>" is unknown trust
+// :: File /var/cache/pacman/pkg/foo-1.0-1-x86_64.pkg.tar.zst is corrupted (invalid or corrupted package (PGP signature)).
+// error: key "0123456789ABCDEF" could not be looked up remotely
+// error: database 'core' is not valid (invalid or corrupted database (PGP signature))
+pub fn classify_signature_error(output: &str) -> Option<SignatureProblem> {
+    let mut kinds: Vec<&str> = Vec::new();
+    let mut packages: Vec<String> = Vec::new();
+    let mut files: Vec<String> = Vec::new();
+    let mut keys: Vec<String> = Vec::new();
+
+    let push = |list: &mut Vec<String>, value: &str| {
+        if !value.is_empty() && !list.iter().any(|existing| existing == value) {
+            list.push(value.to_string());
+        }
+    };
+
+    for line in output.lines().map(str::trim) {
+        let kind = if line.contains("keyring not found") || line.contains("pacman-key --init") {
+            "keyring_not_initialized"
+        } else if line.contains("is unknown trust") {
+            "unknown_trust"
+        } else if line.contains("is marginal trust") {
+            "marginal_trust"
+        } else if line.contains("has expired") || line.contains("is expired") {
+            "expired_key"
+        } else if line.contains("could not be looked up remotely")
+            || line.contains("required key missing from keyring")
+            || (line.contains("key \"") && line.contains("is unknown"))
+        {
+            "unknown_key"
+        } else if line.contains("corrupted database (PGP signature)") {
+            "corrupted_database"
+        } else if line.contains("(PGP signature)") {
+            "corrupted_package"
+        } else if line.contains("signature from") && line.contains("is invalid") {
+            "invalid_signature"
+        } else {
+            continue;
+        };
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+
+        // "error: foo: signature from ..." names the package
+        if let Some((package, _)) = line
+            .strip_prefix("error: ")
+            .and_then(|rest| rest.split_once(": signature from"))
+        {
+            push(&mut packages, package);
+        }
+
+        if let Some(key) = line
+            .split_once("key \"")
+            .and_then(|(_, rest)| rest.split_once('"'))
+            .map(|(key, _)| key)
+        {
+            push(&mut keys, key);
+        }
+
+        // Package files are named either by path (":: File /var/cache/...") or bare
+        for word in line.split_whitespace() {
+            let word = word.trim_end_matches(['.', ',', ':']);
+            if word.contains(".pkg.tar") {
+                let name = word.rsplit('/').next().unwrap_or(word);
+                push(&mut files, name);
+            }
+        }
+    }
+
+    // The most specific cause wins; corrupted packages are usually a symptom of the others
+    let priority = [
+        "keyring_not_initialized",
+        "unknown_key",
+        "expired_key",
+        "unknown_trust",
+        "marginal_trust",
+        "invalid_signature",
+        "corrupted_database",
+        "corrupted_package",
+    ];
+    let kind = priority.into_iter().find(|kind| kinds.contains(kind))?;
+
+    let summary = match kind {
+        "keyring_not_initialized" => "The pacman keyring has not been initialized",
+        "unknown_key" => "A package was signed with a key that is not in the keyring",
+        "expired_key" => "A signing key in the keyring has expired",
+        "unknown_trust" | "marginal_trust" => {
+            "A package was signed with a key the keyring does not trust"
+        }
+        "invalid_signature" => "A package signature is invalid",
+        "corrupted_database" => "A package database failed its signature check",
+        _ => "Downloaded packages failed their signature check",
+    };
+
+    Some(SignatureProblem {
+        kind: kind.to_string(),
+        summary: format!(
+            "{}; refreshing archlinux-keyring usually fixes this",
+            summary
+        ),
+        packages,
+        files,
+        keys,
+    })
+}
+
+// `gpg --with-colons --list-keys` output
+// pub:f:4096:1:786C63F330D7CB92:1530564446:1845924446::-:::scSC::::::23::0:
+// fpr:::::::::91FFE0700E80619CEB73235CA88E23E377514E00:
+// uid:f::::1530564446::0D6D3BE7ABF6D7D9A2B77F1C8B02C2C4E1F0C3F5::Jane Doe <jane@archlinux.org>::::::::::0:
+pub fn parse_gpg_keys(output: &str) -> Vec<KeyringKey> {
+    let mut keys: Vec<KeyringKey> = Vec::new();
+    // The fingerprint directly follows the primary key, subkeys have their own
+    let mut in_primary = false;
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        let field = |index: usize| fields.get(index).copied().unwrap_or("");
+
+        match field(0) {
+            "pub" => {
+                keys.push(KeyringKey {
+                    key_id: field(4).to_string(),
+                    fingerprint: String::new(),
+                    uid: String::new(),
+                    validity: validity_name(field(1)).to_string(),
+                    created: field(5).parse().ok(),
+                    expires: field(6).parse().ok(),
+                });
+                in_primary = true;
+            }
+            "sub" => in_primary = false,
+            "fpr" if in_primary => {
+                if let Some(key) = keys.last_mut().filter(|key| key.fingerprint.is_empty()) {
+                    key.fingerprint = field(9).to_string();
+                }
+            }
+            "uid" => {
+                if let Some(key) = keys.last_mut().filter(|key| key.uid.is_empty()) {
+                    key.uid = field(9).replace("\\x3a", ":");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    keys
+}
+
+fn validity_name(code: &str) -> &'static str {
+    match code {
+        "u" => "ultimate",
+        "f" => "full",
+        "m" => "marginal",
+        "n" => "never",
+        "e" => "expired",
+        "r" => "revoked",
+        "i" => "invalid",
+        "d" => "disabled",
+        _ => "unknown",
+    }
+}
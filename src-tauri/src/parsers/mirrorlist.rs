@@ -0,0 +1,66 @@
+use crate::models::{MirrorEntry, MirrorStatus};
+use serde::Deserialize;
+
+// Parse /etc/pacman.d/mirrorlist
+// Reflector and the stock file group servers under "## Country" comments
+// Commented-out servers are kept as disabled entries
+pub fn parse_mirrorlist(content: &str) -> Vec<MirrorEntry> {
+    let mut country = String::new();
+    let mut mirrors = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix("##") {
+            // "## Worldwide", "## Germany"; skip the file header lines
+            let comment = comment.trim();
+            let header = comment.is_empty()
+                || comment.contains("mirrorlist")
+                || comment.starts_with("Generated")
+                || comment.contains(':');
+            if !header {
+                country = comment.to_string();
+            }
+            continue;
+        }
+
+        let (body, enabled) = match line.strip_prefix('#') {
+            Some(rest) => (rest.trim(), false),
+            None => (line, true),
+        };
+        let Some((key, value)) = body.split_once('=') else {
+            continue;
+        };
+        if key.trim() != "Server" {
+            continue;
+        }
+
+        mirrors.push(MirrorEntry {
+            server: value.trim().to_string(),
+            enabled,
+            country: country.clone(),
+        });
+    }
+
+    mirrors
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    urls: Vec<MirrorStatus>,
+}
+
+// Parse the mirror status JSON published at archlinux.org/mirrors/status/json/
+pub fn parse_mirror_status(json: &str) -> Result<Vec<MirrorStatus>, String> {
+    serde_json::from_str::<StatusResponse>(json)
+        .map(|response| response.urls)
+        .map_err(|e| format!("Invalid mirror status data: {}", e))
+}
+
+// Mirrorlist with the given servers in order
+pub fn format_mirrorlist(servers: &[String], generated: &str) -> String {
+    let mut content = String::from("##\n## Arch Linux repository mirrorlist\n");
+    content.push_str(&format!("## Ranked by ArchStore on {}\n##\n\n", generated));
+    for server in servers {
+        content.push_str(&format!("Server = {}\n", server));
+    }
+    content
+}
@@ -1,7 +1,30 @@
-pub mod aur;
+pub mod alpm_db;
+pub mod archive;
+pub mod elf;
+pub mod files;
 pub mod flatpak;
+pub mod integrity;
+pub mod keyring;
+pub mod mirrorlist;
+pub mod news;
 pub mod pacman;
+pub mod pacman_conf;
+pub mod pkgbuild;
+pub mod version;
 
-pub use aur::parse_aur_search;
+pub use alpm_db::{parse_backup_files, parse_db_entry};
+pub use archive::parse_archive_listing;
+pub use elf::{ElfClass, parse_ldconfig_cache, parse_readelf_dynamic};
+pub use files::{parse_file_owner, parse_file_search};
 pub use flatpak::parse_flatpak_search;
-pub use pacman::{parse_package_info, parse_pacman_search};
+pub use integrity::{parse_integrity_backup, parse_integrity_summary, parse_integrity_warning};
+pub use keyring::{classify_signature_error, parse_gpg_keys};
+pub use mirrorlist::{format_mirrorlist, parse_mirror_status, parse_mirrorlist};
+pub use news::parse_news_feed;
+pub use pacman::{
+    format_bytes, is_valid_package_name, parse_info_blocks, parse_package_filename,
+    parse_package_info, parse_pacman_search_by_repo,
+};
+pub use pacman_conf::PacmanConf;
+pub use pkgbuild::{parse_vcs_sources, scan_install_script, scan_pkgbuild};
+pub use version::{dependency_name, satisfies_dependency};
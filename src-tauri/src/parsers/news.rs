@@ -0,0 +1,286 @@
+use crate::models::NewsItem;
+use crate::parsers::pacman::is_valid_package_name;
+use regex::Regex;
+
+// Parse an RSS 2.0 or Atom feed into news items, newest first
+pub fn parse_news_feed(feed: &str) -> Vec<NewsItem> {
+    let item_re = Regex::new(r"(?s)<(item|entry)[\s>].*?</(?:item|entry)>").unwrap();
+
+    let mut items: Vec<NewsItem> = item_re
+        .find_iter(feed)
+        .map(|m| {
+            let block = m.as_str();
+            let title = decode_entities(&tag_text(block, "title"));
+
+            // RSS has <link>url</link>, Atom has <link href="url"/>
+            let link = match tag_text(block, "link") {
+                link if !link.is_empty() => link,
+                _ => attribute(block, "link", "href"),
+            };
+
+            let published = ["pubDate", "published", "updated", "dc:date"]
+                .iter()
+                .map(|tag| tag_text(block, tag))
+                .find(|date| !date.is_empty())
+                .map(|date| normalize_date(&date))
+                .unwrap_or_default();
+
+            let html = ["description", "summary", "content"]
+                .iter()
+                .map(|tag| tag_text(block, tag))
+                .find(|text| !text.is_empty())
+                .map(|text| decode_entities(&text))
+                .unwrap_or_default();
+            let summary = strip_html(&html);
+
+            let id = ["guid", "id"]
+                .iter()
+                .map(|tag| tag_text(block, tag))
+                .find(|id| !id.is_empty())
+                .unwrap_or_else(|| link.clone());
+
+            let lower_title = title.to_lowercase();
+            NewsItem {
+                id,
+                manual_intervention: lower_title.contains("intervention")
+                    || lower_title.contains("manual action"),
+                link,
+                published,
+                summary,
+                read: false,
+                since_last_update: false,
+                // Candidates only; the caller keeps the installed ones
+                affected_packages: mentioned_names(&title, &html),
+                title,
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| b.published.cmp(&a.published));
+    items
+}
+
+// Package names a news item points at: <code> spans, versioned tokens such as openssh-9.8p1
+// or "pacman 7.0.0", and repo/name references
+// Plain words are never candidates, so "which", "file" or "base" in a sentence do not match
+fn mentioned_names(title: &str, html: &str) -> Vec<String> {
+    let code_re = Regex::new(r"(?s)<code>(.*?)</code>").unwrap();
+    let repo_re = Regex::new(
+        r"\b(?:core|extra|multilib|core-testing|extra-testing|multilib-testing)/([a-z0-9@_+][a-z0-9@._+-]*)",
+    )
+    .unwrap();
+    let version_re = Regex::new(r"^(?:\d+:)?\d+(?:\.\d+)+[a-z0-9.+_-]*$").unwrap();
+    let tokens = |text: &str| -> Vec<String> {
+        text.to_lowercase()
+            .split(|c: char| !(c.is_ascii_alphanumeric() || "@._+-:".contains(c)))
+            .map(|token| token.trim_matches(|c: char| ".-:".contains(c)).to_string())
+            .filter(|token| !token.is_empty())
+            .collect()
+    };
+
+    let mut names: Vec<String> = Vec::new();
+    for span in code_re.captures_iter(html) {
+        names.extend(
+            tokens(&strip_html(&span[1]))
+                .iter()
+                .map(|token| without_version(token).to_string()),
+        );
+    }
+    let text = format!("{} {}", title, strip_html(html));
+    for caps in repo_re.captures_iter(&text.to_lowercase()) {
+        names.push(caps[1].trim_end_matches(['.', '-']).to_string());
+    }
+    let words = tokens(&text);
+    for (index, token) in words.iter().enumerate() {
+        let name = without_version(token);
+        if name != token {
+            names.push(name.to_string());
+        } else if words
+            .get(index + 1)
+            .is_some_and(|next| version_re.is_match(next))
+        {
+            names.push(token.clone());
+        }
+    }
+
+    let mut found: Vec<String> = Vec::new();
+    for name in names {
+        // Version numbers such as 2:2.12 are left over from "grub 2:2.12-1"
+        let named = is_valid_package_name(&name) && name.chars().any(|c| c.is_ascii_alphabetic());
+        if named && name.len() >= 2 && !found.contains(&name) {
+            found.push(name);
+        }
+    }
+    found
+}
+
+// Strip a trailing version: openssh-9.8p1 -> openssh, linux-firmware stays as it is
+fn without_version(token: &str) -> &str {
+    token
+        .match_indices('-')
+        .find(|(pos, _)| {
+            token[pos + 1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit())
+        })
+        .map(|(pos, _)| &token[..pos])
+        .unwrap_or(token)
+}
+
+// Text content of the first <tag>...</tag>, with CDATA unwrapped
+fn tag_text(block: &str, tag: &str) -> String {
+    let pattern = format!(
+        r"(?s)<{tag}(?:\s[^>]*)?>(.*?)</{tag}>",
+        tag = regex::escape(tag)
+    );
+    Regex::new(&pattern)
+        .ok()
+        .and_then(|re| re.captures(block))
+        .map(|caps| {
+            let text = caps[1].trim();
+            text.strip_prefix("<![CDATA[")
+                .and_then(|inner| inner.strip_suffix("]]>"))
+                .unwrap_or(text)
+                .trim()
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn attribute(block: &str, tag: &str, attr: &str) -> String {
+    let pattern = format!(
+        r#"<{tag}\s[^>]*{attr}="([^"]*)""#,
+        tag = regex::escape(tag),
+        attr = regex::escape(attr)
+    );
+    Regex::new(&pattern)
+        .ok()
+        .and_then(|re| re.captures(block))
+        .map(|caps| decode_entities(&caps[1]))
+        .unwrap_or_default()
+}
+
+// Store dates as RFC 3339 so they sort and compare as strings
+fn normalize_date(date: &str) -> String {
+    chrono::DateTime::parse_from_rfc2822(date)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(date))
+        .map(|parsed| parsed.with_timezone(&chrono::Utc).to_rfc3339())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn strip_html(html: &str) -> String {
+    let tag_re = Regex::new(r"<[^>]*>").unwrap();
+    let text = tag_re.replace_all(html, " ");
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0"><channel><title>Arch Linux: Recent news updates</title>
+<item><title>Manual intervention for pacman 7.0.0 and local repositories required</title>
+<link>https://archlinux.org/news/manual-intervention-for-pacman-700/</link>
+<description>&lt;p&gt;With the release of pacman 7.0.0, which adds a new user for downloads, local
+repositories need the right file permissions. Install &lt;code&gt;base-devel&lt;/code&gt;
+if you make packages.&lt;/p&gt;</description>
+<pubDate>Sat, 14 Sep 2024 10:00:00 +0000</pubDate>
+<guid isPermaLink="false">tag:archlinux.org,2024-09-14:/news/manual-intervention-for-pacman-700/</guid></item>
+<item><title>The sshd service needs a restart after upgrading to openssh-9.8p1</title>
+<link>https://archlinux.org/news/the-sshd-service-needs-a-restart-after-upgrading-to-openssh-98p1/</link>
+<description><![CDATA[<p>After upgrading to <code>openssh-9.8p1</code> the daemon needs a restart; see extra/openssh.</p>]]></description>
+<pubDate>Mon, 01 Jul 2024 12:00:00 +0000</pubDate>
+<guid isPermaLink="false">tag:archlinux.org,2024-07-01:/news/openssh/</guid></item>
+</channel></rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom"><title>News</title>
+<entry><title>Older entry &amp; notes</title>
+<link href="https://example.com/news/older" rel="alternate"/>
+<id>urn:example:older</id>
+<updated>2024-01-02T03:04:05Z</updated>
+<summary type="html">Nothing to do &lt;b&gt;here&lt;/b&gt;, just less time and patch notes.</summary></entry>
+<entry><title>grub 2:2.12-1 needs a reinstall</title>
+<link href="https://example.com/news/grub"/>
+<id>urn:example:grub</id>
+<published>2024-03-05T10:00:00+01:00</published>
+<content type="html">Run &lt;code&gt;grub-install&lt;/code&gt; again.</content></entry>
+</feed>"#;
+
+    #[test]
+    fn parses_rss() {
+        let items = parse_news_feed(RSS);
+        assert_eq!(items.len(), 2);
+
+        let pacman = &items[0];
+        assert_eq!(
+            pacman.title,
+            "Manual intervention for pacman 7.0.0 and local repositories required"
+        );
+        assert_eq!(
+            pacman.link,
+            "https://archlinux.org/news/manual-intervention-for-pacman-700/"
+        );
+        assert_eq!(pacman.published, "2024-09-14T10:00:00+00:00");
+        assert!(pacman.manual_intervention);
+        assert!(
+            pacman
+                .summary
+                .starts_with("With the release of pacman 7.0.0, which")
+        );
+        assert!(!pacman.summary.contains('<'));
+        assert_eq!(pacman.affected_packages, vec!["base-devel", "pacman"]);
+
+        let openssh = &items[1];
+        assert_eq!(openssh.id, "tag:archlinux.org,2024-07-01:/news/openssh/");
+        assert!(!openssh.manual_intervention);
+        assert_eq!(openssh.affected_packages, vec!["openssh"]);
+    }
+
+    #[test]
+    fn parses_atom() {
+        let items = parse_news_feed(ATOM);
+        assert_eq!(items.len(), 2);
+
+        // Newest first
+        let grub = &items[0];
+        assert_eq!(grub.id, "urn:example:grub");
+        assert_eq!(grub.link, "https://example.com/news/grub");
+        assert_eq!(grub.published, "2024-03-05T09:00:00+00:00");
+        assert_eq!(grub.summary, "Run grub-install again.");
+        assert_eq!(grub.affected_packages, vec!["grub-install", "grub"]);
+
+        let older = &items[1];
+        assert_eq!(older.title, "Older entry & notes");
+        assert_eq!(older.link, "https://example.com/news/older");
+        assert_eq!(older.published, "2024-01-02T03:04:05+00:00");
+        assert_eq!(
+            older.summary,
+            "Nothing to do here , just less time and patch notes."
+        );
+        assert!(older.affected_packages.is_empty());
+    }
+
+    #[test]
+    fn plain_words_are_not_mentions() {
+        let names = mentioned_names(
+            "Which file to patch",
+            "<p>Make sure the base system has less time to make a patch file.</p>",
+        );
+        assert!(names.is_empty());
+    }
+}
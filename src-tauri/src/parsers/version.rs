@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+
+// Compare two package versions the same way pacman's vercmp does
+// Versions have the form [epoch:]pkgver[-pkgrel]
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (epoch_a, ver_a, rel_a) = split_evr(a);
+    let (epoch_b, ver_b, rel_b) = split_evr(b);
+
+    let ordering = rpmvercmp(epoch_a, epoch_b);
+    if ordering != Ordering::Equal {
+        return ordering;
+    }
+
+    let ordering = rpmvercmp(ver_a, ver_b);
+    if ordering != Ordering::Equal {
+        return ordering;
+    }
+
+    match (rel_a, rel_b) {
+        (Some(rel_a), Some(rel_b)) => rpmvercmp(rel_a, rel_b),
+        _ => Ordering::Equal,
+    }
+}
+
+// Split a version into epoch, pkgver and optional pkgrel
+fn split_evr(evr: &str) -> (&str, &str, Option<&str>) {
+    let digits = evr.bytes().take_while(|b| b.is_ascii_digit()).count();
+
+    let (epoch, rest) = if evr[digits..].starts_with(':') {
+        let epoch = &evr[..digits];
+        (
+            if epoch.is_empty() { "0" } else { epoch },
+            &evr[digits + 1..],
+        )
+    } else {
+        ("0", evr)
+    };
+
+    match rest.rfind('-') {
+        Some(pos) => (epoch, &rest[..pos], Some(&rest[pos + 1..])),
+        None => (epoch, rest, None),
+    }
+}
+
+// Port of rpmvercmp as used by libalpm
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let one = a.as_bytes();
+    let two = b.as_bytes();
+    let (mut i, mut j) = (0, 0);
+    let (mut seg_end_one, mut seg_end_two) = (0, 0);
+
+    while i < one.len() && j < two.len() {
+        while i < one.len() && !one[i].is_ascii_alphanumeric() {
+            i += 1;
+        }
+        while j < two.len() && !two[j].is_ascii_alphanumeric() {
+            j += 1;
+        }
+
+        if i >= one.len() || j >= two.len() {
+            break;
+        }
+
+        // A different number of separators means the version with more of them is newer
+        if i - seg_end_one != j - seg_end_two {
+            return (i - seg_end_one).cmp(&(j - seg_end_two));
+        }
+
+        let numeric = one[i].is_ascii_digit();
+        let matches = |c: u8| {
+            if numeric {
+                c.is_ascii_digit()
+            } else {
+                c.is_ascii_alphabetic()
+            }
+        };
+
+        let mut end_one = i;
+        while end_one < one.len() && matches(one[end_one]) {
+            end_one += 1;
+        }
+        let mut end_two = j;
+        while end_two < two.len() && matches(two[end_two]) {
+            end_two += 1;
+        }
+
+        // Numeric segments are always newer than alpha segments
+        if end_two == j {
+            return if numeric {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let mut seg_one = &one[i..end_one];
+        let mut seg_two = &two[j..end_two];
+
+        if numeric {
+            while seg_one.first() == Some(&b'0') {
+                seg_one = &seg_one[1..];
+            }
+            while seg_two.first() == Some(&b'0') {
+                seg_two = &seg_two[1..];
+            }
+            if seg_one.len() != seg_two.len() {
+                return seg_one.len().cmp(&seg_two.len());
+            }
+        }
+
+        let ordering = seg_one.cmp(seg_two);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        i = end_one;
+        j = end_two;
+        seg_end_one = i;
+        seg_end_two = j;
+    }
+
+    let rest_one = i < one.len();
+    let rest_two = j < two.len();
+
+    if !rest_one && !rest_two {
+        return Ordering::Equal;
+    }
+
+    // A remaining alpha segment never beats an empty one
+    if (!rest_one && !two[j].is_ascii_alphabetic()) || (rest_one && one[i].is_ascii_alphabetic()) {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+// Name part of a dependency string such as "glibc>=2.38" or "sh"
+pub fn dependency_name(dep: &str) -> &str {
+    dep.split(['<', '>', '=']).next().unwrap_or(dep).trim()
+}
+
+// Whether a package or provides entry `name` at `version` satisfies a dependency string such as
+// "foo>=1.2"; an empty version (unversioned provides) only satisfies unversioned dependencies
+pub fn satisfies_dependency(dep: &str, name: &str, version: &str) -> bool {
+    let dep = dep.trim();
+    if dependency_name(dep) != name {
+        return false;
+    }
+    let Some(start) = dep.find(['<', '>', '=']) else {
+        return true;
+    };
+    if version.is_empty() {
+        return false;
+    }
+
+    let constraint = &dep[start..];
+    let (operator, required) = ["<=", ">=", "=", "<", ">"]
+        .into_iter()
+        .find_map(|operator| {
+            constraint
+                .strip_prefix(operator)
+                .map(|required| (operator, required))
+        })
+        .unwrap_or(("=", constraint));
+
+    let ordering = vercmp(version, required.trim());
+    match operator {
+        "<=" => ordering != Ordering::Greater,
+        ">=" => ordering != Ordering::Less,
+        "<" => ordering == Ordering::Less,
+        ">" => ordering == Ordering::Greater,
+        _ => ordering == Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from pacman's test/util/vercmptest.sh; each pair is also checked reversed
+    #[test]
+    fn vercmp_matches_pacman() {
+        let cases = [
+            // Same length, no pkgrel
+            ("1.5.0", "1.5.0", 0),
+            ("1.5.1", "1.5.0", 1),
+            // Mixed length
+            ("1.5.1", "1.5", 1),
+            // With pkgrel
+            ("1.5.0-1", "1.5.0-1", 0),
+            ("1.5.0-1", "1.5.0-2", -1),
+            ("1.5.0-1", "1.5.1-1", -1),
+            ("1.5.0-2", "1.5.1-1", -1),
+            ("1.5-1", "1.5.1-1", -1),
+            ("1.5-2", "1.5.1-1", -1),
+            ("1.5-2", "1.5.1-2", -1),
+            // A missing pkgrel matches any pkgrel
+            ("1.5", "1.5-1", 0),
+            ("1.1-1", "1.1", 0),
+            ("1.0-1", "1.1", -1),
+            ("1.1-1", "1.0", 1),
+            // Alphanumerics
+            ("1.0a", "1.0alpha", -1),
+            ("1.0alpha", "1.0b", -1),
+            ("1.0b", "1.0beta", -1),
+            ("1.0beta", "1.0rc", -1),
+            ("1.0rc", "1.0", -1),
+            // From the manpage
+            ("1.0a", "1.0", -1),
+            ("1.0", "1.0.a", -1),
+            ("1.0.a", "1.0.1", -1),
+            ("1.0a", "1.0.1", -1),
+            // Alpha-dotted versions
+            ("1.5.a", "1.5", 1),
+            ("1.5.b", "1.5.a", 1),
+            ("1.5.1", "1.5.b", 1),
+            ("1.5.b-1", "1.5.b", 0),
+            ("1.5-1", "1.5.b", -1),
+            // Differing separators
+            ("2.0", "2_0", 0),
+            ("2.0_a", "2_0.a", 0),
+            ("2.0a", "2.0.a", -1),
+            ("2___a", "2_a", 1),
+            // Leading zeros
+            ("1.01", "1.1", 0),
+            ("1.001-1", "1.1-1", 0),
+            // Epochs
+            ("0:1.0", "0:1.0", 0),
+            ("0:1.0", "0:1.1", -1),
+            ("1:1.0", "0:1.0", 1),
+            ("1:1.0", "0:1.1", 1),
+            ("1:1.0", "2:1.1", -1),
+            ("1:1.0", "0:1.0-1", 1),
+            ("1:1.0-1", "0:1.1-1", 1),
+            ("0:1.0", "1.0", 0),
+            ("0:1.0", "1.1", -1),
+            ("0:1.1", "1.0", 1),
+            ("1:1.0", "1.0", 1),
+            ("1:1.0", "1.1", 1),
+            ("1:1.1", "1.1", 1),
+        ];
+        for (a, b, expected) in cases {
+            let expected = expected.cmp(&0);
+            assert_eq!(vercmp(a, b), expected, "vercmp {} {}", a, b);
+            assert_eq!(vercmp(b, a), expected.reverse(), "vercmp {} {}", b, a);
+        }
+    }
+
+    #[test]
+    fn satisfies_each_operator() {
+        let cases = [
+            ("foo>=1.2", "1.2-1", true),
+            ("foo>=1.2", "1.10-1", true),
+            ("foo>=1.2", "1.1-3", false),
+            ("foo<=1.2", "1.2-1", true),
+            ("foo<=1.2", "1.2.1-1", false),
+            ("foo>1.2", "1.2-1", false),
+            ("foo>1.2", "1.2.1-1", true),
+            ("foo<1.2", "1.1-1", true),
+            ("foo<1.2", "1.2-1", false),
+            ("foo=1.2", "1.2-5", true),
+            ("foo=1.2-1", "1.2-2", false),
+            ("foo=1.2", "1.2.0-1", false),
+            ("foo", "0.1-1", true),
+        ];
+        for (dep, version, expected) in cases {
+            assert_eq!(
+                satisfies_dependency(dep, "foo", version),
+                expected,
+                "{} with foo {}",
+                dep,
+                version
+            );
+        }
+        assert!(!satisfies_dependency("bar>=1", "foo", "2-1"));
+        assert!(!satisfies_dependency("foobar", "foo", "1-1"));
+    }
+
+    #[test]
+    fn satisfies_with_epoch() {
+        assert!(satisfies_dependency("foo>=2.0", "foo", "1:1.0-1"));
+        assert!(!satisfies_dependency("foo<2.0", "foo", "1:1.0-1"));
+        assert!(satisfies_dependency("foo>=1:1.0", "foo", "1:1.0-1"));
+        assert!(!satisfies_dependency("foo>=1:1.0", "foo", "5.0-1"));
+        assert!(satisfies_dependency("foo=0:1.0", "foo", "1.0-1"));
+    }
+
+    #[test]
+    fn unversioned_provides_only_satisfy_unversioned_deps() {
+        assert!(satisfies_dependency("libfoo.so", "libfoo.so", ""));
+        assert!(satisfies_dependency(" sh ", "sh", ""));
+        assert!(!satisfies_dependency("libfoo.so>=1", "libfoo.so", ""));
+        assert!(!satisfies_dependency("java-runtime=17", "java-runtime", ""));
+        assert!(satisfies_dependency("libfoo.so=1-64", "libfoo.so", "1-64"));
+    }
+}
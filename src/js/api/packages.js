@@ -2,6 +2,8 @@ import {
   searchOfficialPackages,
   searchAurPackages,
   searchFlatpakPackages,
+  searchCommand,
+  searchCustomPackages,
   installPackage as tauriInstallPackage,
   removePackage as tauriRemovePackage,
 } from "./tauri.js";
@@ -28,6 +30,12 @@ export async function searchAllSources(query, settings, onProgress) {
     search: () => searchOfficialPackages(query),
   });
 
+  // Custom repositories from pacman.conf (returns nothing when there are none)
+  sources.push({
+    name: "custom",
+    search: () => searchCustomPackages(query),
+  });
+
   if (settings.enableAur) {
     sources.push({
       name: "aur",
@@ -68,6 +76,27 @@ export async function searchAllSources(query, settings, onProgress) {
   return results;
 }
 
+/**
+ * Search for packages that provide a command, e.g. "rg"
+ * Results use the same package shape as searchAllSources, so they install the same way
+ * @param {string} command - Command name
+ * @param {Object} settings - App settings (enableAur, enableFlatpak)
+ * @returns {Promise<Array>} Array of packages providing the command
+ */
+export async function searchByCommand(command, settings) {
+  try {
+    const packages = await searchCommand(command);
+    return packages.filter(
+      (pkg) =>
+        (pkg.source !== "aur" || settings.enableAur) &&
+        (pkg.source !== "flatpak" || settings.enableFlatpak)
+    );
+  } catch (error) {
+    console.error("Error searching by command:", error);
+    return [];
+  }
+}
+
 /**
  * Fetch detailed package information from specific sources
  * @param {Object} app - App object with sources
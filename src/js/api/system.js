@@ -24,17 +24,29 @@ export async function checkForUpdates() {
 /**
  * Update the entire system (pacman -Syu, yay -Syu, flatpak update)
  * @param {string} password - User password for sudo operations
+ * @param {Object} options - { takeSnapshot, allowSnapshotFailure, skipNewsCheck }
  */
-export async function performSystemUpdate(password) {
-  return await updateSystem(password);
+export async function performSystemUpdate(password, options = {}) {
+  return await updateSystem(
+    password,
+    options.takeSnapshot,
+    options.allowSnapshotFailure,
+    options.skipNewsCheck
+  );
 }
 
 /**
  * Update only official packages (pacman -Syu)
  * @param {string} password - User password for sudo operations
+ * @param {Object} options - { takeSnapshot, allowSnapshotFailure, skipNewsCheck }
  */
-export async function performOfficialUpdate(password) {
-  return await updateOfficial(password);
+export async function performOfficialUpdate(password, options = {}) {
+  return await updateOfficial(
+    password,
+    options.takeSnapshot,
+    options.allowSnapshotFailure,
+    options.skipNewsCheck
+  );
 }
 
 /**
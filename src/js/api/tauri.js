@@ -31,6 +31,20 @@ export async function searchFlatpakPackages(query) {
   return await invoke("search_flatpak_packages", { query });
 }
 
+/**
+ * Search packages from custom repositories (source "custom", repo in category)
+ */
+export async function searchCustomPackages(query) {
+  return await invoke("search_custom_packages", { query });
+}
+
+/**
+ * Find packages (official, AUR, Flatpak) that provide a command
+ */
+export async function searchCommand(command) {
+  return await invoke("search_command", { command });
+}
+
 // ============================================================================
 // Package Installation/Removal Commands
 // ============================================================================
@@ -70,17 +84,37 @@ export async function checkUpdates() {
 }
 
 /**
- * Update the entire system
+ * Update the entire system, gated on Arch news and optionally snapshotted first
  */
-export async function updateSystem(password) {
-  return await invoke("update_system", { password });
+export async function updateSystem(
+  password,
+  takeSnapshot = false,
+  allowSnapshotFailure = false,
+  skipNewsCheck = false
+) {
+  return await invoke("update_system", {
+    password,
+    takeSnapshot,
+    allowSnapshotFailure,
+    skipNewsCheck,
+  });
 }
 
 /**
- * Update only official packages
+ * Update only official packages, gated on Arch news and optionally snapshotted first
  */
-export async function updateOfficial(password) {
-  return await invoke("update_official", { password });
+export async function updateOfficial(
+  password,
+  takeSnapshot = false,
+  allowSnapshotFailure = false,
+  skipNewsCheck = false
+) {
+  return await invoke("update_official", {
+    password,
+    takeSnapshot,
+    allowSnapshotFailure,
+    skipNewsCheck,
+  });
 }
 
 /**
@@ -90,6 +124,29 @@ export async function updateAur(password) {
   return await invoke("update_aur", { password });
 }
 
+/**
+ * Update only the selected packages, grouped by source
+ */
+export async function updateSelected(
+  official,
+  aur,
+  flatpak,
+  password,
+  takeSnapshot = false,
+  allowSnapshotFailure = false,
+  skipNewsCheck = false
+) {
+  return await invoke("update_selected", {
+    official,
+    aur,
+    flatpak,
+    password,
+    takeSnapshot,
+    allowSnapshotFailure,
+    skipNewsCheck,
+  });
+}
+
 /**
  * Update only Flatpak packages
  */
@@ -115,6 +172,539 @@ export async function checkSystemCapabilities() {
   return await invoke("check_system_capabilities");
 }
 
+// ============================================================================
+// Arch Linux Archive Commands
+// ============================================================================
+
+/**
+ * List historical versions of an official package from the Arch Linux Archive
+ */
+export async function listArchiveVersions(packageName) {
+  return await invoke("list_archive_versions", { packageName });
+}
+
+/**
+ * Install an archived package file (downloads and verifies it if not cached)
+ */
+export async function downgradePackage(packageName, filename, password) {
+  return await invoke("downgrade_package", { packageName, filename, password });
+}
+
+// ============================================================================
+// Snapshot Commands
+// ============================================================================
+
+/**
+ * Report which snapshot tool (snapper, timeshift, btrfs) is available
+ */
+export async function getSnapshotStatus() {
+  return await invoke("get_snapshot_status");
+}
+
+/**
+ * List snapshots ArchStore created before updates
+ */
+export async function listSnapshots(password) {
+  return await invoke("list_snapshots", { password });
+}
+
+// ============================================================================
+// Arch News Commands
+// ============================================================================
+
+/**
+ * Fetch Arch Linux news with read state and affected installed packages
+ */
+export async function getArchNews() {
+  return await invoke("get_arch_news");
+}
+
+/**
+ * Unread news published since the last successful update
+ */
+export async function getPendingNews() {
+  return await invoke("get_pending_news");
+}
+
+/**
+ * Mark news items as read
+ */
+export async function markNewsRead(ids) {
+  return await invoke("mark_news_read", { ids });
+}
+
+// ============================================================================
+// Configuration File Commands
+// ============================================================================
+
+/**
+ * Find .pacnew and .pacsave files for pacman-tracked backup files
+ */
+export async function scanConfigChanges() {
+  return await invoke("scan_config_changes");
+}
+
+/**
+ * Unified diff between a live config file and its .pacnew/.pacsave
+ */
+export async function getConfigDiff(path, newPath, password = null) {
+  return await invoke("get_config_diff", { path, newPath, password });
+}
+
+/**
+ * Keep, replace or merge a .pacnew/.pacsave file
+ * @param {string} action - "keep", "replace" or "merge"
+ */
+export async function resolveConfigChange(
+  path,
+  newPath,
+  action,
+  mergedContent,
+  password
+) {
+  return await invoke("resolve_config_change", {
+    path,
+    newPath,
+    action,
+    mergedContent,
+    password,
+  });
+}
+
+// ============================================================================
+// Held Package Commands
+// ============================================================================
+
+/**
+ * Packages and groups in IgnorePkg/IgnoreGroup, plus masked Flatpaks
+ */
+export async function getHeldPackages() {
+  return await invoke("get_held_packages");
+}
+
+/**
+ * Hold a package back from updates
+ * @param {string} kind - "package", "group" or "flatpak"
+ */
+export async function holdPackage(name, kind, password) {
+  return await invoke("hold_package", { name, kind, password });
+}
+
+/**
+ * Release a held package
+ * @param {string} kind - "package", "group" or "flatpak"
+ */
+export async function unholdPackage(name, kind, password) {
+  return await invoke("unhold_package", { name, kind, password });
+}
+
+// ============================================================================
+// Orphan Commands
+// ============================================================================
+
+/**
+ * Dependencies no longer required by anything, with sizes and reclaimable total
+ */
+export async function listOrphans() {
+  return await invoke("list_orphans");
+}
+
+/**
+ * Remove orphaned packages (progress via "remove-progress")
+ */
+export async function removeOrphans(packages, password) {
+  return await invoke("remove_orphans", { packages, password });
+}
+
+// ============================================================================
+// Package Cache Commands
+// ============================================================================
+
+/**
+ * Preview a package cache cleanup
+ * @param {number} keep - Newest versions kept per installed package
+ */
+export async function analyzePackageCache(
+  keep = 3,
+  includeUninstalled = true,
+  includeHelperCaches = false
+) {
+  return await invoke("analyze_package_cache", {
+    keep,
+    includeUninstalled,
+    includeHelperCaches,
+  });
+}
+
+/**
+ * Run the previewed cleanup (progress via "cache-progress")
+ */
+export async function cleanPackageCache(
+  keep,
+  includeUninstalled,
+  includeHelperCaches,
+  password
+) {
+  return await invoke("clean_package_cache", {
+    keep,
+    includeUninstalled,
+    includeHelperCaches,
+    password,
+  });
+}
+
+// ============================================================================
+// Install Reason Commands
+// ============================================================================
+
+/**
+ * Show which packages would become (or stop being) orphans
+ * @param {string} reason - "dependency" or "explicit"
+ */
+export async function previewInstallReason(packages, reason) {
+  return await invoke("preview_install_reason", { packages, reason });
+}
+
+/**
+ * Mark packages as dependencies or explicitly installed
+ * @param {string} reason - "dependency" or "explicit"
+ */
+export async function setInstallReason(packages, reason, password) {
+  return await invoke("set_install_reason", { packages, reason, password });
+}
+
+// ============================================================================
+// Dependency Commands
+// ============================================================================
+
+/**
+ * Dependency graph around a package, edges point from a package to its dependency
+ * @param {string} direction - "forward", "reverse" or "both"
+ */
+export async function getDependencyGraph(
+  packageName,
+  direction = "forward",
+  maxDepth = 3,
+  includeOptional = true
+) {
+  return await invoke("get_dependency_graph", {
+    packageName,
+    direction,
+    maxDepth,
+    includeOptional,
+  });
+}
+
+/**
+ * Shortest dependency chains from explicitly installed packages down to a package
+ */
+export async function getInstallChains(packageName) {
+  return await invoke("get_install_chains", { packageName });
+}
+
+// ============================================================================
+// File Commands
+// ============================================================================
+
+/**
+ * Files installed by a package (local database or sync files databases)
+ */
+export async function listPackageFiles(packageName) {
+  return await invoke("list_package_files", { packageName });
+}
+
+/**
+ * Package owning an absolute path, or null
+ */
+export async function findFileOwner(path) {
+  return await invoke("find_file_owner", { path });
+}
+
+/**
+ * Search the files databases by file name or full path
+ */
+export async function searchFiles(query) {
+  return await invoke("search_files", { query });
+}
+
+/**
+ * Sync the files databases (progress via "files-progress")
+ */
+export async function syncFilesDatabases(password) {
+  return await invoke("sync_files_databases", { password });
+}
+
+// ============================================================================
+// Integrity Commands
+// ============================================================================
+
+/**
+ * Verify installed files with pacman -Qkk (progress via "integrity-progress")
+ * @param {string|null} packageName - Package to check, or null for all packages
+ * @param {string|null} password - Check through sudo so root-only files can be read
+ */
+export async function checkPackageIntegrity(packageName = null, password = null) {
+  return await invoke("check_package_integrity", { packageName, password });
+}
+
+/**
+ * Reinstall damaged packages (progress via "integrity-progress")
+ */
+export async function reinstallPackages(packages, password) {
+  return await invoke("reinstall_packages", { packages, password });
+}
+
+// ============================================================================
+// pacman.conf Commands
+// ============================================================================
+
+/**
+ * Options and repositories from pacman.conf, with Include'd server lists
+ */
+export async function getPacmanConfig() {
+  return await invoke("get_pacman_config");
+}
+
+/**
+ * Diff and resulting configuration for a list of edits
+ * Edits look like { action: "set_repo_enabled", repo: "multilib", enabled: true },
+ * { action: "set_servers", repo, values }, { action: "set_sig_level", repo, value }
+ * or { action: "set_option", key: "Color", enabled: true }
+ */
+export async function previewPacmanConfChanges(edits) {
+  return await invoke("preview_pacman_conf_changes", { edits });
+}
+
+/**
+ * Write the edits to pacman.conf, returns the path of the backup
+ */
+export async function applyPacmanConfChanges(edits, password) {
+  return await invoke("apply_pacman_conf_changes", { edits, password });
+}
+
+// ============================================================================
+// Custom Repository Commands
+// ============================================================================
+
+/**
+ * Third-party repositories from pacman.conf
+ */
+export async function listCustomRepositories() {
+  return await invoke("list_custom_repositories");
+}
+
+/**
+ * Add a repository (progress via "repo-progress")
+ * @param {Object} repo - { name, servers, include, sigLevel, keyId, keyserver, setupPackages }
+ */
+export async function addCustomRepository(repo, password) {
+  return await invoke("add_custom_repository", {
+    repo: {
+      name: repo.name,
+      servers: repo.servers || [],
+      include: repo.include || null,
+      sig_level: repo.sigLevel || null,
+      key_id: repo.keyId || null,
+      keyserver: repo.keyserver || null,
+      setup_packages: repo.setupPackages || [],
+    },
+    password,
+  });
+}
+
+/**
+ * Remove a repository with its key and setup packages (progress via "repo-progress")
+ */
+export async function removeCustomRepository(name, password) {
+  return await invoke("remove_custom_repository", { name, password });
+}
+
+// ============================================================================
+// Mirror Commands
+// ============================================================================
+
+function mirrorFilter(filter = {}) {
+  return {
+    countries: filter.countries || [],
+    protocols: filter.protocols || [],
+    min_completion: filter.minCompletion ?? null,
+    max_delay: filter.maxDelay ?? null,
+  };
+}
+
+/**
+ * Servers in /etc/pacman.d/mirrorlist
+ */
+export async function getMirrorlist() {
+  return await invoke("get_mirrorlist");
+}
+
+/**
+ * Mirrors from the Arch mirror status, sorted by score
+ * @param {Object} filter - { countries, protocols, minCompletion, maxDelay }
+ */
+export async function getMirrorStatus(filter) {
+  return await invoke("get_mirror_status", { filter: mirrorFilter(filter) });
+}
+
+/**
+ * Measure latency and throughput of matching mirrors (progress via "mirror-progress")
+ * @param {Object} filter - { countries, protocols, minCompletion, maxDelay }
+ * @param {number} count - Number of mirrors to test
+ */
+export async function rankMirrors(filter, count = null) {
+  return await invoke("rank_mirrors", { filter: mirrorFilter(filter), count });
+}
+
+/**
+ * Diff of the mirrorlist that would be written for these servers
+ */
+export async function previewMirrorlist(servers) {
+  return await invoke("preview_mirrorlist", { servers });
+}
+
+/**
+ * Write the mirrorlist, keeping a backup
+ */
+export async function saveMirrorlist(servers, password) {
+  return await invoke("save_mirrorlist", { servers, password });
+}
+
+// ============================================================================
+// Keyring Commands
+// ============================================================================
+
+/**
+ * Recognise signature/keyring problems in an update error
+ * @returns {Promise<Object|null>} { kind, summary, packages, files, keys } or null
+ */
+export async function classifyUpdateError(error) {
+  return await invoke("classify_update_error", { error: String(error) });
+}
+
+/**
+ * Key counts, expiry and archlinux-keyring version of the pacman keyring
+ * @param {string|null} password - Only needed when the keyring is not world-readable
+ */
+export async function getKeyringStatus(password = null) {
+  return await invoke("get_keyring_status", { password });
+}
+
+/**
+ * Refresh archlinux-keyring, repopulate, drop failed cached files and optionally retry
+ * the update (progress via "keyring-progress")
+ * The retry goes through the same news gate and snapshot options as updateOfficial
+ * @param {string[]} files - Package files from classifyUpdateError
+ */
+export async function recoverKeyring(
+  files,
+  retryUpdate,
+  password,
+  takeSnapshot = false,
+  allowSnapshotFailure = false,
+  skipNewsCheck = false
+) {
+  return await invoke("recover_keyring", {
+    files,
+    retryUpdate,
+    takeSnapshot,
+    allowSnapshotFailure,
+    skipNewsCheck,
+    password,
+  });
+}
+
+// ============================================================================
+// AUR Review Commands
+// ============================================================================
+
+/**
+ * Fetch the PKGBUILD and install scripts of an AUR package, diffed against the last build
+ * @returns {Promise<Object>} { package, pkgbase, version, commit, files, approved, ... }
+ */
+export async function reviewAurPackage(packageName) {
+  return await invoke("review_aur_package", { packageName });
+}
+
+/**
+ * Approve the reviewed commit; required before the package can be built
+ */
+export async function approveAurReview(pkgbase, commit) {
+  return await invoke("approve_aur_review", { pkgbase, commit });
+}
+
+/**
+ * Static checks of an AUR package's build files combined with its AUR metadata
+ * @returns {Promise<Object>} { level, score, findings, scripts_scanned }
+ */
+export async function getAurRiskReport(packageName) {
+  return await invoke("get_aur_risk_report", { packageName });
+}
+
+/**
+ * Withdraw the approval of a package base
+ */
+export async function revokeAurApproval(pkgbase) {
+  return await invoke("revoke_aur_approval", { pkgbase });
+}
+
+// ============================================================================
+// AUR Build Commands
+// ============================================================================
+
+/**
+ * Build AUR packages natively: resolve dependencies, build with makepkg and install with pacman
+ * Every package base in the plan must have an approved review
+ * @returns {Promise<Object>} The build plan that was executed { targets, steps, repo_dependencies }
+ */
+export async function installAurPackages(packages, password) {
+  return await invoke("install_aur_packages", { packages, password });
+}
+
+/**
+ * Resolve AUR packages into an ordered build plan without building anything
+ * @returns {Promise<Object>} { targets, steps: [{ pkgbase, version, packages, as_dependency, required_by }], repo_dependencies }
+ */
+export async function getAurBuildPlan(packages) {
+  return await invoke("get_aur_build_plan", { packages });
+}
+
+/**
+ * -git AUR packages whose upstream has new commits since they were built (other VCS
+ * suffixes are not checked)
+ * Listed separately from checkUpdates, which only sees PKGBUILD changes
+ * @returns {Promise<Array>} [{ name, pkgbase, version, url, branch, built_revision, latest_revision }]
+ */
+export async function checkVcsUpdates() {
+  return await invoke("check_vcs_updates");
+}
+
+/**
+ * Foreign packages linking libraries that are no longer installed, or with modules in an old
+ * python's site-packages
+ * @returns {Promise<Array>} [{ name, version, missing_libraries, broken_files, stale_python }]
+ */
+export async function checkRebuilds() {
+  return await invoke("check_rebuilds");
+}
+
+/**
+ * Rebuild installed AUR packages from their reviewed sources, keeping their install reasons
+ * @returns {Promise<Object>} The build plan that was executed
+ */
+export async function rebuildAurPackages(packages, password) {
+  return await invoke("rebuild_aur_packages", { packages, password });
+}
+
+/**
+ * Classify foreign packages as aur_present, aur_missing, official_available or local_only,
+ * with a suggested replacement where one exists
+ * @returns {Promise<Array>} [{ name, version, status, aur_version, replacement, replacement_source, matched_by, suggestion }]
+ */
+export async function auditForeignPackages() {
+  return await invoke("audit_foreign_packages");
+}
+
 // ============================================================================
 // Event Listeners
 // ============================================================================
@@ -145,3 +735,66 @@ export async function onUpdateProgress(callback) {
     callback(event.payload);
   });
 }
+
+/**
+ * Listen for package cache cleanup progress events
+ */
+export async function onCacheProgress(callback) {
+  return await listen("cache-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen for files database sync progress events
+ */
+export async function onFilesProgress(callback) {
+  return await listen("files-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen for integrity check and reinstall progress events
+ */
+export async function onIntegrityProgress(callback) {
+  return await listen("integrity-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen for custom repository setup progress events
+ */
+export async function onRepoProgress(callback) {
+  return await listen("repo-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen for mirror ranking progress events
+ */
+export async function onMirrorProgress(callback) {
+  return await listen("mirror-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen for keyring recovery progress events
+ */
+export async function onKeyringProgress(callback) {
+  return await listen("keyring-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Listen for AUR build pipeline progress events
+ */
+export async function onAurProgress(callback) {
+  return await listen("aur-progress", (event) => {
+    callback(event.payload);
+  });
+}
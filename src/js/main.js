@@ -263,6 +263,7 @@ async function handleCheckUpdates() {
       let html = "";
 
       // Helper function to create package rows
+      // Held packages (IgnorePkg/IgnoreGroup, flatpak masks) are shown but cannot be selected
       const createPackageRow = (update, index) => {
         const sourceClass = `source-${update.source}`;
         return `
@@ -273,16 +274,17 @@ async function handleCheckUpdates() {
             margin-bottom: 4px;
             background: var(--bg-primary);
             border-radius: 6px;
-            cursor: pointer;
+            cursor: ${update.held ? "not-allowed" : "pointer"};
+            opacity: ${update.held ? "0.5" : "1"};
             transition: background 0.2s;
-          " class="update-package-item">
+          " class="update-package-item" ${update.held ? 'title="Held back; release the hold to update"' : ""}>
             <input
               type="checkbox"
               class="update-package-checkbox"
               data-package-name="${escapeHtml(update.name)}"
               data-package-source="${update.source}"
               data-package-version="${escapeHtml(update.version)}"
-              checked
+              ${update.held ? "disabled" : "checked"}
               style="width: 18px; height: 18px; margin-right: 12px; cursor: pointer;"
             />
             <div style="flex: 1; min-width: 0;">
@@ -290,6 +292,7 @@ async function handleCheckUpdates() {
                 <div style="display: flex; align-items: center; gap: 8px;">
                   <span style="font-weight: 500; color: var(--text-primary);">${escapeHtml(update.name)}</span>
                   <span class="source-badge ${sourceClass}" style="font-size: 10px;">${update.source}</span>
+                  ${update.held ? '<span class="source-badge" style="font-size: 10px;">held</span>' : ""}
                 </div>
                 <span style="font-size: 12px; color: var(--text-secondary);">${escapeHtml(update.version)}</span>
               </div>
@@ -383,7 +386,9 @@ function updateCheckAllButtonText() {
   const btn = document.getElementById("check-all-updates");
   if (!btn) return;
 
-  const checkboxes = document.querySelectorAll(".update-package-checkbox");
+  const checkboxes = document.querySelectorAll(
+    ".update-package-checkbox:not(:disabled)",
+  );
   if (checkboxes.length === 0) {
     btn.textContent = "Check All";
     return;
@@ -404,7 +409,9 @@ function updateCheckAllButtonText() {
 
 // Handle Check All / Uncheck All toggle
 function handleCheckAllToggle() {
-  const checkboxes = document.querySelectorAll(".update-package-checkbox");
+  const checkboxes = document.querySelectorAll(
+    ".update-package-checkbox:not(:disabled)",
+  );
   if (checkboxes.length === 0) return;
 
   const allChecked = Array.from(checkboxes).every((cb) => cb.checked);
@@ -460,6 +467,15 @@ async function handleUpdateSelected() {
   try {
     let password = null;
 
+    // Changed AUR build files are reviewed before anything is built, like installs
+    const { reviewAurBuildPlan, approveAurReviews } = await import(
+      "./ui/detail.js"
+    );
+    const accepted =
+      selectedPackages.aur.length > 0
+        ? await reviewAurBuildPlan(selectedPackages.aur)
+        : [];
+
     // Get password if needed (for official or AUR)
     if (
       selectedPackages.official.length > 0 ||
@@ -469,6 +485,7 @@ async function handleUpdateSelected() {
       password = await showPasswordPrompt();
       console.log("[Update] Password received, length:", password.length);
     }
+    await approveAurReviews(accepted);
 
     // Force remove ALL modals first
     console.log("[Update] Force removing all modals...");
@@ -556,103 +573,14 @@ async function handleUpdateSelected() {
     }
     addUpdateTerminalLine("", "normal");
 
-    let completedCount = 0;
-
-    // Update official packages
-    if (selectedPackages.official.length > 0) {
-      addUpdateTerminalLine(
-        `:: Updating ${selectedPackages.official.length} official package(s)...`,
-        "normal",
-      );
-      addUpdateTerminalLine("", "normal");
-
-      for (const pkgName of selectedPackages.official) {
-        completedCount++;
-        addUpdateTerminalLine(
-          `[${completedCount}/${totalPackages}] Updating ${pkgName} from official repositories...`,
-          "normal",
-        );
-
-        try {
-          await invoke("install_package", {
-            packageName: pkgName,
-            source: "official",
-            password: password,
-          });
-          addUpdateTerminalLine(`✓ Successfully updated ${pkgName}`, "normal");
-        } catch (err) {
-          addUpdateTerminalLine(
-            `✗ Failed to update ${pkgName}: ${err}`,
-            "error",
-          );
-        }
-        addUpdateTerminalLine("", "normal");
-      }
-    }
-
-    // Update AUR packages
-    if (selectedPackages.aur.length > 0) {
-      addUpdateTerminalLine(
-        `:: Updating ${selectedPackages.aur.length} AUR package(s)...`,
-        "normal",
-      );
-      addUpdateTerminalLine("", "normal");
-
-      for (const pkgName of selectedPackages.aur) {
-        completedCount++;
-        addUpdateTerminalLine(
-          `[${completedCount}/${totalPackages}] Updating ${pkgName} from AUR...`,
-          "normal",
-        );
-
-        try {
-          await invoke("install_package", {
-            packageName: pkgName,
-            source: "aur",
-            password: password,
-          });
-          addUpdateTerminalLine(`✓ Successfully updated ${pkgName}`, "normal");
-        } catch (err) {
-          addUpdateTerminalLine(
-            `✗ Failed to update ${pkgName}: ${err}`,
-            "error",
-          );
-        }
-        addUpdateTerminalLine("", "normal");
-      }
-    }
-
-    // Update Flatpak packages
-    if (selectedPackages.flatpak.length > 0) {
-      addUpdateTerminalLine(
-        `:: Updating ${selectedPackages.flatpak.length} Flatpak package(s)...`,
-        "normal",
-      );
-      addUpdateTerminalLine("", "normal");
-
-      for (const pkgName of selectedPackages.flatpak) {
-        completedCount++;
-        addUpdateTerminalLine(
-          `[${completedCount}/${totalPackages}] Updating ${pkgName} from Flatpak...`,
-          "normal",
-        );
-
-        try {
-          await invoke("install_package", {
-            packageName: pkgName,
-            source: "flatpak",
-            password: "",
-          });
-          addUpdateTerminalLine(`✓ Successfully updated ${pkgName}`, "normal");
-        } catch (err) {
-          addUpdateTerminalLine(
-            `✗ Failed to update ${pkgName}: ${err}`,
-            "error",
-          );
-        }
-        addUpdateTerminalLine("", "normal");
-      }
-    }
+    // Official packages are refused by the backend if updating them alone
+    // would cause a partial upgrade; output streams through update-progress
+    await invoke("update_selected", {
+      official: selectedPackages.official,
+      aur: selectedPackages.aur,
+      flatpak: selectedPackages.flatpak,
+      password: password || "",
+    });
 
     addUpdateTerminalLine("=".repeat(60), "normal");
     addUpdateTerminalLine(
@@ -676,7 +604,9 @@ async function handleUpdateSelected() {
       }, 500);
     }, 3000);
   } catch (error) {
-    if (error.message === "Password prompt cancelled") {
+    if (error.message === "Review rejected") {
+      console.log("Update cancelled at AUR review");
+    } else if (error.message === "Password prompt cancelled") {
       console.log("Update cancelled by user");
       addUpdateTerminalLine("", "normal");
       addUpdateTerminalLine("=".repeat(60), "error");
@@ -9,7 +9,11 @@ import {
   setCurrentView,
   setCurrentApp,
 } from "../main.js";
-import { showPasswordPrompt } from "./modal.js";
+import {
+  showPasswordPrompt,
+  showAurReviewPrompt,
+  showAurBuildPlan,
+} from "./modal.js";
 
 // Fetch app detail data without showing loading UI
 export async function fetchAppDetailData(app) {
@@ -263,15 +267,54 @@ export function showAppDetail(app) {
   });
 }
 
+// Walk the AUR build plan of the given packages and show every build file that has not
+// been approved at its current commit. Returns the accepted reviews; they are approved
+// with approveAurReviews only once the job is about to start
+export async function reviewAurBuildPlan(packages) {
+  const plan = await invoke("get_aur_build_plan", { packages });
+  if (plan.steps.length > 1 || plan.repo_dependencies.length > 0) {
+    await showAurBuildPlan(plan);
+  }
+
+  const accepted = [];
+  for (const step of plan.steps) {
+    const review = await invoke("review_aur_package", {
+      packageName: step.packages[0],
+    });
+    if (!review.approved) {
+      await showAurReviewPrompt(review);
+      accepted.push(review);
+    }
+  }
+  return accepted;
+}
+
+// Record approvals for reviews accepted in reviewAurBuildPlan
+export async function approveAurReviews(reviews) {
+  for (const review of reviews) {
+    await invoke("approve_aur_review", {
+      pkgbase: review.pkgbase,
+      commit: review.commit,
+    });
+  }
+}
+
 // Install package
 export async function installPackage(name, source) {
   try {
+    // AUR build files have to be reviewed and approved before anything is built,
+    // including every AUR dependency in the build plan
+    const accepted = source === "aur" ? await reviewAurBuildPlan([name]) : [];
+
     // Prompt for password first (only for official and AUR, not flatpak)
     let password = "";
     if (source !== "flatpak") {
       password = await showPasswordPrompt();
     }
 
+    // Approve only now, so cancelling the password prompt leaves nothing approved
+    await approveAurReviews(accepted);
+
     const { showModal } = await import("./modal.js");
     showModal(name);
 
@@ -281,7 +324,9 @@ export async function installPackage(name, source) {
       password,
     });
   } catch (error) {
-    if (error.message === "Password prompt cancelled") {
+    if (error.message === "Review rejected") {
+      return;
+    } else if (error.message === "Password prompt cancelled") {
       const { addTerminalLine } = await import("./modal.js");
       addTerminalLine("Installation cancelled by user", "error");
     } else {
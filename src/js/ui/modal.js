@@ -1,5 +1,5 @@
 import { listen } from "@tauri-apps/api/event";
-import { currentView, currentApp } from "../main.js";
+import { currentView, currentApp, escapeHtml } from "../main.js";
 import { fetchAndShowAppDetail } from "./detail.js";
 
 // Show password prompt
@@ -101,6 +101,130 @@ export function showPasswordPrompt() {
   });
 }
 
+// Show the PKGBUILD review of an AUR package and wait for approval
+// Resolves when the user approves, rejects with "Review rejected" otherwise
+export function showAurReviewPrompt(review) {
+  return new Promise((resolve, reject) => {
+    const existingModal = document.getElementById("aur-review-modal");
+    if (existingModal) existingModal.remove();
+
+    const since = review.last_built_commit
+      ? `Changes since the last build (${escapeHtml(review.last_built_version || review.last_built_commit.slice(0, 10))})`
+      : "First build: review the complete files";
+    const files = review.files
+      .map(
+        (file) => `
+          <details ${file.status === "unchanged" ? "" : "open"} style="margin-bottom: 10px;">
+            <summary style="cursor: pointer; color: #ddd;">${escapeHtml(file.name)} <span style="color: #888;">(${escapeHtml(file.status)})</span></summary>
+            <pre style="max-height: 300px; overflow: auto; background: #1a1a1a; padding: 10px; border-radius: 4px; font-size: 12px; color: #ccc;">${escapeHtml(file.diff || file.content)}</pre>
+          </details>`,
+      )
+      .join("");
+
+    const riskColors = { low: "#4caf50", medium: "#ff9800", high: "#f44336" };
+    const findings = review.risk.findings
+      .map(
+        (finding) => `
+          <li style="color: #ccc; margin-bottom: 4px;">
+            <strong>${escapeHtml(finding.severity)}</strong>: ${escapeHtml(finding.message)}
+            ${finding.file ? `<span style="color: #888;">(${escapeHtml(finding.file)}${finding.line ? `:${finding.line}` : ""})</span>` : ""}
+          </li>`,
+      )
+      .join("");
+    const risk = `
+      <p style="margin-bottom: 10px; color: ${riskColors[review.risk.level] || "#ccc"};">Risk: ${escapeHtml(review.risk.level)}</p>
+      ${findings ? `<ul style="margin: 0 0 15px 20px; font-size: 13px;">${findings}</ul>` : ""}`;
+
+    const modal = document.createElement("div");
+    modal.className = "modal active";
+    modal.id = "aur-review-modal";
+    modal.style.zIndex = "10000";
+    modal.innerHTML = `
+      <div class="modal-content" style="max-width: 800px;">
+        <div class="modal-header">
+          <h2 class="modal-title">Review ${escapeHtml(review.pkgbase)} ${escapeHtml(review.version)}</h2>
+        </div>
+        <div class="modal-body">
+          ${risk}
+          <p style="margin-bottom: 15px; color: #ccc;">${since}</p>
+          ${files}
+          <p style="margin-top: 10px; font-size: 12px; color: #888;">These files run on your system while building and installing. Only approve them if you trust what they do.</p>
+        </div>
+        <div class="modal-footer" style="display: flex; gap: 10px; justify-content: flex-end; margin-top: 20px;">
+          <button type="button" class="reject-review-btn" style="padding: 8px 20px; background: #444; border: none; border-radius: 4px; color: white; cursor: pointer;">Cancel</button>
+          <button type="button" class="approve-review-btn" style="padding: 8px 20px; background: #1793d1; border: none; border-radius: 4px; color: white; cursor: pointer;">Approve and Build</button>
+        </div>
+      </div>
+    `;
+
+    document.body.appendChild(modal);
+
+    modal.querySelector(".approve-review-btn").addEventListener("click", () => {
+      modal.remove();
+      resolve();
+    });
+    modal.querySelector(".reject-review-btn").addEventListener("click", () => {
+      modal.remove();
+      reject(new Error("Review rejected"));
+    });
+  });
+}
+
+// Show the ordered AUR build plan before anything is reviewed or built
+export function showAurBuildPlan(plan) {
+  return new Promise((resolve, reject) => {
+    const existingModal = document.getElementById("aur-plan-modal");
+    if (existingModal) existingModal.remove();
+
+    const repoDeps = plan.repo_dependencies.length
+      ? `<p style="margin-bottom: 15px; color: #ccc;">From the repositories: ${plan.repo_dependencies.map(escapeHtml).join(", ")}</p>`
+      : "";
+    const steps = plan.steps
+      .map(
+        (step, index) => `
+          <li style="color: #ccc; margin-bottom: 6px;">
+            <strong>${index + 1}. ${escapeHtml(step.pkgbase)} ${escapeHtml(step.version)}</strong>
+            ${step.packages.length > 1 || step.packages[0] !== step.pkgbase ? `<span style="color: #888;">(${step.packages.map(escapeHtml).join(", ")})</span>` : ""}
+            ${step.required_by.length ? `<div style="font-size: 12px; color: #888;">Required by ${step.required_by.map(escapeHtml).join(", ")}</div>` : ""}
+          </li>`,
+      )
+      .join("");
+
+    const modal = document.createElement("div");
+    modal.className = "modal active";
+    modal.id = "aur-plan-modal";
+    modal.style.zIndex = "10000";
+    modal.innerHTML = `
+      <div class="modal-content" style="max-width: 600px;">
+        <div class="modal-header">
+          <h2 class="modal-title">Build Plan</h2>
+        </div>
+        <div class="modal-body">
+          ${repoDeps}
+          <p style="margin-bottom: 10px; color: #ccc;">AUR packages are built in this order:</p>
+          <ul style="list-style: none; margin: 0; padding: 0;">${steps}</ul>
+          <p style="margin-top: 10px; font-size: 12px; color: #888;">Each package base is reviewed before the build starts.</p>
+        </div>
+        <div class="modal-footer" style="display: flex; gap: 10px; justify-content: flex-end; margin-top: 20px;">
+          <button type="button" class="reject-plan-btn" style="padding: 8px 20px; background: #444; border: none; border-radius: 4px; color: white; cursor: pointer;">Cancel</button>
+          <button type="button" class="accept-plan-btn" style="padding: 8px 20px; background: #1793d1; border: none; border-radius: 4px; color: white; cursor: pointer;">Continue</button>
+        </div>
+      </div>
+    `;
+
+    document.body.appendChild(modal);
+
+    modal.querySelector(".accept-plan-btn").addEventListener("click", () => {
+      modal.remove();
+      resolve();
+    });
+    modal.querySelector(".reject-plan-btn").addEventListener("click", () => {
+      modal.remove();
+      reject(new Error("Review rejected"));
+    });
+  });
+}
+
 // Show modal
 export function showModal(packageName) {
   const modal = document.getElementById("install-modal");